@@ -0,0 +1,60 @@
+//! Compares the cost of serializing a large map with hooks that read the
+//! path string of every map entry versus hooks that don't.
+//!
+//! Run with `cargo bench --bench map_paths`.
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use serde_hooks::{ser, Path};
+
+const ITERATIONS: usize = 10;
+
+struct NoopHooks;
+
+impl ser::Hooks for NoopHooks {
+    fn on_value<S: serde::Serializer>(&self, _path: &Path, _value: &mut ser::ValueScope<S>) {}
+}
+
+#[derive(Default)]
+struct PathReadingHooks {
+    total_len: Cell<usize>,
+}
+
+impl ser::Hooks for PathReadingHooks {
+    fn on_value<S: serde::Serializer>(&self, path: &Path, _value: &mut ser::ValueScope<S>) {
+        self.total_len
+            .set(self.total_len.get() + path.borrow_str().len());
+    }
+}
+
+fn measure(name: &str, mut f: impl FnMut() -> String) -> Duration {
+    let expected = f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        assert_eq!(f(), expected);
+    }
+    let elapsed = start.elapsed() / ITERATIONS as u32;
+    println!("{name:>20}: {elapsed:?} per iteration");
+    elapsed
+}
+
+fn main() {
+    let data: BTreeMap<String, u32> = (0..1_000_000).map(|i| (format!("key{i}"), i)).collect();
+
+    let baseline = measure("no hooks", || serde_json::to_string(&data).unwrap());
+    let noop = measure("no-op on_value", || {
+        serde_json::to_string(&ser::hook(&data, &NoopHooks)).unwrap()
+    });
+    let hooks = PathReadingHooks::default();
+    let reading = measure("path reading", || {
+        serde_json::to_string(&ser::hook(&data, &hooks)).unwrap()
+    });
+
+    println!(
+        "overhead: no-op {:?}, path reading {:?}",
+        noop.saturating_sub(baseline),
+        reading.saturating_sub(baseline)
+    );
+}
@@ -0,0 +1,100 @@
+//! Compares the cost of matching paths against many rules with [`ser::CompiledMatchers`]
+//! versus naive comparison of path strings in a hook.
+//!
+//! Run with `cargo bench --bench matchers`.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_hooks::{ser, Path};
+
+const RULES: usize = 200;
+const ITERATIONS: usize = 20;
+
+#[derive(Serialize)]
+struct Record {
+    id: u64,
+    name: String,
+    attributes: BTreeMap<String, u32>,
+    values: Vec<f64>,
+}
+
+fn make_data() -> Vec<Record> {
+    (0..500)
+        .map(|i| Record {
+            id: i,
+            name: format!("record {i}"),
+            attributes: (0..10).map(|a| (format!("attr{a}"), a)).collect(),
+            values: (0..10).map(|v| v as f64).collect(),
+        })
+        .collect()
+}
+
+fn make_rules() -> Vec<String> {
+    // Rules that mostly don't match, plus a couple that do.
+    let mut rules: Vec<String> = (0..RULES - 2)
+        .map(|i| format!("[{i}].attributes[\"missing{i}\"]"))
+        .collect();
+    rules.push("[7].name".into());
+    rules.push("[42].values[3]".into());
+    rules
+}
+
+struct NaiveHooks {
+    rules: Vec<String>,
+}
+
+impl ser::Hooks for NaiveHooks {
+    fn on_value<S: serde::Serializer>(&self, path: &Path, value: &mut ser::ValueScope<S>) {
+        let path = path.borrow_str();
+        if self.rules.contains(&path) {
+            value.replace(&0);
+        }
+    }
+}
+
+struct MatchedHooks;
+
+impl ser::Hooks for MatchedHooks {
+    fn on_value<S: serde::Serializer>(&self, _path: &Path, value: &mut ser::ValueScope<S>) {
+        value.replace(&0);
+    }
+}
+
+fn measure(name: &str, mut f: impl FnMut() -> String) -> Duration {
+    let expected = f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        assert_eq!(f(), expected);
+    }
+    let elapsed = start.elapsed() / ITERATIONS as u32;
+    println!("{name:>20}: {elapsed:?} per iteration");
+    elapsed
+}
+
+fn main() {
+    let data = make_data();
+    let rules = make_rules();
+
+    let naive_hooks = NaiveHooks {
+        rules: rules.clone(),
+    };
+    let naive = measure("naive", || {
+        serde_json::to_string(&ser::hook(&data, &naive_hooks)).unwrap()
+    });
+
+    let matchers = ser::CompiledMatchers::builder().patterns(&rules).build();
+    let compiled = measure("compiled matchers", || {
+        let options = ser::HookOptions::new().only_matching(matchers.clone());
+        serde_json::to_string(&ser::hook_with_options(&data, &MatchedHooks, options)).unwrap()
+    });
+
+    let baseline = measure("no hooks", || serde_json::to_string(&data).unwrap());
+
+    println!(
+        "matching overhead: naive {:?}, compiled {:?}",
+        naive.saturating_sub(baseline),
+        compiled.saturating_sub(baseline)
+    );
+}
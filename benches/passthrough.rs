@@ -0,0 +1,43 @@
+//! Compares the cost of serializing with [`ser::NoHooks`] and with empty hooks
+//! versus plain serde.
+//!
+//! Run with `cargo bench --bench passthrough`.
+
+use std::time::{Duration, Instant};
+
+use serde_hooks::ser;
+
+const ITERATIONS: usize = 20;
+
+struct EmptyHooks;
+
+impl ser::Hooks for EmptyHooks {}
+
+fn measure(name: &str, mut f: impl FnMut() -> String) -> Duration {
+    let expected = f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        assert_eq!(f(), expected);
+    }
+    let elapsed = start.elapsed() / ITERATIONS as u32;
+    println!("{name:>20}: {elapsed:?} per iteration");
+    elapsed
+}
+
+fn main() {
+    let data: Vec<u64> = (0..1_000_000).collect();
+
+    let baseline = measure("no hooks", || serde_json::to_string(&data).unwrap());
+    let no_hooks = measure("ser::NoHooks", || {
+        serde_json::to_string(&ser::hook(&data, &ser::NoHooks)).unwrap()
+    });
+    let empty = measure("empty hooks", || {
+        serde_json::to_string(&ser::hook(&data, &EmptyHooks)).unwrap()
+    });
+
+    println!(
+        "overhead: ser::NoHooks {:?}, empty hooks {:?}",
+        no_hooks.saturating_sub(baseline),
+        empty.saturating_sub(baseline)
+    );
+}
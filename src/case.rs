@@ -38,6 +38,29 @@ pub enum Case {
     Kebab,
     /// `SCREAMING-KEBAB-CASE`
     ScreamingKebab,
+    /// `Train-Case`
+    Train,
+    /// Custom conversion with a function.
+    ///
+    /// Allows expressing naming rules that the other variants can't, e.g. stripping
+    /// a prefix:
+    /// ```
+    /// # use serde_hooks::{ser, Case, Path};
+    /// fn strip_prefix(name: &str) -> String {
+    ///     name.trim_start_matches("db_").to_string()
+    /// }
+    ///
+    /// struct Hooks;
+    /// impl ser::Hooks for Hooks {
+    ///     fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+    ///         st.rename_all_fields_case(Case::Custom(strip_prefix));
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// `Case` is `Copy`, so only plain function pointers (and non-capturing closures)
+    /// can be used here.
+    Custom(fn(&str) -> String),
 }
 
 impl From<&str> for Case {
@@ -53,6 +76,8 @@ impl From<&str> for Case {
     /// `"kebab-case"`,
     /// `"SCREAMING-KEBAB-CASE"`.
     ///
+    /// In addition, `"Train-Case"` is accepted, which is not supported by serde.
+    ///
     /// Panics on unknown identifiers.
     fn from(value: &str) -> Self {
         match value {
@@ -64,13 +89,51 @@ impl From<&str> for Case {
             "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
             "kebab-case" => Self::Kebab,
             "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            "Train-Case" => Self::Train,
             _ => panic!("unsupported case convention '{value}'"),
         }
     }
 }
 
 impl Case {
-    pub(crate) fn string_to_case(key: &str, to_case: Case) -> String {
+    /// Converts a string to the given case convention.
+    ///
+    /// This is the exact conversion used by case-renaming actions, e.g.
+    /// [`StructScope::rename_all_fields_case`](crate::ser::StructScope::rename_all_fields_case).
+    /// It can be used to precompute the renamed keys, so that they match the automatic renaming
+    /// byte-for-byte:
+    /// ```
+    /// # use serde::Serialize;
+    /// # use serde_hooks::{ser, Case, Path};
+    /// #[derive(Serialize)]
+    /// struct Payload {
+    ///     some_field: u32,
+    /// }
+    ///
+    /// struct Hooks;
+    /// impl ser::Hooks for Hooks {
+    ///     fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+    ///         st.rename_field(
+    ///             "some_field",
+    ///             Case::string_to_case("some_field", Case::Camel),
+    ///         );
+    ///     }
+    /// }
+    ///
+    /// struct CaseHooks;
+    /// impl ser::Hooks for CaseHooks {
+    ///     fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+    ///         st.rename_all_fields_case(Case::Camel);
+    ///     }
+    /// }
+    ///
+    /// let payload = Payload { some_field: 1 };
+    /// assert_eq!(
+    ///     serde_json::to_string(&ser::hook(&payload, &Hooks)).unwrap(),
+    ///     serde_json::to_string(&ser::hook(&payload, &CaseHooks)).unwrap(),
+    /// );
+    /// ```
+    pub fn string_to_case(key: &str, to_case: Case) -> String {
         use convert_case::Casing;
         match to_case {
             Case::Lower => key.to_case(convert_case::Case::Lower),
@@ -81,13 +144,27 @@ impl Case {
             Case::ScreamingSnake => key.to_case(convert_case::Case::ScreamingSnake),
             Case::Kebab => key.to_case(convert_case::Case::Kebab),
             Case::ScreamingKebab => key.to_case(convert_case::Case::UpperKebab),
+            Case::Train => key.to_case(convert_case::Case::Train),
+            Case::Custom(f) => f(key),
         }
     }
 
-    #[allow(clippy::ptr_arg)]
+    /// Converts a string to the given case convention, same as
+    /// [`string_to_case`](Self::string_to_case).
+    ///
     /// For borrowed static strings that did not change when converted the static str is reused
-    /// in the returned cow.
-    pub(crate) fn cow_to_case(key: &Cow<'static, str>, to_case: Case) -> Cow<'static, str> {
+    /// in the returned cow, which avoids an allocation:
+    /// ```
+    /// # use std::borrow::Cow;
+    /// # use serde_hooks::Case;
+    /// let converted = Case::cow_to_case(&Cow::Borrowed("some_field"), Case::Snake);
+    /// assert!(matches!(converted, Cow::Borrowed("some_field")));
+    ///
+    /// let converted = Case::cow_to_case(&Cow::Borrowed("some_field"), Case::Pascal);
+    /// assert!(matches!(converted, Cow::Owned(s) if s == "SomeField"));
+    /// ```
+    #[allow(clippy::ptr_arg)]
+    pub fn cow_to_case(key: &Cow<'static, str>, to_case: Case) -> Cow<'static, str> {
         use convert_case::Casing;
         let converted = match to_case {
             Case::Lower => key.to_case(convert_case::Case::Lower),
@@ -98,6 +175,8 @@ impl Case {
             Case::ScreamingSnake => key.to_case(convert_case::Case::ScreamingSnake),
             Case::Kebab => key.to_case(convert_case::Case::Kebab),
             Case::ScreamingKebab => key.to_case(convert_case::Case::UpperKebab),
+            Case::Train => key.to_case(convert_case::Case::Train),
+            Case::Custom(f) => f(key),
         };
 
         match key {
@@ -112,3 +191,19 @@ impl Case {
         }
     }
 }
+
+#[test]
+fn test_train_case() {
+    assert_eq!(
+        Case::string_to_case("some_field", "Train-Case".into()),
+        "Some-Field"
+    );
+    assert_eq!(
+        Case::string_to_case("HTTPRequest", Case::Train),
+        "Http-Request"
+    );
+    assert!(matches!(
+        Case::cow_to_case(&Cow::Borrowed("Some-Field"), Case::Train),
+        Cow::Borrowed("Some-Field")
+    ));
+}
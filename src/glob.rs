@@ -0,0 +1,291 @@
+use std::fmt::Write;
+
+use smallvec::SmallVec;
+
+use crate::{PathMapKey, PathSegment};
+
+/// A single segment of a compiled glob pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GlobSegment {
+    /// Struct field with the given name.
+    Field(String),
+    /// Sequence element or map entry, written as `[...]`.
+    ///
+    /// Matches sequence elements by their index and map entries by their key
+    /// as it is rendered in the path string.
+    Element { text: String, index: Option<usize> },
+    /// `*`, any single segment.
+    Any,
+    /// `[*]`, any single sequence element or map entry.
+    AnyElement,
+    /// `**`, any number of segments, including none.
+    AnyDeep,
+}
+
+impl GlobSegment {
+    fn matches(&self, segment: &PathSegment) -> bool {
+        match (self, segment) {
+            (GlobSegment::Any, _) => true,
+            (GlobSegment::AnyDeep, _) => true,
+            (GlobSegment::Field(name), PathSegment::StructField(field)) => name == field,
+            (GlobSegment::AnyElement, PathSegment::SeqElement(_) | PathSegment::MapEntry(_)) => {
+                true
+            }
+            (GlobSegment::Element { index, .. }, PathSegment::SeqElement(i)) => *index == Some(*i),
+            (GlobSegment::Element { text, .. }, PathSegment::MapEntry(key)) => {
+                map_key_renders_as(key, text)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Compares the rendered map key with `text` without allocating.
+fn map_key_renders_as(key: &PathMapKey, text: &str) -> bool {
+    struct EqWriter<'t> {
+        rest: &'t str,
+    }
+
+    impl Write for EqWriter<'_> {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            match self.rest.strip_prefix(s) {
+                Some(rest) => {
+                    self.rest = rest;
+                    Ok(())
+                }
+                None => Err(std::fmt::Error),
+            }
+        }
+    }
+
+    let mut writer = EqWriter { rest: text };
+    write!(&mut writer, "{key}").is_ok() && writer.rest.is_empty()
+}
+
+/// A glob pattern compiled into a list of segments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Glob {
+    segments: Vec<GlobSegment>,
+}
+
+impl Glob {
+    /// Parses a glob pattern.
+    ///
+    /// The pattern syntax follows the path string representation: struct fields are
+    /// separated with dots, sequence elements and map entries are written in square brackets.
+    /// `*` matches any single segment, `[*]` matches any single sequence element or map entry,
+    /// `**` matches any number of segments.
+    pub(crate) fn new(pattern: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut chars = pattern.char_indices();
+        let mut field_start: Option<usize> = None;
+
+        let push_field = |segments: &mut Vec<GlobSegment>, field: &str| {
+            segments.push(match field {
+                "*" => GlobSegment::Any,
+                "**" => GlobSegment::AnyDeep,
+                _ => GlobSegment::Field(field.to_string()),
+            })
+        };
+
+        while let Some((pos, c)) = chars.next() {
+            match c {
+                '.' | '[' => {
+                    if let Some(start) = field_start.take() {
+                        push_field(&mut segments, &pattern[start..pos]);
+                    }
+
+                    if c == '[' {
+                        let text_start = pos + 1;
+                        let mut text_end = pattern.len();
+                        for (pos, c) in chars.by_ref() {
+                            if c == ']' {
+                                text_end = pos;
+                                break;
+                            }
+                        }
+                        let text = &pattern[text_start..text_end];
+                        segments.push(if text == "*" {
+                            GlobSegment::AnyElement
+                        } else {
+                            GlobSegment::Element {
+                                text: text.to_string(),
+                                index: text.parse().ok(),
+                            }
+                        });
+                    }
+                }
+                _ => {
+                    field_start.get_or_insert(pos);
+                }
+            }
+        }
+
+        if let Some(start) = field_start {
+            push_field(&mut segments, &pattern[start..]);
+        }
+
+        Self { segments }
+    }
+
+    /// Matches the glob against a whole path.
+    pub(crate) fn matches(&self, path: &[PathSegment]) -> bool {
+        let set = GlobSet::new(vec![self.clone()]);
+        let states = path.iter().fold(set.initial_states(), |states, segment| {
+            set.next_states(&states, segment)
+        });
+        set.is_match(&states)
+    }
+}
+
+/// Set of NFA states for a [`GlobSet`].
+pub(crate) type GlobStates = SmallVec<[u32; 8]>;
+
+/// A set of globs compiled into a single non-deterministic automaton.
+///
+/// Each state of the automaton is a position within one of the globs. The automaton
+/// is advanced one path segment at a time, so that matching a path pushed segment by
+/// segment costs a single step per segment, instead of re-matching the whole path.
+#[derive(Debug, Clone)]
+pub(crate) struct GlobSet {
+    globs: Vec<Glob>,
+    // (glob index, position within the glob) for each state
+    states: Vec<(u32, u32)>,
+    // index of the first state of each glob
+    first_states: Vec<u32>,
+}
+
+impl GlobSet {
+    pub(crate) fn new(globs: Vec<Glob>) -> Self {
+        let mut states = Vec::new();
+        let mut first_states = Vec::new();
+        for (glob_index, glob) in globs.iter().enumerate() {
+            first_states.push(states.len() as u32);
+            for position in 0..=glob.segments.len() {
+                states.push((glob_index as u32, position as u32));
+            }
+        }
+        Self {
+            globs,
+            states,
+            first_states,
+        }
+    }
+
+    /// Returns the states of the automaton at the root path.
+    pub(crate) fn initial_states(&self) -> GlobStates {
+        let mut states: GlobStates = self.first_states.iter().copied().collect();
+        self.close(&mut states);
+        states
+    }
+
+    /// Advances the automaton over a path segment.
+    pub(crate) fn next_states(&self, states: &GlobStates, segment: &PathSegment) -> GlobStates {
+        let mut next = GlobStates::new();
+        for &state in states {
+            let (glob_index, position) = self.states[state as usize];
+            let Some(glob_segment) = self.globs[glob_index as usize]
+                .segments
+                .get(position as usize)
+            else {
+                continue;
+            };
+
+            if let GlobSegment::AnyDeep = glob_segment {
+                push_unique(&mut next, state);
+            } else if glob_segment.matches(segment) {
+                push_unique(&mut next, state + 1);
+            }
+        }
+        self.close(&mut next);
+        next
+    }
+
+    /// Returns `true` if any of the globs matches in the given states.
+    pub(crate) fn is_match(&self, states: &GlobStates) -> bool {
+        self.matching_globs(states).next().is_some()
+    }
+
+    /// Returns indices of globs that match in the given states.
+    pub(crate) fn matching_globs<'s>(
+        &'s self,
+        states: &'s GlobStates,
+    ) -> impl Iterator<Item = usize> + 's {
+        states.iter().filter_map(|&state| {
+            let (glob_index, position) = self.states[state as usize];
+            (self.globs[glob_index as usize].segments.len() == position as usize)
+                .then_some(glob_index as usize)
+        })
+    }
+
+    /// Adds states reachable by skipping `**` segments, which can match no segments at all.
+    fn close(&self, states: &mut GlobStates) {
+        let mut i = 0;
+        while i < states.len() {
+            let state = states[i];
+            let (glob_index, position) = self.states[state as usize];
+            if let Some(GlobSegment::AnyDeep) = self.globs[glob_index as usize]
+                .segments
+                .get(position as usize)
+            {
+                push_unique(states, state + 1);
+            }
+            i += 1;
+        }
+    }
+}
+
+fn push_unique(states: &mut GlobStates, state: u32) {
+    if !states.contains(&state) {
+        states.push(state);
+    }
+}
+
+#[test]
+fn test_glob_parse() {
+    assert_eq!(
+        Glob::new("users[*].password.**[0].*[\"key\"]").segments,
+        vec![
+            GlobSegment::Field("users".into()),
+            GlobSegment::AnyElement,
+            GlobSegment::Field("password".into()),
+            GlobSegment::AnyDeep,
+            GlobSegment::Element {
+                text: "0".into(),
+                index: Some(0)
+            },
+            GlobSegment::Any,
+            GlobSegment::Element {
+                text: "\"key\"".into(),
+                index: None
+            },
+        ]
+    );
+    assert_eq!(Glob::new("").segments, vec![]);
+}
+
+#[test]
+fn test_glob_matches() {
+    let path = [
+        PathSegment::StructField("users"),
+        PathSegment::SeqElement(3),
+        PathSegment::MapEntry(PathMapKey::new(0, "secret".into())),
+        PathSegment::StructField("password"),
+    ];
+
+    assert!(Glob::new("users[3][\"secret\"].password").matches(&path));
+    assert!(Glob::new("users.*.*.password").matches(&path));
+    assert!(Glob::new("users[*][*].password").matches(&path));
+    assert!(Glob::new("**.password").matches(&path));
+    assert!(Glob::new("**").matches(&path));
+    assert!(Glob::new("users.**.password").matches(&path));
+    assert!(Glob::new("users.**.**.password").matches(&path));
+    assert!(Glob::new("**[\"secret\"].**").matches(&path));
+
+    assert!(!Glob::new("users.*.password").matches(&path));
+    assert!(!Glob::new("users[2].**").matches(&path));
+    assert!(!Glob::new("*[*]").matches(&path));
+    assert!(!Glob::new("**.secret").matches(&path));
+    assert!(!Glob::new("").matches(&path));
+    assert!(Glob::new("").matches(&[]));
+}
@@ -6,9 +6,10 @@
 pub mod ser;
 
 mod case;
+mod glob;
 mod path;
 mod value;
 
 pub use case::Case;
-pub use path::{Path, PathMapKey, PathSegment};
+pub use path::{KeyRenderStyle, Path, PathMapKey, PathSegment, PathSegmentPattern};
 pub use value::{StaticValue, Value};
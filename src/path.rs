@@ -5,7 +5,7 @@ use std::{
 
 use smallvec::SmallVec;
 
-use crate::{StaticValue, Value};
+use crate::{glob::Glob, StaticValue, Value};
 
 /// A path within the structure of serialized data.
 ///
@@ -39,6 +39,33 @@ impl Path {
         self.segments.push(segment);
     }
 
+    /// Returns the number of leading segments whose string representation is `prefix`.
+    pub(crate) fn prefix_depth(&self, prefix: &str) -> Option<usize> {
+        if prefix.is_empty() {
+            return Some(0);
+        }
+        let path_str = self.borrow_str();
+        if !path_str.starts_with(prefix) {
+            return None;
+        }
+        let str_cache = self.str_cache.borrow();
+        let mut len = 0;
+        for (depth, written_length) in str_cache.written_lengths.iter().enumerate() {
+            len += *written_length as usize;
+            if len >= prefix.len() {
+                return (len == prefix.len()).then_some(depth + 1);
+            }
+        }
+        None
+    }
+
+    /// Returns a new path made of the segments after the first `depth` ones.
+    pub(crate) fn relative_to(&self, depth: usize) -> Path {
+        let mut path = Path::new();
+        path.segments.extend(self.segments[depth..].iter().cloned());
+        path
+    }
+
     pub(crate) fn pop_segment(&mut self) -> PathSegment {
         let res = self.segments.pop().expect("unbalanced pop_segment");
 
@@ -66,6 +93,87 @@ impl Path {
         &self.segments
     }
 
+    /// Returns the number of segments in the path, i.e. how deep the path is nested.
+    ///
+    /// The root path has depth 0, elements of the top-level container have depth 1, etc.
+    pub fn depth(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns segments of the parent path, i.e. all segments but the last one.
+    ///
+    /// Returns `None` for the root path, which has no parent.
+    pub fn parent_segments(&self) -> Option<&[PathSegment]> {
+        self.segments
+            .split_last()
+            .map(|(_, parent_segments)| parent_segments)
+    }
+
+    /// Returns the last segment of the path.
+    ///
+    /// Returns `None` for the root path.
+    pub fn last(&self) -> Option<&PathSegment> {
+        self.segments.last()
+    }
+
+    /// Returns the segment at the given index, starting from the top-level container.
+    ///
+    /// Returns `None` if the path is not that deep.
+    pub fn nth(&self, i: usize) -> Option<&PathSegment> {
+        self.segments.get(i)
+    }
+
+    /// Returns `true` if the last segments of the path match the given patterns.
+    ///
+    /// Unlike comparing the string representation, this matches segments structurally,
+    /// so field names containing dots or brackets are not confused with nested segments.
+    ///
+    /// ```
+    /// # use serde_hooks::{Path, PathSegmentPattern};
+    /// # fn check(path: &Path) {
+    /// // Any element of the `user` field.
+    /// if path.ends_with_segments(&[PathSegmentPattern::Field("user"), PathSegmentPattern::Any]) {
+    ///     // ...
+    /// }
+    /// # }
+    /// ```
+    pub fn ends_with_segments(&self, segments: &[PathSegmentPattern]) -> bool {
+        self.segments.len() >= segments.len()
+            && self.segments[self.segments.len() - segments.len()..]
+                .iter()
+                .zip(segments)
+                .all(|(segment, pattern)| pattern.matches(segment))
+    }
+
+    /// Returns the field name if the last segment of the path is a struct field.
+    pub fn field_name(&self) -> Option<&str> {
+        self.last().and_then(PathSegment::as_struct_field)
+    }
+
+    /// Returns `true` if the path matches a glob pattern.
+    ///
+    /// The pattern follows the string representation of the path (see [`borrow_str`](Self::borrow_str)):
+    /// struct fields are separated with dots, sequence elements and map entries are written
+    /// in square brackets. `*` matches any single segment, `[*]` matches any single sequence
+    /// element or map entry, `**` matches any number of segments, including none.
+    ///
+    /// ```
+    /// # use serde_hooks::Path;
+    /// # fn check(path: &Path) {
+    /// if path.matches("users[*].password") || path.matches("**.secret") {
+    ///     // ...
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// Matching is done segment by segment, so a field name containing a dot does not
+    /// match two separate segments. The pattern is parsed on every call, use
+    /// [`HookOptions::only_matching`](crate::ser::HookOptions::only_matching) to
+    /// match many paths against the same patterns.
+    pub fn matches(&self, pattern: &str) -> bool {
+        Glob::new(pattern).matches(&self.segments)
+    }
+
     /// Returns `true` if the path is pointing at the root element of the serialized
     /// data.
     pub fn is_root(&self) -> bool {
@@ -92,7 +200,8 @@ impl Path {
     /// `Path` maintains an internal cache for the string representation of the
     /// segments that is updated lazily when this method is called. It is
     /// optimized to reduce allocations and string formatting for individual
-    /// path segments.
+    /// path segments: each segment is rendered only once, and struct fields and
+    /// string map keys are copied into the cache as is, without formatting.
     ///
     /// This method returns a borrowed `Ref` for the cached string representation.
     /// The borrowed `Ref` must be dropped at the end of the hook otherwise `Path`
@@ -112,15 +221,26 @@ impl Path {
                 }
 
                 let len_before = str_cache.cache.len();
-                match &self.segments[str_cache.written_lengths.len()] {
-                    item @ PathSegment::StructField(_) => {
-                        if str_cache.cache.is_empty() {
-                            write!(&mut str_cache.cache, "{item}").expect("path concat failed");
-                        } else {
-                            write!(&mut str_cache.cache, ".{item}").expect("path concat failed");
+                let segment = &self.segments[str_cache.written_lengths.len()];
+                let cache = &mut str_cache.cache;
+                match segment {
+                    PathSegment::StructField(field_name) => {
+                        if !cache.is_empty() {
+                            cache.push('.');
                         }
+                        cache.push_str(field_name);
+                    }
+                    // Fast path for string map keys, which are the most common: the key
+                    // is copied from the captured key buffer without formatting.
+                    PathSegment::MapEntry(PathMapKey {
+                        value: Value::Str(key),
+                        ..
+                    }) => {
+                        cache.push_str("[\"");
+                        cache.push_str(key);
+                        cache.push_str("\"]");
                     }
-                    item => write!(&mut str_cache.cache, "{item}").expect("path concat failed"),
+                    item => write!(cache, "{item}").expect("path concat failed"),
                 }
                 let written_length = (str_cache.cache.len() - len_before) as u16;
                 str_cache.written_lengths.push(written_length);
@@ -129,6 +249,43 @@ impl Path {
 
         Ref::map(self.str_cache.borrow(), |c| &c.cache)
     }
+
+    /// Calls `f` with the string representation of the path.
+    ///
+    /// Same as [`borrow_str`](Self::borrow_str), but the borrow of the cached string is
+    /// released when `f` returns, so it can't be accidentally held for the rest of the hook.
+    /// Repeated calls at the same path reuse the cache without allocating.
+    pub fn with_str<R>(&self, f: impl FnOnce(&str) -> R) -> R {
+        f(&self.borrow_str())
+    }
+
+    /// Returns a [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901) representation of the path.
+    ///
+    /// Each segment becomes a reference token, e.g. `/users/0/name`. Struct fields and
+    /// string or char map keys are used as is, with `~` and `/` escaped, sequence indices and
+    /// primitive map keys are rendered as in the [string representation](Self::borrow_str),
+    /// and compound map keys are represented by the entry index. The root path is an empty string.
+    ///
+    /// Unlike [`borrow_str`](Self::borrow_str), this is not cached and allocates a new string
+    /// on every call.
+    pub fn to_json_pointer(&self) -> String {
+        let mut pointer = String::new();
+        for segment in &self.segments {
+            pointer.push('/');
+            match segment {
+                PathSegment::StructField(field_name) => {
+                    push_json_pointer_token(&mut pointer, field_name)
+                }
+                PathSegment::SeqElement(index) => {
+                    write!(&mut pointer, "{index}").expect("path concat failed")
+                }
+                PathSegment::MapEntry(key) => {
+                    pointer.push_str(&key.render(KeyRenderStyle::JsonPointerToken))
+                }
+            }
+        }
+        pointer
+    }
 }
 
 impl PartialEq<str> for Path {
@@ -177,7 +334,8 @@ pub struct PathMapKey {
     /// For non-trivial, compound values, only the metadata is captured.
     ///
     /// For map keys of type `Value::Bytes` the actual bytes are not captured to avoid
-    /// allocation on every map key.
+    /// allocation on every map key, unless enabled with
+    /// [`HookOptions::capture_byte_keys`](crate::ser::HookOptions::capture_byte_keys).
     pub value: StaticValue,
 }
 
@@ -185,6 +343,59 @@ impl PathMapKey {
     pub(crate) fn new(index: usize, value: StaticValue) -> Self {
         Self { index, value }
     }
+
+    /// Returns the captured value of the map key.
+    pub fn as_value(&self) -> &StaticValue {
+        &self.value
+    }
+
+    /// Renders the key in the given style.
+    ///
+    /// See [`KeyRenderStyle`] for the available styles. [`Display`] renders the key in the
+    /// [`KeyRenderStyle::Rust`] style.
+    pub fn render(&self, style: KeyRenderStyle) -> String {
+        match style {
+            KeyRenderStyle::Rust => self.to_string(),
+            KeyRenderStyle::JsonPointerToken => {
+                let mut token = String::new();
+                match &self.value {
+                    Value::Str(s) => push_json_pointer_token(&mut token, s),
+                    Value::Char(c) => {
+                        push_json_pointer_token(&mut token, c.encode_utf8(&mut [0; 4]))
+                    }
+                    _ => push_json_pointer_token(&mut token, &self.to_string()),
+                }
+                token
+            }
+            KeyRenderStyle::Debug => format!("{:?}", self.value),
+        }
+    }
+}
+
+/// Style of rendering a map key with [`PathMapKey::render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyRenderStyle {
+    /// Same as in the [string representation](Path::borrow_str) of a path: primitive keys
+    /// are rendered by value, with strings and chars quoted, and compound keys are rendered
+    /// as the entry index, e.g. `"key"`, `42` or `2`.
+    Rust,
+    /// A [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901) reference token, same as in
+    /// [`Path::to_json_pointer`]: strings and chars are not quoted, with `~` and `/` escaped,
+    /// other keys are rendered as for [`Rust`](Self::Rust), e.g. `key`, `42` or `2`.
+    JsonPointerToken,
+    /// Debug representation of the captured [`Value`], which includes the metadata of
+    /// compound keys, e.g. `Str("key")`, `I32(42)` or `Tuple(2)`.
+    Debug,
+}
+
+fn push_json_pointer_token(pointer: &mut String, token: &str) {
+    for c in token.chars() {
+        match c {
+            '~' => pointer.push_str("~0"),
+            '/' => pointer.push_str("~1"),
+            c => pointer.push(c),
+        }
+    }
 }
 
 impl Display for PathMapKey {
@@ -195,10 +406,12 @@ impl Display for PathMapKey {
             | Value::I16(_)
             | Value::I32(_)
             | Value::I64(_)
+            | Value::I128(_)
             | Value::U8(_)
             | Value::U16(_)
             | Value::U32(_)
             | Value::U64(_)
+            | Value::U128(_)
             | Value::F32(_)
             | Value::F64(_)
             | Value::Char(_)
@@ -223,6 +436,32 @@ pub enum PathSegment {
     SeqElement(usize),
 }
 
+impl PathSegment {
+    /// Returns the field name if the segment is a struct field.
+    pub fn as_struct_field(&self) -> Option<&str> {
+        match self {
+            PathSegment::StructField(field_name) => Some(field_name),
+            _ => None,
+        }
+    }
+
+    /// Returns the element index if the segment is a sequence element.
+    pub fn as_seq_index(&self) -> Option<usize> {
+        match self {
+            PathSegment::SeqElement(index) => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Returns the map key if the segment is a map entry.
+    pub fn as_map_key(&self) -> Option<&PathMapKey> {
+        match self {
+            PathSegment::MapEntry(key) => Some(key),
+            _ => None,
+        }
+    }
+}
+
 impl Display for PathSegment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -233,8 +472,241 @@ impl Display for PathSegment {
     }
 }
 
+/// A pattern matching a single [`PathSegment`], see [`Path::ends_with_segments`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSegmentPattern<'p> {
+    /// Matches a struct field with the given name.
+    Field(&'p str),
+    /// Matches a sequence element with the given index.
+    Index(usize),
+    /// Matches any segment.
+    Any,
+}
+
+impl PathSegmentPattern<'_> {
+    /// Returns `true` if the segment matches this pattern.
+    pub fn matches(&self, segment: &PathSegment) -> bool {
+        match self {
+            PathSegmentPattern::Field(name) => segment.as_struct_field() == Some(name),
+            PathSegmentPattern::Index(index) => segment.as_seq_index() == Some(*index),
+            PathSegmentPattern::Any => true,
+        }
+    }
+}
+
 impl From<PathMapKey> for PathSegment {
     fn from(map_key: PathMapKey) -> Self {
         PathSegment::MapEntry(map_key)
     }
 }
+
+#[test]
+fn test_depth_and_parent_segments() {
+    let mut path = Path::new();
+    assert_eq!(path.depth(), 0);
+    assert!(path.parent_segments().is_none());
+
+    path.push_segment(PathSegment::StructField("users"));
+    assert_eq!(path.depth(), 1);
+    assert!(path.parent_segments().unwrap().is_empty());
+
+    path.push_segment(PathSegment::SeqElement(3));
+    path.push_segment(PathSegment::StructField("name"));
+    assert_eq!(path.depth(), 3);
+    let parent = path.parent_segments().unwrap();
+    assert_eq!(parent.len(), 2);
+    assert!(matches!(parent[0], PathSegment::StructField("users")));
+    assert!(matches!(parent[1], PathSegment::SeqElement(3)));
+
+    path.pop_segment();
+    assert_eq!(path.depth(), 2);
+    assert!(matches!(
+        path.parent_segments().unwrap(),
+        [PathSegment::StructField("users")]
+    ));
+}
+
+#[test]
+fn test_segment_accessors() {
+    let mut path = Path::new();
+    assert!(path.last().is_none());
+    assert!(path.nth(0).is_none());
+    assert!(path.field_name().is_none());
+
+    path.push_segment(PathSegment::StructField("users"));
+    assert!(matches!(
+        path.last(),
+        Some(PathSegment::StructField("users"))
+    ));
+    assert_eq!(path.field_name(), Some("users"));
+
+    path.push_segment(PathSegment::SeqElement(3));
+    assert!(matches!(path.last(), Some(PathSegment::SeqElement(3))));
+    assert!(path.field_name().is_none());
+    assert!(matches!(
+        path.nth(0),
+        Some(PathSegment::StructField("users"))
+    ));
+    assert!(matches!(path.nth(1), Some(PathSegment::SeqElement(3))));
+    assert!(path.nth(2).is_none());
+
+    path.push_segment(PathSegment::MapEntry(PathMapKey::new(0, "key".into())));
+    assert!(matches!(path.last(), Some(PathSegment::MapEntry(_))));
+    assert!(path.field_name().is_none());
+}
+
+#[test]
+fn test_segment_extraction() {
+    let field = PathSegment::StructField("name");
+    assert_eq!(field.as_struct_field(), Some("name"));
+    assert_eq!(field.as_seq_index(), None);
+    assert!(field.as_map_key().is_none());
+
+    let element = PathSegment::SeqElement(5);
+    assert_eq!(element.as_struct_field(), None);
+    assert_eq!(element.as_seq_index(), Some(5));
+    assert!(element.as_map_key().is_none());
+
+    let entry = PathSegment::MapEntry(PathMapKey::new(2, "key".into()));
+    assert_eq!(entry.as_struct_field(), None);
+    assert_eq!(entry.as_seq_index(), None);
+    let key = entry.as_map_key().unwrap();
+    assert_eq!(key.index, 2);
+    assert_eq!(*key.as_value(), Value::Str("key".into()));
+}
+
+#[test]
+fn test_ends_with_segments() {
+    use PathSegmentPattern::{Any, Field, Index};
+
+    let mut path = Path::new();
+    assert!(path.ends_with_segments(&[]));
+    assert!(!path.ends_with_segments(&[Any]));
+
+    path.push_segment(PathSegment::StructField("users"));
+    path.push_segment(PathSegment::SeqElement(3));
+    path.push_segment(PathSegment::StructField("a.b"));
+
+    assert!(path.ends_with_segments(&[Field("a.b")]));
+    assert!(path.ends_with_segments(&[Index(3), Field("a.b")]));
+    assert!(path.ends_with_segments(&[Field("users"), Any, Field("a.b")]));
+    assert!(path.ends_with_segments(&[Any, Any]));
+    assert!(path.ends_with_segments(&[]));
+
+    assert!(!path.ends_with_segments(&[Field("a"), Field("b")]));
+    assert!(!path.ends_with_segments(&[Index(2), Field("a.b")]));
+    assert!(!path.ends_with_segments(&[Field("users"), Any]));
+    assert!(!path.ends_with_segments(&[Any, Any, Any, Any]));
+
+    path.push_segment(PathSegment::MapEntry(PathMapKey::new(0, "3".into())));
+    assert!(path.ends_with_segments(&[Field("a.b"), Any]));
+    assert!(!path.ends_with_segments(&[Index(0)]));
+    assert!(!path.ends_with_segments(&[Field("3")]));
+}
+
+#[test]
+fn test_matches() {
+    let mut path = Path::new();
+    assert!(path.matches(""));
+    assert!(path.matches("**"));
+    assert!(!path.matches("*"));
+
+    path.push_segment(PathSegment::StructField("users"));
+    path.push_segment(PathSegment::SeqElement(3));
+    path.push_segment(PathSegment::MapEntry(PathMapKey::new(0, "secret".into())));
+    path.push_segment(PathSegment::StructField("password"));
+
+    assert!(path.matches("users[3][\"secret\"].password"));
+    assert!(path.matches("users.*.*.password"));
+    assert!(path.matches("users[*][*].password"));
+    assert!(path.matches("**.password"));
+    assert!(path.matches("**[\"secret\"].**"));
+    assert!(path.matches("users.**"));
+
+    assert!(!path.matches("users[2].**"));
+    assert!(!path.matches("users.*.password"));
+    assert!(!path.matches("**.secret"));
+    assert!(!path.matches("users"));
+
+    // Field names with dots are a single segment
+    let mut path = Path::new();
+    path.push_segment(PathSegment::StructField("a.b"));
+    assert!(path.matches("*"));
+    assert!(!path.matches("a.b"));
+}
+
+#[test]
+fn test_to_json_pointer() {
+    let mut path = Path::new();
+    assert_eq!(path.to_json_pointer(), "");
+
+    path.push_segment(PathSegment::StructField("users"));
+    path.push_segment(PathSegment::SeqElement(0));
+    path.push_segment(PathSegment::StructField("name"));
+    assert_eq!(*path.borrow_str(), "users[0].name");
+    assert_eq!(path.to_json_pointer(), "/users/0/name");
+
+    let mut path = Path::new();
+    path.push_segment(PathSegment::MapEntry(PathMapKey::new(0, "a/b~c".into())));
+    path.push_segment(PathSegment::MapEntry(PathMapKey::new(1, 42.into())));
+    path.push_segment(PathSegment::MapEntry(PathMapKey::new(2, Value::Tuple(2))));
+    assert_eq!(*path.borrow_str(), "[\"a/b~c\"][42][2]");
+    assert_eq!(path.to_json_pointer(), "/a~1b~0c/42/2");
+}
+
+#[test]
+fn test_key_render() {
+    let key = PathMapKey::new(0, "a/b".into());
+    assert_eq!(key.render(KeyRenderStyle::Rust), "\"a/b\"");
+    assert_eq!(key.render(KeyRenderStyle::JsonPointerToken), "a~1b");
+    assert_eq!(key.render(KeyRenderStyle::Debug), "Str(\"a/b\")");
+
+    let key = PathMapKey::new(3, Value::Tuple(2));
+    assert_eq!(key.render(KeyRenderStyle::Rust), "3");
+    assert_eq!(key.render(KeyRenderStyle::JsonPointerToken), "3");
+    assert_eq!(key.render(KeyRenderStyle::Debug), "Tuple(2)");
+}
+
+#[test]
+fn test_with_str_reuses_cache() {
+    let mut path = Path::new();
+    path.push_segment(PathSegment::StructField("users"));
+    path.push_segment(PathSegment::SeqElement(3));
+
+    let first = path.with_str(|s| {
+        assert_eq!(s, "users[3]");
+        (s.as_ptr(), s.len())
+    });
+    let second = path.with_str(|s| (s.as_ptr(), s.len()));
+    assert_eq!(first, second);
+
+    // The borrow is released, so the path can be modified and the cache extended.
+    path.push_segment(PathSegment::StructField("name"));
+    path.with_str(|s| assert_eq!(s, "users[3].name"));
+}
+
+#[test]
+fn test_borrow_str_matches_display() {
+    let mut path = Path::new();
+    let segments = [
+        PathSegment::StructField("users"),
+        PathMapKey::new(0, Value::Str("a \"b\"".into())).into(),
+        PathSegment::SeqElement(3),
+        PathMapKey::new(1, Value::U32(7)).into(),
+        PathMapKey::new(2, Value::Char('c')).into(),
+        PathMapKey::new(3, Value::Tuple(2)).into(),
+        PathSegment::StructField("name"),
+    ];
+    let mut expected = String::new();
+    for segment in segments {
+        match &segment {
+            PathSegment::StructField(_) if !expected.is_empty() => {
+                write!(expected, ".{segment}").unwrap()
+            }
+            _ => write!(expected, "{segment}").unwrap(),
+        }
+        path.push_segment(segment);
+        assert_eq!(*path.borrow_str(), expected);
+    }
+    assert_eq!(*path.borrow_str(), r#"users["a "b""][3][7]['c'][3].name"#);
+}
@@ -0,0 +1,196 @@
+use serde::Serializer;
+
+use super::{
+    BytesScope, EndScope, EnumVariantScope, ErrorScope, Hooks, MapKeyScope, MapScope,
+    NewtypeStructScope, OptionScope, SeqScope, StartScope, StructScope, TupleScope,
+    TupleStructScope, UnitStructScope, ValueScope,
+};
+use crate::{Path, Value};
+
+impl<H: Hooks> Hooks for &H {
+    const ACTIVE: bool = H::ACTIVE;
+
+    fn on_start(&self, start: &mut StartScope) {
+        (**self).on_start(start);
+    }
+
+    fn on_end<Error: serde::ser::Error>(&self, end: &mut EndScope<Error>) {
+        (**self).on_end(end);
+    }
+
+    fn on_value<S: Serializer>(&self, path: &Path, value: &mut ValueScope<S>) {
+        (**self).on_value(path, value);
+    }
+
+    fn on_bytes<S: Serializer>(&self, path: &Path, bytes: &mut BytesScope<S>) {
+        (**self).on_bytes(path, bytes);
+    }
+
+    fn on_option<S: Serializer>(&self, path: &Path, opt: &mut OptionScope<S>) {
+        (**self).on_option(path, opt);
+    }
+
+    fn on_struct(&self, path: &Path, st: &mut StructScope) {
+        (**self).on_struct(path, st);
+    }
+
+    fn on_struct_end(&self, path: &Path, emitted_len: usize) {
+        (**self).on_struct_end(path, emitted_len);
+    }
+
+    fn on_unit_struct(&self, path: &Path, us: &mut UnitStructScope) {
+        (**self).on_unit_struct(path, us);
+    }
+
+    fn on_newtype_struct(&self, path: &Path, nt: &mut NewtypeStructScope) {
+        (**self).on_newtype_struct(path, nt);
+    }
+
+    fn on_seq(&self, path: &Path, seq: &mut SeqScope) {
+        (**self).on_seq(path, seq);
+    }
+
+    fn on_seq_element_end(&self, path: &Path, index: usize) {
+        (**self).on_seq_element_end(path, index);
+    }
+
+    fn on_tuple(&self, path: &Path, tpl: &mut TupleScope, seq: &mut SeqScope) {
+        (**self).on_tuple(path, tpl, seq);
+    }
+
+    fn on_tuple_struct(&self, path: &Path, tpl: &mut TupleStructScope, seq: &mut SeqScope) {
+        (**self).on_tuple_struct(path, tpl, seq);
+    }
+
+    fn on_map(&self, path: &Path, map: &mut MapScope) {
+        (**self).on_map(path, map);
+    }
+
+    fn on_map_key<S: Serializer>(&self, path: &Path, map_key: &mut MapKeyScope<S>) {
+        (**self).on_map_key(path, map_key);
+    }
+
+    fn on_map_entry_end(&self, path: &Path, key: &Value, index: usize) {
+        (**self).on_map_entry_end(path, key, index);
+    }
+
+    fn on_enum_variant(&self, path: &Path, ev: &mut EnumVariantScope) {
+        (**self).on_enum_variant(path, ev);
+    }
+
+    fn on_struct_variant(&self, path: &Path, ev: &mut EnumVariantScope, st: &mut StructScope) {
+        (**self).on_struct_variant(path, ev, st);
+    }
+
+    fn on_tuple_variant(
+        &self,
+        path: &Path,
+        ev: &mut EnumVariantScope,
+        tpl: &mut TupleScope,
+        seq: &mut SeqScope,
+    ) {
+        (**self).on_tuple_variant(path, ev, tpl, seq);
+    }
+
+    fn on_scope_error(&self, path: &Path, err: &mut ErrorScope) {
+        (**self).on_scope_error(path, err);
+    }
+}
+
+macro_rules! tuple_hooks {
+    ($($h:ident $i:tt),+) => {
+        impl<$($h: Hooks),+> Hooks for ($($h,)+) {
+            const ACTIVE: bool = $($h::ACTIVE)||+;
+
+            fn on_start(&self, start: &mut StartScope) {
+                $(self.$i.on_start(start);)+
+            }
+
+            fn on_end<Error: serde::ser::Error>(&self, end: &mut EndScope<Error>) {
+                $(self.$i.on_end(end);)+
+            }
+
+            fn on_value<S: Serializer>(&self, path: &Path, value: &mut ValueScope<S>) {
+                $(self.$i.on_value(path, value);)+
+            }
+
+            fn on_bytes<S: Serializer>(&self, path: &Path, bytes: &mut BytesScope<S>) {
+                $(self.$i.on_bytes(path, bytes);)+
+            }
+
+            fn on_option<S: Serializer>(&self, path: &Path, opt: &mut OptionScope<S>) {
+                $(self.$i.on_option(path, opt);)+
+            }
+
+            fn on_struct(&self, path: &Path, st: &mut StructScope) {
+                $(self.$i.on_struct(path, st);)+
+            }
+
+            fn on_struct_end(&self, path: &Path, emitted_len: usize) {
+                $(self.$i.on_struct_end(path, emitted_len);)+
+            }
+
+            fn on_unit_struct(&self, path: &Path, us: &mut UnitStructScope) {
+                $(self.$i.on_unit_struct(path, us);)+
+            }
+
+            fn on_newtype_struct(&self, path: &Path, nt: &mut NewtypeStructScope) {
+                $(self.$i.on_newtype_struct(path, nt);)+
+            }
+
+            fn on_seq(&self, path: &Path, seq: &mut SeqScope) {
+                $(self.$i.on_seq(path, seq);)+
+            }
+
+            fn on_seq_element_end(&self, path: &Path, index: usize) {
+                $(self.$i.on_seq_element_end(path, index);)+
+            }
+
+            fn on_tuple(&self, path: &Path, tpl: &mut TupleScope, seq: &mut SeqScope) {
+                $(self.$i.on_tuple(path, tpl, seq);)+
+            }
+
+            fn on_tuple_struct(&self, path: &Path, tpl: &mut TupleStructScope, seq: &mut SeqScope) {
+                $(self.$i.on_tuple_struct(path, tpl, seq);)+
+            }
+
+            fn on_map(&self, path: &Path, map: &mut MapScope) {
+                $(self.$i.on_map(path, map);)+
+            }
+
+            fn on_map_key<S: Serializer>(&self, path: &Path, map_key: &mut MapKeyScope<S>) {
+                $(self.$i.on_map_key(path, map_key);)+
+            }
+
+            fn on_map_entry_end(&self, path: &Path, key: &Value, index: usize) {
+                $(self.$i.on_map_entry_end(path, key, index);)+
+            }
+
+            fn on_enum_variant(&self, path: &Path, ev: &mut EnumVariantScope) {
+                $(self.$i.on_enum_variant(path, ev);)+
+            }
+
+            fn on_struct_variant(&self, path: &Path, ev: &mut EnumVariantScope, st: &mut StructScope) {
+                $(self.$i.on_struct_variant(path, ev, st);)+
+            }
+
+            fn on_tuple_variant(
+                &self,
+                path: &Path,
+                ev: &mut EnumVariantScope,
+                tpl: &mut TupleScope,
+                seq: &mut SeqScope,
+            ) {
+                $(self.$i.on_tuple_variant(path, ev, tpl, seq);)+
+            }
+
+            fn on_scope_error(&self, path: &Path, err: &mut ErrorScope) {
+                $(self.$i.on_scope_error(path, err);)+
+            }
+        }
+    };
+}
+
+tuple_hooks!(A 0, B 1);
+tuple_hooks!(A 0, B 1, C 2);
+tuple_hooks!(A 0, B 1, C 2, D 3);
@@ -1,22 +1,26 @@
 use std::borrow::Cow;
-use std::cell::{RefCell, RefMut};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
 use std::pin::Pin;
 use std::rc::Rc;
 
 use serde::{Serialize, Serializer};
 
+use super::fingerprint::SchemaFingerprint;
 use super::scope::{
-    EnumVariantScope, ErrorScope, MapKeyScope, MapScope, SeqScope, StartScope, StructScope,
-    TupleScope, TupleStructScope, ValueScope,
+    BytesScope, EnumVariantScope, ErrorScope, MapKeyScope, MapScope, NewtypeStructScope,
+    OptionScope, SeqScope, StartScope, Statistics, StructScope, TupleScope, TupleStructScope,
+    UnitStructScope, ValueScope,
 };
 use super::wrapper::{
-    MapEntryActions, SeqElementActions, SerializableKind, SerializerWrapper,
+    MapEntryActions, NewtypeStructActions, SeqElementActions, SerializableKind, SerializerWrapper,
     SerializerWrapperHooks, StructActions, StructFieldActions, ValueAction, VariantActions,
 };
-use super::EndScope;
+use super::{EndScope, HookOptions};
+use crate::glob::GlobStates;
 use crate::path::{Path, PathSegment};
 use crate::ser::{Hooks, HooksError};
-use crate::Value;
+use crate::{StaticValue, Value};
 
 pub(crate) struct SerializableWithContext<'s, 'h, T: Serialize + ?Sized, H: Hooks> {
     serializable: &'s T,
@@ -24,10 +28,10 @@ pub(crate) struct SerializableWithContext<'s, 'h, T: Serialize + ?Sized, H: Hook
 }
 
 impl<'s, 'h, T: Serialize + ?Sized, H: Hooks> SerializableWithContext<'s, 'h, T, H> {
-    pub(super) fn new(serializable: &'s T, hooks: &'h H) -> Self {
+    pub(super) fn new(serializable: &'s T, hooks: &'h H, options: HookOptions) -> Self {
         Self {
             serializable,
-            context: Context::new(hooks),
+            context: Context::new(hooks, options),
         }
     }
 }
@@ -37,7 +41,16 @@ impl<T: Serialize + ?Sized, H: Hooks> Serialize for SerializableWithContext<'_,
     where
         S: Serializer,
     {
-        self.context.on_start(serializer.is_human_readable());
+        if !H::ACTIVE && !self.context.inner.borrow().options.changes_output() {
+            return self.serializable.serialize(serializer);
+        }
+
+        if let Some(message) = self.context.on_start(serializer.is_human_readable()) {
+            let err = serde::ser::Error::custom(message);
+            self.context.on_end(Err(&err));
+            return Err(err);
+        }
+
         let res = self.serializable.serialize(SerializerWrapper::new(
             serializer,
             &self.context,
@@ -54,32 +67,105 @@ pub(crate) struct Context<'h, H: Hooks> {
 }
 
 impl<H: Hooks> SerializerWrapperHooks for Context<'_, H> {
-    fn path_push(&self, segment: PathSegment) {
-        self.inner.borrow_mut().path.push_segment(segment);
+    fn path_push<S: Serializer>(&self, segment: PathSegment) -> Result<(), S::Error> {
+        let (depth, max_depth) = {
+            let inner = &mut *self.inner.borrow_mut();
+            if let Some(matchers) = &inner.options.matchers {
+                let states = matchers.next_states(
+                    inner
+                        .matcher_states
+                        .last()
+                        .expect("no initial matcher states"),
+                    &segment,
+                );
+                inner.matcher_states.push(states);
+            }
+            inner.path.push_segment(segment);
+            (inner.path.depth(), inner.options.max_depth)
+        };
+
+        match max_depth {
+            Some(max_depth) if depth > max_depth => {
+                self.on_error::<S>(HooksError::MaxDepthExceeded(max_depth))
+            }
+            _ => Ok(()),
+        }
     }
 
     fn path_pop(&self) -> PathSegment {
-        self.inner.borrow_mut().path.pop_segment()
+        let inner = &mut *self.inner.borrow_mut();
+        if inner.options.matchers.is_some() {
+            inner.matcher_states.pop();
+        }
+        let segment = inner.path.pop_segment();
+        if matches!(inner.suppressed_errors_depth, Some(depth) if inner.path.depth() < depth) {
+            inner.suppressed_errors_depth = None;
+        }
+        segment
     }
 
     fn on_map(&self, map_len: Option<usize>) -> MapEntryActions {
+        self.inner.borrow_mut().statistics.maps_serialized += 1;
         let path = &self.inner.borrow().path;
-        let mut scope = MapScope::new(map_len);
-        self.inner.borrow().hooks.on_map(path, &mut scope);
+        let mut scope = MapScope::new(map_len, self.is_human_readable());
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_map(path, &mut scope);
+        }
+        self.store_annotations(path, scope.take_annotations());
         scope.into_actions()
     }
 
+    fn on_map_entry_end(&self, replacement_key: Option<&StaticValue>) {
+        let path = &self.inner.borrow().path;
+        let Some(PathSegment::MapEntry(map_key)) = path.segments().last() else {
+            return;
+        };
+        if let Some(hooks) = self.active_hooks() {
+            let key = replacement_key.unwrap_or(&map_key.value);
+            hooks.on_map_entry_end(path, key, map_key.index);
+        }
+    }
+
     fn on_struct(
         &self,
         struct_len: usize,
         struct_name: &'static str,
     ) -> (StructActions, StructFieldActions) {
+        self.inner.borrow_mut().statistics.structs_serialized += 1;
         let path = &self.inner.borrow().path;
-        let mut scope = StructScope::new(struct_len, struct_name);
-        self.inner.borrow().hooks.on_struct(path, &mut scope);
+        let mut scope = StructScope::new(struct_len, struct_name, self.is_human_readable());
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_struct(path, &mut scope);
+        }
+        self.store_annotations(path, scope.take_annotations());
         scope.into_actions()
     }
 
+    fn on_struct_end(&self, emitted_len: usize) {
+        let path = &self.inner.borrow().path;
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_struct_end(path, emitted_len);
+        }
+    }
+
+    fn on_newtype_struct(&self, name: &'static str) -> NewtypeStructActions {
+        let path = &self.inner.borrow().path;
+        let mut scope = NewtypeStructScope::new(name);
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_newtype_struct(path, &mut scope);
+        }
+        scope.into_actions()
+    }
+
+    fn on_unit_struct(&self, name: &'static str) -> Option<Cow<'static, str>> {
+        let path = &self.inner.borrow().path;
+        let mut scope = UnitStructScope::new(name);
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_unit_struct(path, &mut scope);
+        }
+        scope.into_new_struct_name()
+    }
+
     fn on_struct_variant(
         &self,
         struct_len: usize,
@@ -87,17 +173,19 @@ impl<H: Hooks> SerializerWrapperHooks for Context<'_, H> {
         variant_name: &'static str,
         variant_index: u32,
     ) -> (VariantActions, StructActions, StructFieldActions) {
+        self.inner.borrow_mut().statistics.structs_serialized += 1;
         let path = &self.inner.borrow().path;
 
         let mut variant_scope = EnumVariantScope::new(enum_name, variant_name, variant_index);
-        let mut struct_scope = StructScope::new(struct_len, variant_name);
-
-        let hooks = self.inner.borrow().hooks;
+        let mut struct_scope = StructScope::new(struct_len, variant_name, self.is_human_readable());
 
-        hooks.on_enum_variant(path, &mut variant_scope);
-        hooks.on_struct(path, &mut struct_scope);
-        hooks.on_struct_variant(path, &mut variant_scope, &mut struct_scope);
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_enum_variant(path, &mut variant_scope);
+            hooks.on_struct(path, &mut struct_scope);
+            hooks.on_struct_variant(path, &mut variant_scope, &mut struct_scope);
+        }
 
+        self.store_annotations(path, struct_scope.take_annotations());
         let (struct_actions, field_actions) = struct_scope.into_actions();
         (variant_scope.into_actions(), struct_actions, field_actions)
     }
@@ -109,9 +197,16 @@ impl<H: Hooks> SerializerWrapperHooks for Context<'_, H> {
     ) -> Result<ValueAction<S>, S::Error> {
         let path = &self.inner.borrow().path;
 
-        let mut scope = MapKeyScope::new(serializer, value);
-        self.inner.borrow().hooks.on_map_key(path, &mut scope);
-        scope.into_action()
+        if let Some(fingerprint) = &mut *self.inner.borrow().schema_fingerprint.borrow_mut() {
+            fingerprint.record_map_key(path, &value);
+        }
+
+        let mut scope = MapKeyScope::new(serializer, value, false, false);
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_map_key(path, &mut scope);
+        }
+        self.store_annotations(path, scope.take_annotations());
+        scope.into_action().map(|(action, _)| action)
     }
 
     fn on_value<S: Serializer>(
@@ -119,29 +214,196 @@ impl<H: Hooks> SerializerWrapperHooks for Context<'_, H> {
         serializer: S,
         value: Value,
     ) -> Result<ValueAction<S>, S::Error> {
+        // Only the first value after begin_skippable_value is the one being captured.
+        let is_skippable = {
+            let inner = &mut *self.inner.borrow_mut();
+            inner.statistics.values_serialized += 1;
+            std::mem::take(&mut inner.is_skippable_value)
+        };
+
+        let is_option = matches!(value, Value::None | Value::Some);
+
+        let (action, expanded_elements) = {
+            let path = &self.inner.borrow().path;
+            let is_seq_element = matches!(path.segments().last(), Some(PathSegment::SeqElement(_)));
+
+            if let Some(fingerprint) = &mut *self.inner.borrow().schema_fingerprint.borrow_mut() {
+                fingerprint.record_value(path, &value);
+            }
+
+            let is_expanding_allowed = self.inner.borrow().options.allow_expanding_elements;
+            let mut scope =
+                ValueScope::new(serializer, value, is_seq_element, is_expanding_allowed);
+            if let Some(hooks) = self.active_hooks() {
+                hooks.on_value(path, &mut scope);
+            }
+            self.store_annotations(path, scope.take_annotations());
+            scope.into_action()?
+        };
+
+        if !expanded_elements.is_empty() {
+            self.inner.borrow_mut().expanded_elements = expanded_elements;
+        }
+
+        match action {
+            ValueAction::Skipped(s) if !is_skippable => {
+                self.on_error::<S>(HooksError::CannotSkipValue)?;
+                Ok(ValueAction::ContinueSerialization(s))
+            }
+            ValueAction::Skipped(s) => {
+                self.inner.borrow_mut().is_value_skipped = true;
+                Ok(ValueAction::Skipped(s))
+            }
+            ValueAction::ContinueSerialization(s) if is_option => {
+                // Options can still be skipped in on_option, which follows.
+                self.inner.borrow_mut().is_skippable_value = is_skippable;
+                Ok(ValueAction::ContinueSerialization(s))
+            }
+            action => Ok(action),
+        }
+    }
+
+    fn on_option<S: Serializer>(
+        &self,
+        serializer: S,
+        is_some: bool,
+    ) -> Result<ValueAction<S>, S::Error> {
+        let is_skippable = std::mem::take(&mut self.inner.borrow_mut().is_skippable_value);
+
+        let action = {
+            let path = &self.inner.borrow().path;
+            let mut scope = OptionScope::new(serializer, is_some);
+            if let Some(hooks) = self.active_hooks() {
+                hooks.on_option(path, &mut scope);
+            }
+            scope.into_action()
+        };
+
+        match action {
+            ValueAction::Skipped(s) if !is_skippable => {
+                self.on_error::<S>(HooksError::CannotSkipValue)?;
+                Ok(ValueAction::ContinueSerialization(s))
+            }
+            ValueAction::Skipped(s) => {
+                self.inner.borrow_mut().is_value_skipped = true;
+                Ok(ValueAction::Skipped(s))
+            }
+            action => Ok(action),
+        }
+    }
+
+    fn on_bytes<S: Serializer>(
+        &self,
+        serializer: S,
+        bytes: &[u8],
+    ) -> Result<ValueAction<S>, S::Error> {
+        let encoding = self.inner.borrow().options.bytes_encoding;
         let path = &self.inner.borrow().path;
+        let mut scope = BytesScope::new(serializer, bytes);
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_bytes(path, &mut scope);
+        }
+        match scope.into_action()? {
+            ValueAction::ContinueSerialization(s) => match encoding.encode(bytes) {
+                Some(encoded) => Ok(ValueAction::ValueReplaced(s.serialize_str(&encoded))),
+                None => Ok(ValueAction::ContinueSerialization(s)),
+            },
+            action => Ok(action),
+        }
+    }
+
+    fn on_float<S: Serializer>(
+        &self,
+        serializer: S,
+        value: f64,
+    ) -> Result<ValueAction<S>, S::Error> {
+        let policy = self.inner.borrow().options.float_policy;
+        match policy.replacement(value) {
+            Some(replacement) => {
+                replacement
+                    .check_if_can_serialize()
+                    .or_else(|err| self.on_error::<S>(err))?;
+                Ok(ValueAction::ValueReplaced(
+                    replacement.serialize(serializer),
+                ))
+            }
+            None => Ok(ValueAction::ContinueSerialization(serializer)),
+        }
+    }
+
+    fn take_expanded_elements(&self) -> Vec<StaticValue> {
+        std::mem::take(&mut self.inner.borrow_mut().expanded_elements)
+    }
+
+    fn is_value_skipping_allowed(&self) -> bool {
+        self.inner.borrow().options.allow_skipping_values
+    }
 
-        let mut scope = ValueScope::new(serializer, value);
-        self.inner.borrow().hooks.on_value(path, &mut scope);
-        scope.into_action()
+    fn is_element_expanding_allowed(&self) -> bool {
+        self.inner.borrow().options.allow_expanding_elements
+    }
+
+    fn captures_byte_keys(&self) -> bool {
+        self.inner.borrow().options.capture_byte_keys
+    }
+
+    fn begin_skippable_value(&self) {
+        let inner = &mut *self.inner.borrow_mut();
+        inner.is_skippable_value = true;
+        inner.is_value_skipped = false;
+    }
+
+    fn end_skippable_value(&self) -> bool {
+        let inner = &mut *self.inner.borrow_mut();
+        inner.is_skippable_value = false;
+        std::mem::take(&mut inner.is_value_skipped)
     }
 
     fn on_error<S: Serializer>(&self, error: HooksError) -> Result<(), S::Error> {
-        let path = &self.inner.borrow().path;
+        let inner = self.inner.borrow();
+        let path = &inner.path;
+        if inner.suppressed_errors_depth == Some(path.depth()) {
+            return Ok(());
+        }
 
+        let force_propagate = inner.options.strict && error.is_unmatched_action();
         let mut scope = ErrorScope::new(path, error);
-        self.inner.borrow().hooks.on_scope_error(path, &mut scope);
-        scope.into_result::<S>()
+        inner.hooks.on_scope_error(path, &mut scope);
+        if force_propagate {
+            scope.propagate();
+        }
+        let suppress_scope_errors = scope.suppresses_scope_errors();
+        let ignored_error = scope.into_result::<S>()?;
+
+        let path_str = path.borrow_str().clone();
+        let depth = path.depth();
+        drop(inner);
+        let mut inner = self.inner.borrow_mut();
+        inner.ignored_errors.push((path_str, ignored_error));
+        if suppress_scope_errors {
+            inner.suppressed_errors_depth = Some(depth);
+        }
+        Ok(())
     }
 
     fn on_seq(&self, len: Option<usize>) -> SeqElementActions {
         let path = &self.inner.borrow().path;
 
-        let mut scope = SeqScope::new(len);
-        self.inner.borrow().hooks.on_seq(path, &mut scope);
+        let mut scope = SeqScope::new(len, self.is_human_readable());
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_seq(path, &mut scope);
+        }
+        self.store_annotations(path, scope.take_annotations());
         scope.into_actions()
     }
 
+    fn on_seq_element_end(&self, index: usize) {
+        let path = &self.inner.borrow().path;
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_seq_element_end(path, index);
+        }
+    }
+
     fn on_unit_variant(
         &self,
         enum_name: &'static str,
@@ -152,8 +414,9 @@ impl<H: Hooks> SerializerWrapperHooks for Context<'_, H> {
 
         let mut variant_scope = EnumVariantScope::new(enum_name, variant_name, variant_index);
 
-        let hooks = self.inner.borrow().hooks;
-        hooks.on_enum_variant(path, &mut variant_scope);
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_enum_variant(path, &mut variant_scope);
+        }
 
         variant_scope.into_actions()
     }
@@ -168,8 +431,9 @@ impl<H: Hooks> SerializerWrapperHooks for Context<'_, H> {
 
         let mut variant_scope = EnumVariantScope::new(enum_name, variant_name, variant_index);
 
-        let hooks = self.inner.borrow().hooks;
-        hooks.on_enum_variant(path, &mut variant_scope);
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_enum_variant(path, &mut variant_scope);
+        }
 
         variant_scope.into_actions()
     }
@@ -178,13 +442,14 @@ impl<H: Hooks> SerializerWrapperHooks for Context<'_, H> {
         let path = &self.inner.borrow().path;
 
         let mut tuple_scope = TupleScope::new(len);
-        let mut seq_scope = SeqScope::new(Some(len));
-
-        let hooks = self.inner.borrow().hooks;
+        let mut seq_scope = SeqScope::new(Some(len), self.is_human_readable());
 
-        hooks.on_seq(path, &mut seq_scope);
-        hooks.on_tuple(path, &mut tuple_scope, &mut seq_scope);
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_seq(path, &mut seq_scope);
+            hooks.on_tuple(path, &mut tuple_scope, &mut seq_scope);
+        }
 
+        self.store_annotations(path, seq_scope.take_annotations());
         seq_scope.into_actions()
     }
 
@@ -193,14 +458,15 @@ impl<H: Hooks> SerializerWrapperHooks for Context<'_, H> {
 
         let mut tuple_scope = TupleScope::new(len);
         let mut tuple_struct_scope = TupleStructScope::new(name, len);
-        let mut seq_scope = SeqScope::new(Some(len));
-
-        let hooks = self.inner.borrow().hooks;
+        let mut seq_scope = SeqScope::new(Some(len), self.is_human_readable());
 
-        hooks.on_seq(path, &mut seq_scope);
-        hooks.on_tuple(path, &mut tuple_scope, &mut seq_scope);
-        hooks.on_tuple_struct(path, &mut tuple_struct_scope, &mut seq_scope);
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_seq(path, &mut seq_scope);
+            hooks.on_tuple(path, &mut tuple_scope, &mut seq_scope);
+            hooks.on_tuple_struct(path, &mut tuple_struct_scope, &mut seq_scope);
+        }
 
+        self.store_annotations(path, seq_scope.take_annotations());
         seq_scope.into_actions()
     }
 
@@ -215,15 +481,16 @@ impl<H: Hooks> SerializerWrapperHooks for Context<'_, H> {
 
         let mut variant_scope = EnumVariantScope::new(enum_name, variant_name, variant_index);
         let mut tuple_scope = TupleScope::new(len);
-        let mut seq_scope = SeqScope::new(Some(len));
-
-        let hooks = self.inner.borrow().hooks;
+        let mut seq_scope = SeqScope::new(Some(len), self.is_human_readable());
 
-        hooks.on_enum_variant(path, &mut variant_scope);
-        hooks.on_seq(path, &mut seq_scope);
-        hooks.on_tuple(path, &mut tuple_scope, &mut seq_scope);
-        hooks.on_tuple_variant(path, &mut variant_scope, &mut tuple_scope, &mut seq_scope);
+        if let Some(hooks) = self.active_hooks() {
+            hooks.on_enum_variant(path, &mut variant_scope);
+            hooks.on_seq(path, &mut seq_scope);
+            hooks.on_tuple(path, &mut tuple_scope, &mut seq_scope);
+            hooks.on_tuple_variant(path, &mut variant_scope, &mut tuple_scope, &mut seq_scope);
+        }
 
+        self.store_annotations(path, seq_scope.take_annotations());
         (variant_scope.into_actions(), seq_scope.into_actions())
     }
 
@@ -231,10 +498,18 @@ impl<H: Hooks> SerializerWrapperHooks for Context<'_, H> {
         match key {
             Cow::Borrowed(static_key) => static_key,
             Cow::Owned(string_key) => {
-                let mut static_strs = RefMut::map(self.inner.borrow_mut(), |r| &mut r.static_strs);
+                let mut inner = self.inner.borrow_mut();
+                if let Some(static_key) = inner.interned_strs.get(string_key.as_str()) {
+                    return static_key;
+                }
+                inner.statistics.runtime_string_allocations += 1;
+                inner.statistics.static_string_bytes += string_key.len();
                 let boxed_key = Pin::new(string_key.into_boxed_str());
                 let static_key = unsafe { std::mem::transmute::<&str, &'static str>(&boxed_key) };
-                static_strs.push(boxed_key);
+                inner.static_strs.push(boxed_key);
+                // The boxed strings don't move, and are only dropped after on_end,
+                // where the interned references are cleared too.
+                inner.interned_strs.insert(static_key);
                 static_key
             }
         }
@@ -242,36 +517,127 @@ impl<H: Hooks> SerializerWrapperHooks for Context<'_, H> {
 }
 
 impl<'h, H: Hooks> Context<'h, H> {
-    pub(super) fn new(hooks: &'h H) -> Self {
+    pub(super) fn new(hooks: &'h H, options: HookOptions) -> Self {
+        let matcher_states = options
+            .matchers
+            .iter()
+            .map(|matchers| matchers.initial_states())
+            .collect();
+        let static_strs = Vec::with_capacity(options.static_str_capacity);
         Self {
             inner: Rc::new(RefCell::new(ContextInner {
                 path: Path::new(),
                 hooks,
-                static_strs: Vec::new(),
+                options,
+                matcher_states,
+                static_strs,
+                ignored_errors: Vec::new(),
+                interned_strs: HashSet::new(),
+                statistics: Statistics::default(),
+                annotations: Default::default(),
+                schema_fingerprint: Default::default(),
+                expanded_elements: Vec::new(),
+                is_skippable_value: false,
+                is_value_skipped: false,
+                is_human_readable: true,
+                suppressed_errors_depth: None,
             })),
         }
     }
 
-    pub(super) fn on_start(&self, is_human_readable: bool) {
+    fn is_human_readable(&self) -> bool {
+        self.inner.borrow().is_human_readable
+    }
+
+    fn store_annotations(&self, path: &Path, annotations: Vec<(String, String)>) {
+        if annotations.is_empty() {
+            return;
+        }
         self.inner
             .borrow()
-            .hooks
-            .on_start(&mut StartScope::new(is_human_readable));
+            .annotations
+            .borrow_mut()
+            .entry(path.borrow_str().clone())
+            .or_default()
+            .extend(annotations);
+    }
+
+    /// Returns hooks, if they should be called at the current path.
+    fn active_hooks(&self) -> Option<&'h H> {
+        let inner = self.inner.borrow();
+        match (&inner.options.matchers, inner.matcher_states.last()) {
+            (Some(matchers), Some(states)) if !matchers.is_match_in(states) => None,
+            _ => Some(inner.hooks),
+        }
+    }
+
+    /// Returns the abort message if the hooks aborted serialization.
+    pub(super) fn on_start(&self, is_human_readable: bool) -> Option<String> {
+        self.inner.borrow_mut().is_human_readable = is_human_readable;
+        let format = self.inner.borrow().options.format.clone();
+        let mut scope = StartScope::new(is_human_readable, format);
+        self.inner.borrow().hooks.on_start(&mut scope);
+        if let Some(is_human_readable) = scope.human_readable_override() {
+            self.inner.borrow_mut().is_human_readable = is_human_readable;
+        }
+        if scope.is_schema_fingerprint_enabled() {
+            self.inner
+                .borrow()
+                .schema_fingerprint
+                .replace(Some(SchemaFingerprint::default()));
+        }
+        scope.take_abort_message()
     }
 
     pub(super) fn on_end(&self, result: Result<(), &impl serde::ser::Error>) {
-        let static_strs = std::mem::take(&mut self.inner.borrow_mut().static_strs);
-        self.inner
-            .borrow()
-            .hooks
-            .on_end(&mut EndScope::new(static_strs, result));
+        let (static_strs, ignored_errors, statistics, annotations, schema_fingerprint) = {
+            let mut inner = self.inner.borrow_mut();
+            let capacity = inner.options.static_str_capacity;
+            inner.interned_strs.clear();
+            (
+                std::mem::replace(&mut inner.static_strs, Vec::with_capacity(capacity)),
+                std::mem::take(&mut inner.ignored_errors),
+                inner.statistics,
+                inner.annotations.take(),
+                inner
+                    .schema_fingerprint
+                    .take()
+                    .map(|fingerprint| fingerprint.finish()),
+            )
+        };
+        self.inner.borrow().hooks.on_end(&mut EndScope::new(
+            static_strs,
+            ignored_errors,
+            statistics,
+            annotations,
+            schema_fingerprint,
+            result,
+        ));
     }
 }
 
 struct ContextInner<'h, H: Hooks> {
     path: Path,
     hooks: &'h H,
+    options: HookOptions,
+    // Matcher automaton states for each path segment, plus the root.
+    matcher_states: Vec<GlobStates>,
     static_strs: Vec<Pin<Box<str>>>,
+    ignored_errors: Vec<(String, HooksError)>,
+    // references to static_strs, to reuse the strings that repeat
+    interned_strs: HashSet<&'static str>,
+    statistics: Statistics,
+    // in a RefCell, so that annotations can be stored while the path is borrowed
+    annotations: RefCell<BTreeMap<String, BTreeMap<String, String>>>,
+    schema_fingerprint: RefCell<Option<SchemaFingerprint>>,
+    expanded_elements: Vec<StaticValue>,
+    // set while a value that can be skipped is being captured
+    is_skippable_value: bool,
+    is_value_skipped: bool,
+    // captured in on_start
+    is_human_readable: bool,
+    // path depth of the scope, errors of which are suppressed with ErrorScope::suppress_and_continue
+    suppressed_errors_depth: Option<usize>,
 }
 
 #[test]
@@ -287,7 +653,7 @@ fn test_make_static_str() {
 
     struct FauxHooks;
     impl Hooks for FauxHooks {}
-    let context = Context::new(&FauxHooks);
+    let context = Context::new(&FauxHooks, HookOptions::default());
 
     // Static strings are just pass-through
     let foo_str: &'static str = "foo";
@@ -308,8 +674,39 @@ fn test_make_static_str() {
     let cached_str: &'static str = context.make_static_str(Cow::Owned(baz_str.to_string()));
     assert_refs_ne(baz_str, cached_str);
     assert_eq!(baz_str, cached_str);
+    assert_refs_eq(
+        context.make_static_str(Cow::Owned(baz_str.to_string())),
+        cached_str,
+    );
+    assert_eq!(context.inner.borrow().static_strs.len(), 1);
 
     // Static strings are still pass-through, even if we have cached the exact same
     // owned one
     assert_refs_eq(context.make_static_str(Cow::Borrowed(baz_str)), baz_str);
 }
+
+#[test]
+fn test_make_static_str_interning() {
+    struct FauxHooks;
+    impl Hooks for FauxHooks {}
+    let context = Context::new(&FauxHooks, HookOptions::default());
+
+    let first = context.make_static_str(Cow::Owned("key".to_string()));
+    for _ in 0..100 {
+        let again = context.make_static_str(Cow::Owned("key".to_string()));
+        assert_eq!(first as *const str, again as *const str);
+    }
+    let other = context.make_static_str(Cow::Owned("other".to_string()));
+    assert_ne!(first as *const str, other as *const str);
+    assert_eq!(context.inner.borrow().static_strs.len(), 2);
+    assert_eq!(
+        context.inner.borrow().statistics.runtime_string_allocations,
+        2
+    );
+
+    // The strings are handed over at the end of serialization, so they are not reused after.
+    context.on_end(Ok::<(), &std::fmt::Error>(()));
+    assert!(context.inner.borrow().interned_strs.is_empty());
+    context.make_static_str(Cow::Owned("key".to_string()));
+    assert_eq!(context.inner.borrow().static_strs.len(), 1);
+}
@@ -0,0 +1,252 @@
+use serde::Serializer;
+
+use super::{
+    EnumVariantScope, ErrorScope, Hooks, MapScope, NewtypeStructScope, SeqScope, StartScope,
+    StructScope, TupleScope, TupleStructScope, UnitStructScope, ValueScope,
+};
+use crate::{Path, Value};
+
+/// An object safe version of [`Hooks`].
+///
+/// [`Hooks`] is generic over the serializer, so it can't be used as a trait object.
+/// Implement this trait instead, and wrap the implementation in [`BoxedHooks`] to
+/// pick hooks at runtime, e.g. from a registry.
+///
+/// The hooks have the same meaning as in [`Hooks`], with these limitations:
+/// - [`on_value`](Self::on_value) receives a [`DynValueScope`], which can only replace
+///   values with a [`Value`], not with any serializable type.
+/// - [`on_end`](Hooks::on_end), [`on_bytes`](Hooks::on_bytes), [`on_option`](Hooks::on_option)
+///   and [`on_map_key`](Hooks::on_map_key) are not available.
+///
+/// ```
+/// use serde_hooks::{ser, Path};
+///
+/// struct Redact;
+/// impl ser::DynHooks for Redact {
+///     fn on_value(&self, path: &Path, value: &mut ser::DynValueScope) {
+///         if path == "[1]" {
+///             value.replace("***");
+///         }
+///     }
+/// }
+///
+/// let hooks = ser::BoxedHooks::new(Redact);
+/// let json = serde_json::to_string(&ser::hook(&["user", "secret"], &hooks)).unwrap();
+/// assert_eq!(json, r#"["user","***"]"#);
+/// ```
+#[allow(unused_variables)]
+pub trait DynHooks {
+    /// See [`Hooks::on_start`].
+    fn on_start(&self, start: &mut StartScope) {}
+
+    /// See [`Hooks::on_value`].
+    fn on_value(&self, path: &Path, value: &mut DynValueScope) {}
+
+    /// See [`Hooks::on_struct`].
+    fn on_struct(&self, path: &Path, st: &mut StructScope) {}
+
+    /// See [`Hooks::on_struct_end`].
+    fn on_struct_end(&self, path: &Path, emitted_len: usize) {}
+
+    /// See [`Hooks::on_unit_struct`].
+    fn on_unit_struct(&self, path: &Path, us: &mut UnitStructScope) {}
+
+    /// See [`Hooks::on_newtype_struct`].
+    fn on_newtype_struct(&self, path: &Path, nt: &mut NewtypeStructScope) {}
+
+    /// See [`Hooks::on_seq`].
+    fn on_seq(&self, path: &Path, seq: &mut SeqScope) {}
+
+    /// See [`Hooks::on_seq_element_end`].
+    fn on_seq_element_end(&self, path: &Path, index: usize) {}
+
+    /// See [`Hooks::on_tuple`].
+    fn on_tuple(&self, path: &Path, tpl: &mut TupleScope, seq: &mut SeqScope) {}
+
+    /// See [`Hooks::on_tuple_struct`].
+    fn on_tuple_struct(&self, path: &Path, tpl: &mut TupleStructScope, seq: &mut SeqScope) {}
+
+    /// See [`Hooks::on_map`].
+    fn on_map(&self, path: &Path, map: &mut MapScope) {}
+
+    /// See [`Hooks::on_map_entry_end`].
+    fn on_map_entry_end(&self, path: &Path, key: &Value, index: usize) {}
+
+    /// See [`Hooks::on_enum_variant`].
+    fn on_enum_variant(&self, path: &Path, ev: &mut EnumVariantScope) {}
+
+    /// See [`Hooks::on_struct_variant`].
+    fn on_struct_variant(&self, path: &Path, ev: &mut EnumVariantScope, st: &mut StructScope) {}
+
+    /// See [`Hooks::on_tuple_variant`].
+    fn on_tuple_variant(
+        &self,
+        path: &Path,
+        ev: &mut EnumVariantScope,
+        tpl: &mut TupleScope,
+        seq: &mut SeqScope,
+    ) {
+    }
+
+    /// See [`Hooks::on_scope_error`].
+    fn on_scope_error(&self, path: &Path, err: &mut ErrorScope) {}
+}
+
+/// Boxed [`DynHooks`], usable wherever [`Hooks`] are expected.
+pub struct BoxedHooks(Box<dyn DynHooks>);
+
+impl BoxedHooks {
+    /// Boxes the given hooks.
+    pub fn new(hooks: impl DynHooks + 'static) -> Self {
+        Self(Box::new(hooks))
+    }
+}
+
+impl From<Box<dyn DynHooks>> for BoxedHooks {
+    fn from(hooks: Box<dyn DynHooks>) -> Self {
+        Self(hooks)
+    }
+}
+
+impl Hooks for BoxedHooks {
+    fn on_start(&self, start: &mut StartScope) {
+        self.0.on_start(start);
+    }
+
+    fn on_value<S: Serializer>(&self, path: &Path, value: &mut ValueScope<S>) {
+        self.0.on_value(path, &mut DynValueScope { scope: value });
+    }
+
+    fn on_struct(&self, path: &Path, st: &mut StructScope) {
+        self.0.on_struct(path, st);
+    }
+
+    fn on_struct_end(&self, path: &Path, emitted_len: usize) {
+        self.0.on_struct_end(path, emitted_len);
+    }
+
+    fn on_unit_struct(&self, path: &Path, us: &mut UnitStructScope) {
+        self.0.on_unit_struct(path, us);
+    }
+
+    fn on_newtype_struct(&self, path: &Path, nt: &mut NewtypeStructScope) {
+        self.0.on_newtype_struct(path, nt);
+    }
+
+    fn on_seq(&self, path: &Path, seq: &mut SeqScope) {
+        self.0.on_seq(path, seq);
+    }
+
+    fn on_seq_element_end(&self, path: &Path, index: usize) {
+        self.0.on_seq_element_end(path, index);
+    }
+
+    fn on_tuple(&self, path: &Path, tpl: &mut TupleScope, seq: &mut SeqScope) {
+        self.0.on_tuple(path, tpl, seq);
+    }
+
+    fn on_tuple_struct(&self, path: &Path, tpl: &mut TupleStructScope, seq: &mut SeqScope) {
+        self.0.on_tuple_struct(path, tpl, seq);
+    }
+
+    fn on_map(&self, path: &Path, map: &mut MapScope) {
+        self.0.on_map(path, map);
+    }
+
+    fn on_map_entry_end(&self, path: &Path, key: &Value, index: usize) {
+        self.0.on_map_entry_end(path, key, index);
+    }
+
+    fn on_enum_variant(&self, path: &Path, ev: &mut EnumVariantScope) {
+        self.0.on_enum_variant(path, ev);
+    }
+
+    fn on_struct_variant(&self, path: &Path, ev: &mut EnumVariantScope, st: &mut StructScope) {
+        self.0.on_struct_variant(path, ev, st);
+    }
+
+    fn on_tuple_variant(
+        &self,
+        path: &Path,
+        ev: &mut EnumVariantScope,
+        tpl: &mut TupleScope,
+        seq: &mut SeqScope,
+    ) {
+        self.0.on_tuple_variant(path, ev, tpl, seq);
+    }
+
+    fn on_scope_error(&self, path: &Path, err: &mut ErrorScope) {
+        self.0.on_scope_error(path, err);
+    }
+}
+
+/// Inspect and modify serialized values in [`DynHooks`].
+///
+/// This is a [`ValueScope`] with the serializer type erased. Since the serializer is
+/// not known, values can only be replaced with a [`Value`], which, like with
+/// [`StructScope::replace_value`](crate::ser::StructScope::replace_value), must be primitive.
+pub struct DynValueScope<'a> {
+    scope: &'a mut dyn ErasedValueScope,
+}
+
+impl DynValueScope<'_> {
+    /// See [`ValueScope::value`].
+    pub fn value(&self) -> &Value<'_> {
+        self.scope.value()
+    }
+
+    /// See [`ValueScope::set_annotation`].
+    pub fn set_annotation(&mut self, key: &str, value: &str) -> &mut Self {
+        self.scope.set_annotation(key, value);
+        self
+    }
+
+    /// See [`ValueScope::fail_serialization`].
+    pub fn fail_serialization(&mut self, error: impl std::fmt::Display) {
+        self.scope.fail_serialization(error.to_string());
+    }
+
+    /// Replace the value with another primitive value.
+    ///
+    /// See [`ValueScope::replace`].
+    pub fn replace<'n>(&mut self, new_value: impl Into<Value<'n>>) -> &mut Self {
+        self.scope.replace(&new_value.into());
+        self
+    }
+
+    /// See [`ValueScope::skip`].
+    pub fn skip(&mut self) -> &mut Self {
+        self.scope.skip();
+        self
+    }
+}
+
+trait ErasedValueScope {
+    fn value(&self) -> &Value<'_>;
+    fn set_annotation(&mut self, key: &str, value: &str);
+    fn fail_serialization(&mut self, error: String);
+    fn replace(&mut self, new_value: &Value);
+    fn skip(&mut self);
+}
+
+impl<S: Serializer> ErasedValueScope for ValueScope<'_, S> {
+    fn value(&self) -> &Value<'_> {
+        ValueScope::value(self)
+    }
+
+    fn set_annotation(&mut self, key: &str, value: &str) {
+        ValueScope::set_annotation(self, key, value);
+    }
+
+    fn fail_serialization(&mut self, error: String) {
+        ValueScope::fail_serialization(self, error);
+    }
+
+    fn replace(&mut self, new_value: &Value) {
+        ValueScope::replace(self, new_value);
+    }
+
+    fn skip(&mut self) {
+        ValueScope::skip(self);
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use crate::{Path, PathSegment, Value};
+
+/// Collects the shape of the serialized data, see
+/// [`StartScope::enable_schema_fingerprint`](crate::ser::StartScope::enable_schema_fingerprint).
+///
+/// Every value is recorded as its path with sequence indices and map keys erased,
+/// followed by the type of the value. Records are kept in a sorted set, so that
+/// the fingerprint does not depend on sequence lengths or map iteration order.
+#[derive(Debug, Default)]
+pub(crate) struct SchemaFingerprint {
+    records: BTreeSet<String>,
+    buffer: String,
+}
+
+impl SchemaFingerprint {
+    pub(crate) fn record_value(&mut self, path: &Path, value: &Value) {
+        self.record(path, "", value);
+    }
+
+    pub(crate) fn record_map_key(&mut self, path: &Path, value: &Value) {
+        self.record(path, "key ", value);
+    }
+
+    fn record(&mut self, path: &Path, prefix: &str, value: &Value) {
+        self.buffer.clear();
+        for segment in path.segments() {
+            match segment {
+                PathSegment::StructField(name) => {
+                    self.buffer.push('.');
+                    self.buffer.push_str(name);
+                }
+                PathSegment::SeqElement(_) | PathSegment::MapEntry(_) => {
+                    self.buffer.push_str("[]");
+                }
+            }
+        }
+        self.buffer.push_str(": ");
+        self.buffer.push_str(prefix);
+        write_value_type(&mut self.buffer, value);
+
+        if !self.records.contains(&self.buffer) {
+            self.records.insert(self.buffer.clone());
+        }
+    }
+
+    /// Returns the FNV-1a hash of all records.
+    pub(crate) fn finish(&self) -> u64 {
+        const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const PRIME: u64 = 0x100000001b3;
+
+        self.records
+            .iter()
+            .flat_map(|record| record.bytes().chain(std::iter::once(b'\n')))
+            .fold(OFFSET_BASIS, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(PRIME)
+            })
+    }
+}
+
+/// Writes the type of the value, leaving out anything that depends on the actual data,
+/// like primitive values, lengths of sequences and maps, or selected enum variants.
+fn write_value_type(out: &mut String, value: &Value) {
+    let _ = match value {
+        Value::Bool(_) => out.write_str("bool"),
+        Value::I8(_) => out.write_str("i8"),
+        Value::I16(_) => out.write_str("i16"),
+        Value::I32(_) => out.write_str("i32"),
+        Value::I64(_) => out.write_str("i64"),
+        Value::I128(_) => out.write_str("i128"),
+        Value::U8(_) => out.write_str("u8"),
+        Value::U16(_) => out.write_str("u16"),
+        Value::U32(_) => out.write_str("u32"),
+        Value::U64(_) => out.write_str("u64"),
+        Value::U128(_) => out.write_str("u128"),
+        Value::F32(_) => out.write_str("f32"),
+        Value::F64(_) => out.write_str("f64"),
+        Value::Char(_) => out.write_str("char"),
+        Value::Str(_) => out.write_str("str"),
+        Value::Bytes(_) => out.write_str("bytes"),
+        Value::Unit => out.write_str("unit"),
+        Value::Some | Value::None => out.write_str("option"),
+        Value::UnitStruct(name) => write!(out, "unit struct {name}"),
+        Value::NewtypeStruct(name) => write!(out, "newtype {name}"),
+        Value::UnitVariant { name, .. }
+        | Value::NewtypeVariant { name, .. }
+        | Value::TupleVariant { name, .. }
+        | Value::StructVariant { name, .. } => write!(out, "enum {name}"),
+        Value::Seq(_) => out.write_str("seq"),
+        Value::Tuple(len) => write!(out, "tuple {len}"),
+        Value::TupleStruct { name, .. } => write!(out, "tuple struct {name}"),
+        Value::Map(_) => out.write_str("map"),
+        Value::Struct { name, .. } => write!(out, "struct {name}"),
+    };
+}
@@ -0,0 +1,95 @@
+use crate::glob::{Glob, GlobSet, GlobStates};
+use crate::{Path, PathSegment};
+
+/// A set of path glob patterns, precompiled for fast matching.
+///
+/// Patterns use the same syntax as path strings (see [`Path::borrow_str`]):
+/// struct fields are separated with dots, sequence elements and map entries
+/// are written in square brackets. In addition, the following wildcards are supported:
+/// - `*` matches any single segment,
+/// - `[*]` matches any single sequence element or map entry,
+/// - `**` matches any number of segments, including none.
+///
+/// For example, `users[*].password` matches `users[0].password` and `users[1].password`,
+/// and `**.password` matches `password` field on any level.
+///
+/// The patterns are compiled into a single automaton that is advanced one
+/// path segment at a time during serialization, so that the cost of matching does not
+/// grow with path depth and does not involve any string formatting.
+///
+/// Use [`CompiledMatchers::builder`] to create, and pass to
+/// [`HookOptions::only_matching`](crate::ser::HookOptions::only_matching) to limit
+/// hook calls to matching paths.
+#[derive(Debug, Clone)]
+pub struct CompiledMatchers {
+    globs: GlobSet,
+}
+
+impl CompiledMatchers {
+    /// Creates a builder for a set of patterns.
+    pub fn builder() -> CompiledMatchersBuilder {
+        CompiledMatchersBuilder { globs: Vec::new() }
+    }
+
+    /// Returns `true` if any of the patterns matches the path.
+    pub fn is_match(&self, path: &Path) -> bool {
+        self.globs.is_match(&self.states_for(path))
+    }
+
+    /// Returns indices of patterns that match the path, in the order they were added to the builder.
+    pub fn matching_patterns(&self, path: &Path) -> Vec<usize> {
+        let mut indices: Vec<usize> = self.globs.matching_globs(&self.states_for(path)).collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    fn states_for(&self, path: &Path) -> GlobStates {
+        path.segments()
+            .iter()
+            .fold(self.initial_states(), |states, segment| {
+                self.next_states(&states, segment)
+            })
+    }
+
+    pub(crate) fn initial_states(&self) -> GlobStates {
+        self.globs.initial_states()
+    }
+
+    pub(crate) fn next_states(&self, states: &GlobStates, segment: &PathSegment) -> GlobStates {
+        self.globs.next_states(states, segment)
+    }
+
+    pub(crate) fn is_match_in(&self, states: &GlobStates) -> bool {
+        self.globs.is_match(states)
+    }
+}
+
+/// Builder for [`CompiledMatchers`].
+#[derive(Debug, Clone, Default)]
+pub struct CompiledMatchersBuilder {
+    globs: Vec<Glob>,
+}
+
+impl CompiledMatchersBuilder {
+    /// Adds a glob pattern to the set.
+    ///
+    /// See [`CompiledMatchers`] for the pattern syntax.
+    pub fn pattern(mut self, pattern: impl AsRef<str>) -> Self {
+        self.globs.push(Glob::new(pattern.as_ref()));
+        self
+    }
+
+    /// Adds multiple glob patterns to the set.
+    pub fn patterns<P: AsRef<str>>(mut self, patterns: impl IntoIterator<Item = P>) -> Self {
+        self.globs
+            .extend(patterns.into_iter().map(|p| Glob::new(p.as_ref())));
+        self
+    }
+
+    /// Compiles the patterns.
+    pub fn build(self) -> CompiledMatchers {
+        CompiledMatchers {
+            globs: GlobSet::new(self.globs),
+        }
+    }
+}
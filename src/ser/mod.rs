@@ -4,20 +4,36 @@ use std::borrow::Cow;
 
 use serde::{Serialize, Serializer};
 
+mod chain;
 mod context;
+mod dynamic;
+mod fingerprint;
+mod matchers;
+mod options;
+mod recording;
 mod scope;
+mod scoped;
+pub mod testing;
 mod value;
 mod void_serializer;
 mod wrapper;
 
 pub use scope::{
-    EndScope, EnumVariantScope, ErrorScope, MapInsertLocation, MapKeyScope, MapKeySelector,
-    MapScope, SeqScope, StartScope, StructScope, TupleScope, TupleStructScope, ValueScope,
+    BytesScope, EndScope, EnumVariantScope, ErrorScope, FormatHint, MapInsertLocation, MapKeyScope,
+    MapKeySelector, MapScope, NewtypeStructScope, OptionScope, SeqInsertLocation, SeqScope,
+    StartScope, StructScope, TupleScope, TupleStructScope, UnitStructScope, ValueScope,
 };
 
-use context::SerializableWithContext;
+#[cfg(feature = "unicode-normalization")]
+pub use scope::UnicodeForm;
 
-use crate::Path;
+pub use dynamic::{BoxedHooks, DynHooks, DynValueScope};
+pub use matchers::{CompiledMatchers, CompiledMatchersBuilder};
+pub use options::{BytesEncoding, FloatPolicy, HookBuilder, HookOptions};
+pub use recording::RecordingHooks;
+pub use scoped::{scoped, ScopedHooks};
+
+use crate::{Path, Value};
 
 /// A collection of callback functions (hooks) that are called at specific times during serialization.
 ///
@@ -26,6 +42,14 @@ use crate::Path;
 /// Most hooks take a [`path`](Path) parameter which allows you to figure out where in the
 /// serialized data you're called back from.
 pub trait Hooks {
+    /// Whether the hooks are active.
+    ///
+    /// Set this to `false` only for hooks that implement no callbacks, like [`NoHooks`].
+    /// Inactive hooks let the serializable value be fed directly to the serializer, without
+    /// tracking paths or capturing values, unless [`HookOptions`] that change the serialized
+    /// data, like [`bytes_encoding`](HookOptions::bytes_encoding), are used.
+    const ACTIVE: bool = true;
+
     /// Called at the beginning of serialization, before any serializer calls are made.
     #[allow(unused_variables)]
     fn on_start(&self, start: &mut StartScope) {}
@@ -48,6 +72,32 @@ pub trait Hooks {
     #[allow(unused_variables)]
     fn on_value<S: Serializer>(&self, path: &Path, value: &mut ValueScope<S>) {}
 
+    /// Called before a byte array is serialized.
+    ///
+    /// You can use the passed in scope to inspect the bytes and e.g. replace them with
+    /// a hex or base64 encoded string.
+    ///
+    /// This hook will be preceded with a call to [`on_value`](Self::on_value) at the same path,
+    /// and is not called if the value was replaced there. It is not called for map keys.
+    ///
+    /// Note that serde serializes `Vec<u8>` and `&[u8]` as sequences, this hook is only called
+    /// for values serialized with [`Serializer::serialize_bytes`], e.g. with
+    /// [`serde_bytes`](https://docs.rs/serde_bytes).
+    #[allow(unused_variables)]
+    fn on_bytes<S: Serializer>(&self, path: &Path, bytes: &mut BytesScope<S>) {}
+
+    /// Called before an optional value, `None` or `Some`, is serialized.
+    ///
+    /// You can use the passed in scope to check whether the value is present, and e.g. skip
+    /// `None` values, which requires
+    /// [`HookOptions::allow_skipping_values`].
+    ///
+    /// This hook will be preceded with a call to [`on_value`](Self::on_value) at the same path,
+    /// and is not called if the value was replaced or skipped there. It is not called for map keys.
+    /// For `Some`, this hook is followed by the hooks for the contained value.
+    #[allow(unused_variables)]
+    fn on_option<S: Serializer>(&self, path: &Path, opt: &mut OptionScope<S>) {}
+
     /// Called before a struct is serialized.
     ///
     /// Using the scope passed in, you can modify the struct by e.g. renaming or skipping
@@ -57,6 +107,35 @@ pub trait Hooks {
     #[allow(unused_variables)]
     fn on_struct(&self, path: &Path, st: &mut StructScope) {}
 
+    /// Called after a struct or a struct variant is serialized.
+    ///
+    /// The `emitted_len` is the number of fields actually fed to the serializer, after the
+    /// [`StructScope`] actions were applied and skipped values were dropped. Unlike
+    /// [`StructScope::struct_len`], it accounts for skipped, retained and inserted fields,
+    /// as well as for the tag of internally tagged variants. A flattened field is counted
+    /// once, regardless of how many entries it contributes.
+    #[allow(unused_variables)]
+    fn on_struct_end(&self, path: &Path, emitted_len: usize) {}
+
+    /// Called before a unit struct is serialized.
+    ///
+    /// Using the scope passed in, you can rename the struct. To serialize a different value
+    /// instead, replace it in [`on_value`](Self::on_value).
+    ///
+    /// This hook will be preceded with a call to [`on_value`](Self::on_value) at the same path,
+    /// and is not called if the value was replaced or skipped there.
+    #[allow(unused_variables)]
+    fn on_unit_struct(&self, path: &Path, us: &mut UnitStructScope) {}
+
+    /// Called before a newtype struct is serialized.
+    ///
+    /// Using the scope passed in, you can unwrap the newtype, or rename it.
+    ///
+    /// This hook will be preceded with a call to [`on_value`](Self::on_value) at the same path,
+    /// and is not called if the value was replaced or skipped there.
+    #[allow(unused_variables)]
+    fn on_newtype_struct(&self, path: &Path, nt: &mut NewtypeStructScope) {}
+
     /// Called before a sequence is serialized.
     ///
     /// Using the scope passed in, you can modify the sequence by e.g. skipping or replacing
@@ -72,6 +151,18 @@ pub trait Hooks {
     #[allow(unused_variables)]
     fn on_seq(&self, path: &Path, seq: &mut SeqScope) {}
 
+    /// Called after a sequence element is serialized.
+    ///
+    /// The `path` is the path of the element, and `index` is its original index in the
+    /// sequence. This is called for every element fed by the sequence to the serializer,
+    /// including the ones skipped by the [`SeqScope`] actions, but not for inserted ones.
+    /// It complements [`on_value`](Self::on_value), which is called before the element
+    /// is serialized, e.g. for progress reporting.
+    ///
+    /// This hook is also called for elements of tuples, tuple structs and tuple variants.
+    #[allow(unused_variables)]
+    fn on_seq_element_end(&self, path: &Path, index: usize) {}
+
     /// Called before a tuple is serialized.
     ///
     /// This hook will be preceded with several other hook calls at the same path,
@@ -120,6 +211,18 @@ pub trait Hooks {
     #[allow(unused_variables)]
     fn on_map_key<S: Serializer>(&self, path: &Path, map_key: &mut MapKeyScope<S>) {}
 
+    /// Called after a map entry is serialized.
+    ///
+    /// The `path` is the path of the entry, `key` is the key of the entry after it was
+    /// renamed or replaced by the [`MapScope`] actions, and `index` is the original index
+    /// of the entry in the map. This is not called for skipped or inserted entries, which
+    /// allows detecting the keys that were actually emitted.
+    ///
+    /// Keys replaced in [`on_map_key`](Self::on_map_key) are passed as they were before
+    /// the replacement.
+    #[allow(unused_variables)]
+    fn on_map_entry_end(&self, path: &Path, key: &Value, index: usize) {}
+
     /// Called before an enum variant of any kind is serialized.
     ///
     /// Using the scope passed in, you can modify the variant by e.g. renaming it.
@@ -220,6 +323,16 @@ pub trait Hooks {
     fn on_scope_error(&self, path: &Path, err: &mut ErrorScope) {}
 }
 
+/// Hooks that do nothing.
+///
+/// Serializing with these hooks has no overhead, see [`Hooks::ACTIVE`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoHooks;
+
+impl Hooks for NoHooks {
+    const ACTIVE: bool = false;
+}
+
 /// Kinds of recoverable errors.
 ///
 /// See [`Hooks::on_scope_error`] for more info on handling recoverable errors.
@@ -240,10 +353,14 @@ pub enum HooksError {
     #[error("field \"{0}\" not found")]
     FieldNotFound(Cow<'static, str>),
 
+    /// There is no field at this index in the struct.
+    #[error("field index {0} not found")]
+    FieldIndexNotFound(usize),
+
     /// This value is not serializable.
     ///
     /// This error occurs when trying to replace a value using compound scope methods
-    /// (e.g. on a struct or map scope), and passing in a [`Value`](crate::Value) that is non-primitive.
+    /// (e.g. on a struct or map scope), and passing in a [`Value`] that is non-primitive.
     /// Non-primitive values, like structs, maps, tuples, are represented by their
     /// metadata, which is obviously not sufficient to serialize them.
     #[error("value is not serializable: {0}")]
@@ -256,6 +373,75 @@ pub enum HooksError {
     /// Flattening was attempted on a struct field that does not contain a struct or a map.
     #[error("cannot flatten unsupported data type \"{0}\"")]
     CannotFlattenUnsupportedDataType(&'static str),
+
+    /// The value cannot be skipped.
+    ///
+    /// This error occurs when calling [`ValueScope::skip`] on a value that is not a struct field,
+    /// a sequence element or a map value (e.g. the root value), or when skipping was not allowed
+    /// with [`HookOptions::allow_skipping_values`]. If the error is ignored, the value is serialized.
+    #[error("value cannot be skipped")]
+    CannotSkipValue,
+
+    /// This key occurs in the map more than once.
+    ///
+    /// This error occurs when an entry inserted with
+    /// [`MapScope::insert_entry_checked`] has the same key as another entry in the map.
+    #[error("duplicate key {0}")]
+    DuplicateKey(MapKeySelector),
+
+    /// The value is nested deeper than the given maximum depth.
+    ///
+    /// See [`HookOptions::max_depth`]. If the error is ignored, serialization continues
+    /// at this depth and the error is reported again for each deeper value.
+    #[error("maximum depth {0} exceeded")]
+    MaxDepthExceeded(usize),
+}
+
+impl HooksError {
+    /// Returns a stable machine-readable code of the error kind.
+    ///
+    /// Unlike the `Display` output, codes do not contain any details and are not
+    /// going to change, so they can be used to branch on in
+    /// [`Hooks::on_scope_error`] without matching error messages.
+    ///
+    /// ```
+    /// # use serde_hooks::{ser, Path};
+    /// struct Hooks;
+    /// impl ser::Hooks for Hooks {
+    ///     fn on_scope_error(&self, _path: &Path, err: &mut ser::ErrorScope) {
+    ///         if err.error().code() == "field_not_found" {
+    ///             err.ignore();
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            HooksError::CannotAddEntryByIndex(_) => "cannot_add_entry_by_index",
+            HooksError::KeyNotFound(_) => "key_not_found",
+            HooksError::FieldNotFound(_) => "field_not_found",
+            HooksError::FieldIndexNotFound(_) => "field_index_not_found",
+            HooksError::ValueNotSerializable(_) => "value_not_serializable",
+            HooksError::IndexNotFound(_) => "index_not_found",
+            HooksError::CannotFlattenUnsupportedDataType(_) => {
+                "cannot_flatten_unsupported_data_type"
+            }
+            HooksError::CannotSkipValue => "cannot_skip_value",
+            HooksError::DuplicateKey(_) => "duplicate_key",
+            HooksError::MaxDepthExceeded(_) => "max_depth_exceeded",
+        }
+    }
+
+    /// Whether the error reports a scope action that matched nothing.
+    pub(crate) fn is_unmatched_action(&self) -> bool {
+        matches!(
+            self,
+            HooksError::KeyNotFound(_)
+                | HooksError::FieldNotFound(_)
+                | HooksError::FieldIndexNotFound(_)
+                | HooksError::IndexNotFound(_)
+        )
+    }
 }
 
 /// Attach serialization hooks to a serializable value.
@@ -315,7 +501,83 @@ pub fn hook<'s, 'h: 's, T: Serialize + ?Sized, H: Hooks>(
     serializable: &'s T,
     hooks: &'h H,
 ) -> impl Serialize + 's {
-    SerializableWithContext::new(serializable, hooks)
+    HookBuilder::new(hooks).wrap(serializable)
+}
+
+/// Attach hooks to a serializable struct, with additional options.
+///
+/// Same as [`hook`], but allows tuning the behavior with [`HookOptions`]. See also [`HookBuilder`],
+/// which allows setting the options with chainable calls.
+///
+/// ```
+/// use serde::Serialize;
+/// use serde_hooks::{ser, Path};
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     name: String,
+///     password: String,
+/// }
+///
+/// struct RedactHooks;
+///
+/// impl ser::Hooks for RedactHooks {
+///     // Only called for paths matching the patterns below.
+///     fn on_value<S: serde::Serializer>(&self, _path: &Path, value: &mut ser::ValueScope<S>) {
+///         value.replace("***");
+///     }
+/// }
+///
+/// let users = vec![User {
+///     name: "John Doe".into(),
+///     password: "AKJHDKSHD".into(),
+/// }];
+///
+/// let options = ser::HookOptions::new().only_matching(
+///     ser::CompiledMatchers::builder()
+///         .pattern("[*].password")
+///         .pattern("**.secret")
+///         .build(),
+/// );
+///
+/// let json =
+///     serde_json::to_string(&ser::hook_with_options(&users, &RedactHooks, options)).unwrap();
+///
+/// assert_eq!(json, r#"[{"name":"John Doe","password":"***"}]"#);
+/// ```
+pub fn hook_with_options<'s, 'h: 's, T: Serialize + ?Sized, H: Hooks>(
+    serializable: &'s T,
+    hooks: &'h H,
+    options: HookOptions,
+) -> impl Serialize + 's {
+    HookBuilder::new(hooks).options(options).wrap(serializable)
+}
+
+/// Attach hooks to a serializable struct, tagging the serialization format.
+///
+/// Same as [`hook`], but the `format` tag is available to the hooks with
+/// [`StartScope::format_hint`]. This allows the same hooks to behave differently
+/// depending on the format, which serde gives no way to detect.
+///
+/// ```
+/// use serde_hooks::ser;
+///
+/// struct Hooks;
+///
+/// impl ser::Hooks for Hooks {
+///     fn on_start(&self, start: &mut ser::StartScope) {
+///         assert_eq!(start.format_hint(), ser::FormatHint::Tagged("json"));
+///     }
+/// }
+///
+/// serde_json::to_string(&ser::hook_with_format(&(), &Hooks, "json")).unwrap();
+/// ```
+pub fn hook_with_format<'s, 'h: 's, T: Serialize + ?Sized, H: Hooks>(
+    serializable: &'s T,
+    hooks: &'h H,
+    format: impl Into<Cow<'static, str>>,
+) -> impl Serialize + 's {
+    hook_with_options(serializable, hooks, HookOptions::new().format(format))
 }
 
 /// Invoke hooks on a serializable value.
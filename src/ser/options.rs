@@ -0,0 +1,370 @@
+use std::borrow::Cow;
+
+use serde::Serialize;
+
+use super::{context::SerializableWithContext, CompiledMatchers, Hooks};
+use crate::StaticValue;
+
+/// Options for [`hook_with_options`](crate::ser::hook_with_options).
+#[derive(Debug, Clone, Default)]
+pub struct HookOptions {
+    pub(crate) matchers: Option<CompiledMatchers>,
+    pub(crate) allow_skipping_values: bool,
+    pub(crate) allow_expanding_elements: bool,
+    pub(crate) format: Option<Cow<'static, str>>,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) capture_byte_keys: bool,
+    pub(crate) bytes_encoding: BytesEncoding,
+    pub(crate) float_policy: FloatPolicy,
+    pub(crate) strict: bool,
+    pub(crate) static_str_capacity: usize,
+}
+
+impl HookOptions {
+    /// Creates default options, same as used by [`hook`](crate::ser::hook).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only call hooks for paths that match any of the given patterns.
+    ///
+    /// For paths that do not match, serialization proceeds as if no hooks were
+    /// implemented, with the exception of [`on_start`](crate::ser::Hooks::on_start),
+    /// [`on_end`](crate::ser::Hooks::on_end) and [`on_scope_error`](crate::ser::Hooks::on_scope_error),
+    /// which are always called.
+    ///
+    /// This is an optimization for hooks that would otherwise compare the path
+    /// against many rules on every call.
+    pub fn only_matching(mut self, matchers: CompiledMatchers) -> Self {
+        self.matchers = Some(matchers);
+        self
+    }
+
+    /// Allow skipping values with [`ValueScope::skip`](crate::ser::ValueScope::skip).
+    ///
+    /// By the time [`on_value`](crate::ser::Hooks::on_value) is called, the serializer
+    /// has usually been told about the value already (e.g. a comma and a key have been written).
+    /// To be able to skip values, struct fields, sequence elements and map values are
+    /// first captured into an intermediate buffer, and only fed to the serializer if
+    /// they were not skipped. This has a significant performance cost, so it's disabled by default.
+    ///
+    /// Sequence and map length hints are not given to the serializer when skipping is allowed,
+    /// and tuples are serialized as sequences, since their length can change.
+    pub fn allow_skipping_values(mut self) -> Self {
+        self.allow_skipping_values = true;
+        self
+    }
+
+    /// Allow expanding sequence elements with
+    /// [`ValueScope::expand_into_elements`](crate::ser::ValueScope::expand_into_elements).
+    ///
+    /// The serializer is told the length of a sequence before its elements are serialized,
+    /// so it's too late to change it when an element gets expanded. When expanding is allowed,
+    /// sequence length hints are not given to the serializer, and tuples are serialized as
+    /// sequences, same as when skipping values is allowed.
+    pub fn allow_expanding_elements(mut self) -> Self {
+        self.allow_expanding_elements = true;
+        self
+    }
+
+    /// Tag the serialization format, see [`StartScope::format_hint`](crate::ser::StartScope::format_hint).
+    pub fn format(mut self, format: impl Into<Cow<'static, str>>) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    /// Limit how deep the serialized value can be nested.
+    ///
+    /// When the path depth exceeds `max_depth`, a
+    /// [`HooksError::MaxDepthExceeded`](crate::ser::HooksError::MaxDepthExceeded) error is
+    /// reported to [`on_scope_error`](crate::ser::Hooks::on_scope_error), which fails
+    /// serialization unless ignored. This protects against stack overflows when serializing
+    /// untrusted, deeply nested data. By default, depth is not limited.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Encode all byte values as strings.
+    ///
+    /// Byte values, serialized with [`Serializer::serialize_bytes`](serde::Serializer::serialize_bytes),
+    /// e.g. with [`serde_bytes`](https://docs.rs/serde_bytes), are replaced with strings in the given
+    /// encoding, which is useful for formats like JSON that have no byte strings. The encoding
+    /// is applied after [`on_bytes`](crate::ser::Hooks::on_bytes) hooks, and only if the bytes
+    /// were not replaced there. Map keys are not encoded.
+    ///
+    /// Defaults to [`BytesEncoding::Raw`], i.e. bytes are passed to the serializer as is.
+    pub fn bytes_encoding(mut self, encoding: BytesEncoding) -> Self {
+        self.bytes_encoding = encoding;
+        self
+    }
+
+    /// Set how non-finite float values, `NaN` and infinities, are serialized.
+    ///
+    /// Many formats, e.g. JSON, can't represent non-finite floats, and serializers either fail
+    /// or silently change such values. The policy is applied to `f32` and `f64` values after
+    /// [`on_value`](crate::ser::Hooks::on_value) hooks, and only if the value was not replaced
+    /// there. Map keys are not affected.
+    ///
+    /// Defaults to [`FloatPolicy::Passthrough`].
+    pub fn float_policy(mut self, policy: FloatPolicy) -> Self {
+        self.float_policy = policy;
+        self
+    }
+
+    /// Capture the contents of byte string map keys.
+    ///
+    /// By default, map keys serialized as bytes are captured as an empty
+    /// [`Value::Bytes`](crate::Value::Bytes), to avoid allocation on every map key.
+    /// With this option, the actual bytes are copied into the captured value, so that
+    /// such entries can be selected with [`MapKeySelector::ByValue`](crate::ser::MapKeySelector::ByValue),
+    /// and are visible in [`PathMapKey::value`](crate::PathMapKey::value).
+    pub fn capture_byte_keys(mut self) -> Self {
+        self.capture_byte_keys = true;
+        self
+    }
+
+    /// Fail serialization on scope actions that matched nothing.
+    ///
+    /// Actions that refer to fields, keys or indices that are not present, e.g. a
+    /// [`StructScope::retain_field`](crate::ser::StructScope::retain_field) with a misspelled
+    /// name, are reported to [`on_scope_error`](crate::ser::Hooks::on_scope_error) as
+    /// [`FieldNotFound`](crate::ser::HooksError::FieldNotFound),
+    /// [`FieldIndexNotFound`](crate::ser::HooksError::FieldIndexNotFound),
+    /// [`KeyNotFound`](crate::ser::HooksError::KeyNotFound) or
+    /// [`IndexNotFound`](crate::ser::HooksError::IndexNotFound). Normally the hook can ignore
+    /// them. In strict mode, these errors fail serialization even if the hook ignores them.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Reserve space for `capacity` runtime static strings up front.
+    ///
+    /// Renaming fields or variants with owned strings or case conversions turns the new
+    /// names into static strings, see [Static strings](crate::ser#static-strings). These
+    /// are collected in a list that grows as needed. If you know that your hooks produce
+    /// many distinct names, reserving the space avoids repeated reallocation of the list.
+    pub fn static_str_capacity(mut self, capacity: usize) -> Self {
+        self.static_str_capacity = capacity;
+        self
+    }
+
+    /// Whether the options change the serialized data, even if no hooks are called.
+    pub(crate) fn changes_output(&self) -> bool {
+        self.max_depth.is_some()
+            || !matches!(self.bytes_encoding, BytesEncoding::Raw)
+            || !matches!(self.float_policy, FloatPolicy::Passthrough)
+    }
+}
+
+/// Builder for attaching hooks with options.
+///
+/// A chainable alternative to [`hook_with_options`](crate::ser::hook_with_options), each setter
+/// corresponds to a [`HookOptions`] method.
+///
+/// ```
+/// use serde_hooks::ser;
+///
+/// struct Hooks;
+/// impl ser::Hooks for Hooks {}
+///
+/// let json = serde_json::to_string(
+///     &ser::HookBuilder::new(&Hooks)
+///         .bytes_encoding(ser::BytesEncoding::Hex)
+///         .float_policy(ser::FloatPolicy::NullifyNonFinite)
+///         .wrap(&(f64::NAN, serde_bytes::Bytes::new(b"hi"))),
+/// )
+/// .unwrap();
+/// assert_eq!(json, r#"[null,"6869"]"#);
+/// ```
+pub struct HookBuilder<'h, H: Hooks> {
+    hooks: &'h H,
+    options: HookOptions,
+}
+
+impl<'h, H: Hooks> HookBuilder<'h, H> {
+    /// Creates a builder for the given hooks, with default options.
+    pub fn new(hooks: &'h H) -> Self {
+        Self {
+            hooks,
+            options: HookOptions::default(),
+        }
+    }
+
+    /// Replaces all the options set so far.
+    pub fn options(mut self, options: HookOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// See [`HookOptions::only_matching`].
+    pub fn only_matching(mut self, matchers: CompiledMatchers) -> Self {
+        self.options = self.options.only_matching(matchers);
+        self
+    }
+
+    /// See [`HookOptions::allow_skipping_values`].
+    pub fn allow_skipping_values(mut self) -> Self {
+        self.options = self.options.allow_skipping_values();
+        self
+    }
+
+    /// See [`HookOptions::allow_expanding_elements`].
+    pub fn allow_expanding_elements(mut self) -> Self {
+        self.options = self.options.allow_expanding_elements();
+        self
+    }
+
+    /// See [`HookOptions::format`].
+    pub fn format(mut self, format: impl Into<Cow<'static, str>>) -> Self {
+        self.options = self.options.format(format);
+        self
+    }
+
+    /// See [`HookOptions::max_depth`].
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.options = self.options.max_depth(max_depth);
+        self
+    }
+
+    /// See [`HookOptions::bytes_encoding`].
+    pub fn bytes_encoding(mut self, encoding: BytesEncoding) -> Self {
+        self.options = self.options.bytes_encoding(encoding);
+        self
+    }
+
+    /// See [`HookOptions::float_policy`].
+    pub fn float_policy(mut self, policy: FloatPolicy) -> Self {
+        self.options = self.options.float_policy(policy);
+        self
+    }
+
+    /// See [`HookOptions::capture_byte_keys`].
+    pub fn capture_byte_keys(mut self) -> Self {
+        self.options = self.options.capture_byte_keys();
+        self
+    }
+
+    /// See [`HookOptions::strict`].
+    pub fn strict(mut self) -> Self {
+        self.options = self.options.strict();
+        self
+    }
+
+    /// See [`HookOptions::static_str_capacity`].
+    pub fn static_str_capacity(mut self, capacity: usize) -> Self {
+        self.options = self.options.static_str_capacity(capacity);
+        self
+    }
+
+    /// Attach the hooks to a serializable value.
+    ///
+    /// Same as [`hook_with_options`](crate::ser::hook_with_options) with the built options.
+    pub fn wrap<'s, T: Serialize + ?Sized>(self, serializable: &'s T) -> impl Serialize + 's
+    where
+        'h: 's,
+    {
+        SerializableWithContext::new(serializable, self.hooks, self.options)
+    }
+}
+
+/// Handling of non-finite float values, see [`HookOptions::float_policy`].
+#[derive(Debug, Clone, Copy, Default)]
+pub enum FloatPolicy {
+    /// Non-finite values are passed to the serializer as is.
+    #[default]
+    Passthrough,
+    /// Non-finite values are serialized as `None`.
+    NullifyNonFinite,
+    /// Non-finite values are replaced with the value returned by the function.
+    ///
+    /// The function is called with the original value, converted to `f64` for `f32` values.
+    /// As with [`StructScope::replace_value`](crate::ser::StructScope::replace_value), only
+    /// primitive values can be serialized, returning a compound value results in an
+    /// [`HooksError::ValueNotSerializable`](crate::ser::HooksError::ValueNotSerializable) error.
+    Custom(fn(f64) -> StaticValue),
+}
+
+impl FloatPolicy {
+    /// Returns the replacement for the value, or `None` if it is serialized as is.
+    pub(crate) fn replacement(self, value: f64) -> Option<StaticValue> {
+        if value.is_finite() {
+            return None;
+        }
+        match self {
+            FloatPolicy::Passthrough => None,
+            FloatPolicy::NullifyNonFinite => Some(StaticValue::None),
+            FloatPolicy::Custom(f) => Some(f(value)),
+        }
+    }
+}
+
+/// Encoding of byte values, see [`HookOptions::bytes_encoding`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BytesEncoding {
+    /// Bytes are passed to the serializer as is.
+    #[default]
+    Raw,
+    /// Standard base64 encoding with padding, as in [RFC 4648](https://www.rfc-editor.org/rfc/rfc4648#section-4).
+    Base64,
+    /// Lowercase hex encoding.
+    Hex,
+}
+
+impl BytesEncoding {
+    /// Encodes the bytes, or returns `None` for [`BytesEncoding::Raw`].
+    pub(crate) fn encode(self, bytes: &[u8]) -> Option<String> {
+        const BASE64_ALPHABET: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        const HEX_ALPHABET: &[u8; 16] = b"0123456789abcdef";
+
+        match self {
+            BytesEncoding::Raw => None,
+            BytesEncoding::Base64 => {
+                let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+                for chunk in bytes.chunks(3) {
+                    let b = [
+                        chunk[0],
+                        chunk.get(1).copied().unwrap_or(0),
+                        chunk.get(2).copied().unwrap_or(0),
+                    ];
+                    let n = u32::from_be_bytes([0, b[0], b[1], b[2]]);
+                    for i in 0..4 {
+                        if i <= chunk.len() {
+                            let index = (n >> (18 - 6 * i)) & 0x3f;
+                            encoded.push(BASE64_ALPHABET[index as usize] as char);
+                        } else {
+                            encoded.push('=');
+                        }
+                    }
+                }
+                Some(encoded)
+            }
+            BytesEncoding::Hex => {
+                let mut encoded = String::with_capacity(bytes.len() * 2);
+                for b in bytes {
+                    encoded.push(HEX_ALPHABET[(b >> 4) as usize] as char);
+                    encoded.push(HEX_ALPHABET[(b & 0xf) as usize] as char);
+                }
+                Some(encoded)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_bytes_encoding() {
+    let cases: [(&[u8], &str, &str); 5] = [
+        (b"", "", ""),
+        (b"f", "Zg==", "66"),
+        (b"fo", "Zm8=", "666f"),
+        (b"foo", "Zm9v", "666f6f"),
+        (b"\xde\xad\xbe\xef\x00", "3q2+7wA=", "deadbeef00"),
+    ];
+    for (bytes, base64, hex) in cases {
+        assert_eq!(BytesEncoding::Base64.encode(bytes).unwrap(), base64);
+        assert_eq!(BytesEncoding::Hex.encode(bytes).unwrap(), hex);
+        assert_eq!(BytesEncoding::Raw.encode(bytes), None);
+    }
+}
@@ -0,0 +1,60 @@
+use std::cell::{Ref, RefCell};
+
+use serde::Serializer;
+
+use super::{Hooks, ValueScope};
+use crate::{Path, StaticValue};
+
+/// Ready-made hooks that record every serialized value along with its path.
+///
+/// Useful for debugging, or to infer a schema of the serialized data.
+/// Values are recorded in [`on_value`](Hooks::on_value), so for compound values,
+/// like structs, only metadata is recorded, followed by the records of their contents.
+///
+/// ```
+/// use serde::Serialize;
+/// use serde_hooks::{ser, Value};
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     name: &'static str,
+/// }
+///
+/// let hooks = ser::RecordingHooks::new();
+/// ser::invoke_hooks(&User { name: "Alice" }, &hooks).unwrap();
+///
+/// assert_eq!(
+///     hooks.records()[1],
+///     ("name".to_string(), Value::Str("Alice".into()))
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct RecordingHooks {
+    records: RefCell<Vec<(String, StaticValue)>>,
+}
+
+impl RecordingHooks {
+    /// Creates hooks with no records.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded path strings and values, in the order of serialization.
+    pub fn records(&self) -> Ref<'_, [(String, StaticValue)]> {
+        Ref::map(self.records.borrow(), Vec::as_slice)
+    }
+
+    /// Consumes the hooks and returns the recorded path strings and values.
+    pub fn into_records(self) -> Vec<(String, StaticValue)> {
+        self.records.into_inner()
+    }
+}
+
+impl Hooks for RecordingHooks {
+    fn on_value<S: Serializer>(&self, path: &Path, value: &mut ValueScope<S>) {
+        self.records.borrow_mut().push((
+            path.borrow_str().clone(),
+            value.value().clone().into_owned(),
+        ));
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Serialize, Serializer};
+
+use crate::ser::{
+    value::{check_if_can_serialize, is_value},
+    wrapper::ValueAction,
+};
+
+/// Inspect and modify serialized byte arrays.
+///
+/// See [`Hooks::on_bytes`](crate::ser::Hooks::on_bytes).
+pub struct BytesScope<'b, S: Serializer> {
+    action: Option<ValueAction<S>>,
+    bytes: &'b [u8],
+    result: Result<(), String>,
+}
+
+impl<'b, S: Serializer> BytesScope<'b, S> {
+    pub(crate) fn new(serializer: S, bytes: &'b [u8]) -> Self {
+        Self {
+            action: Some(ValueAction::ContinueSerialization(serializer)),
+            bytes,
+            result: Ok(()),
+        }
+    }
+
+    pub(crate) fn into_action(self) -> Result<ValueAction<S>, S::Error> {
+        self.result
+            .map(|_| self.action.unwrap())
+            .map_err(serde::ser::Error::custom)
+    }
+
+    /// Returns the serialized bytes.
+    pub fn bytes(&self) -> &'b [u8] {
+        self.bytes
+    }
+
+    /// Replace the bytes with another serializable value.
+    ///
+    /// Same as [`ValueScope::replace`](crate::ser::ValueScope::replace).
+    ///
+    /// # Panics
+    ///
+    /// The bytes can only be replaced once. This method will panic if the bytes
+    /// have already been replaced.
+    pub fn replace<T: Serialize + ?Sized>(&mut self, new_value: &T) -> &mut Self {
+        let serializer = match self.action.take().unwrap() {
+            ValueAction::ContinueSerialization(s) => s,
+            ValueAction::ValueReplaced(_) | ValueAction::Skipped(_) => {
+                panic!("value already replaced")
+            }
+        };
+
+        if is_value::<T>() {
+            if let Err(err) = check_if_can_serialize(new_value) {
+                self.action = Some(ValueAction::ContinueSerialization(serializer));
+                self.result = Err(err.to_string());
+                return self;
+            }
+        }
+
+        self.action = Some(ValueAction::ValueReplaced(new_value.serialize(serializer)));
+        self
+    }
+
+    /// Replace the bytes with a string, e.g. with the bytes encoded as hex or base64.
+    ///
+    /// # Panics
+    ///
+    /// The bytes can only be replaced once. This method will panic if the bytes
+    /// have already been replaced.
+    pub fn replace_with_str(&mut self, new_value: &str) -> &mut Self {
+        self.replace(new_value)
+    }
+}
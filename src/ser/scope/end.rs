@@ -1,17 +1,45 @@
+use std::collections::BTreeMap;
 use std::pin::Pin;
 
+use crate::ser::HooksError;
+
+/// Counters accumulated during serialization.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Statistics {
+    pub(crate) values_serialized: usize,
+    pub(crate) structs_serialized: usize,
+    pub(crate) maps_serialized: usize,
+    pub(crate) runtime_string_allocations: usize,
+    pub(crate) static_string_bytes: usize,
+}
+
 /// Inspect serialization state after serialization ends.
 ///
 /// See [`Hooks::on_end`](crate::ser::Hooks::on_end).
 pub struct EndScope<'e, Error: serde::ser::Error> {
     static_strs: Vec<Pin<Box<str>>>,
+    ignored_errors: Vec<(String, HooksError)>,
+    statistics: Statistics,
+    annotations: BTreeMap<String, BTreeMap<String, String>>,
+    schema_fingerprint: Option<u64>,
     result: Result<(), &'e Error>,
 }
 
 impl<'e, Error: serde::ser::Error> EndScope<'e, Error> {
-    pub(crate) fn new(static_strs: Vec<Pin<Box<str>>>, result: Result<(), &'e Error>) -> Self {
+    pub(crate) fn new(
+        static_strs: Vec<Pin<Box<str>>>,
+        ignored_errors: Vec<(String, HooksError)>,
+        statistics: Statistics,
+        annotations: BTreeMap<String, BTreeMap<String, String>>,
+        schema_fingerprint: Option<u64>,
+        result: Result<(), &'e Error>,
+    ) -> Self {
         Self {
             static_strs,
+            ignored_errors,
+            statistics,
+            annotations,
+            schema_fingerprint,
             result,
         }
     }
@@ -24,6 +52,79 @@ impl<'e, Error: serde::ser::Error> EndScope<'e, Error> {
         self.result
     }
 
+    /// Returns the number of owned strings that had to be turned into static strings
+    /// during serialization.
+    ///
+    /// Each of these is a runtime allocation caused by a rename to an owned `String`,
+    /// or a case conversion. Repeated names are only allocated once. Passing `&'static str`
+    /// literals for renames instead avoids these.
+    ///
+    /// See [Static strings](crate::ser#static-strings) for more info.
+    pub fn runtime_string_allocations(&self) -> usize {
+        self.statistics.runtime_string_allocations
+    }
+
+    /// Returns the total size in bytes of the strings counted by
+    /// [`runtime_string_allocations`](Self::runtime_string_allocations).
+    pub fn static_strings_allocated(&self) -> usize {
+        self.statistics.static_string_bytes
+    }
+
+    /// Returns the number of values that went through [`on_value`](crate::ser::Hooks::on_value)
+    /// during serialization.
+    ///
+    /// This includes compound values (e.g. the structs themselves along with their fields),
+    /// but not map keys. Values are counted regardless of
+    /// [`HookOptions::only_matching`](crate::ser::HookOptions::only_matching).
+    pub fn values_serialized(&self) -> usize {
+        self.statistics.values_serialized
+    }
+
+    /// Returns the number of structs and struct variants serialized.
+    pub fn structs_serialized(&self) -> usize {
+        self.statistics.structs_serialized
+    }
+
+    /// Returns the number of maps serialized.
+    ///
+    /// Structs serialized as maps (e.g. with
+    /// [`StructScope::serialize_as_map`](crate::ser::StructScope::serialize_as_map))
+    /// are counted as both structs and maps.
+    pub fn maps_serialized(&self) -> usize {
+        self.statistics.maps_serialized
+    }
+
+    /// Returns annotations set by hooks during serialization.
+    ///
+    /// Annotations are keyed by the path string (see [`Path::borrow_str`](crate::Path::borrow_str))
+    /// where they were set, and then by the annotation key. Setting the same annotation key
+    /// at the same path more than once keeps the last value.
+    ///
+    /// See e.g. [`ValueScope::set_annotation`](crate::ser::ValueScope::set_annotation).
+    pub fn annotations(&self) -> &BTreeMap<String, BTreeMap<String, String>> {
+        &self.annotations
+    }
+
+    /// Returns the errors that were ignored in [`on_scope_error`](crate::ser::Hooks::on_scope_error),
+    /// along with the string representations of the paths where they occurred.
+    ///
+    /// The errors are listed in the order they occurred. Errors that were not passed to
+    /// `on_scope_error`, because they were suppressed with
+    /// [`ErrorScope::suppress_and_continue`](crate::ser::ErrorScope::suppress_and_continue),
+    /// are not listed.
+    pub fn ignored_errors(&self) -> &[(String, HooksError)] {
+        &self.ignored_errors
+    }
+
+    /// Returns the fingerprint of the shape of serialized data.
+    ///
+    /// Returns `None`, unless enabled with
+    /// [`StartScope::enable_schema_fingerprint`](crate::ser::StartScope::enable_schema_fingerprint).
+    /// The fingerprint is stable across runs and library versions, so it can be persisted.
+    pub fn schema_fingerprint(&self) -> Option<u64> {
+        self.schema_fingerprint
+    }
+
     /// Forces all static strings that were captured during serialization to be leaked
     /// and therefore to become truly `&'static str`.
     ///
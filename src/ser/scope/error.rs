@@ -11,6 +11,8 @@ pub struct ErrorScope<'p> {
     path: &'p Path,
     error: HooksError,
     ignore: bool,
+    suppress_scope_errors: bool,
+    message: Option<String>,
 }
 
 impl<'p> ErrorScope<'p> {
@@ -19,14 +21,25 @@ impl<'p> ErrorScope<'p> {
             path,
             error,
             ignore: false,
+            suppress_scope_errors: false,
+            message: None,
         }
     }
 
-    pub(crate) fn into_result<S: Serializer>(self) -> Result<(), S::Error> {
+    pub(crate) fn suppresses_scope_errors(&self) -> bool {
+        self.ignore && self.suppress_scope_errors
+    }
+
+    /// Returns the error back if it was ignored.
+    pub(crate) fn into_result<S: Serializer>(mut self) -> Result<HooksError, S::Error> {
         if self.ignore {
-            Ok(())
+            Ok(self.error)
         } else {
-            Err(serde::ser::Error::custom(self.format_error_message()))
+            let message = match self.message.take() {
+                Some(message) => message,
+                None => self.format_error_message(),
+            };
+            Err(serde::ser::Error::custom(message))
         }
     }
 
@@ -41,6 +54,18 @@ impl<'p> ErrorScope<'p> {
         self
     }
 
+    /// Ignore this error, and all the remaining errors of the same scope, and continue
+    /// serialization.
+    ///
+    /// Unlike [`ignore`](Self::ignore), the remaining queued actions of the scope that
+    /// fail, e.g. other fields or keys that are not found, are discarded without calling
+    /// [`on_scope_error`](crate::ser::Hooks::on_scope_error) for them again. Errors at
+    /// the same path are suppressed until serialization leaves that path.
+    pub fn suppress_and_continue(&mut self) -> &mut Self {
+        self.suppress_scope_errors = true;
+        self.ignore()
+    }
+
     /// Immediately panic.
     ///
     /// The panic message will contain the error message.
@@ -51,9 +76,20 @@ impl<'p> ErrorScope<'p> {
     /// Propagate this error as a custom serialization error.
     pub fn propagate(&mut self) -> &mut Self {
         self.ignore = false;
+        self.suppress_scope_errors = false;
         self
     }
 
+    /// Propagate this error as a custom serialization error with the given message.
+    ///
+    /// By default, the serialization error message contains the path and the
+    /// [`HooksError`] message. This replaces the whole message, e.g. to present
+    /// a user-friendly validation error.
+    pub fn replace_error(&mut self, message: impl Into<String>) -> &mut Self {
+        self.message = Some(message.into());
+        self.propagate()
+    }
+
     fn format_error_message(&self) -> String {
         format!(
             "Error at path '{path}': {err}",
@@ -1,8 +1,8 @@
-use std::{borrow::Cow, fmt::Display};
+use std::{borrow::Cow, cmp::Ordering, fmt::Display};
 
 use crate::{
     ser::wrapper::{MapEntryAction, MapEntryActions},
-    Case, StaticValue,
+    Case, StaticValue, Value,
 };
 
 /// Inspect maps and modify their contents.
@@ -10,14 +10,18 @@ use crate::{
 /// See [`Hooks::on_map`](crate::ser::Hooks::on_map).
 pub struct MapScope {
     map_len: Option<usize>,
+    is_human_readable: bool,
     actions: MapEntryActions,
+    annotations: Vec<(String, String)>,
 }
 
 impl MapScope {
-    pub(crate) fn new(map_len: Option<usize>) -> Self {
+    pub(crate) fn new(map_len: Option<usize>, is_human_readable: bool) -> Self {
         Self {
             map_len,
+            is_human_readable,
             actions: Default::default(),
+            annotations: Vec::new(),
         }
     }
 
@@ -25,6 +29,18 @@ impl MapScope {
         self.actions
     }
 
+    pub(crate) fn take_annotations(&mut self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.annotations)
+    }
+
+    /// Returns `true` if the serializer is expected to produce a human-readable format.
+    ///
+    /// Same as [`StartScope::is_format_human_readable`](crate::ser::StartScope::is_format_human_readable),
+    /// see [`serde::ser::Serializer::is_human_readable`] for more info.
+    pub fn is_human_readable(&self) -> bool {
+        self.is_human_readable
+    }
+
     /// Returns the original number of entries in this map, if known.
     ///
     /// This is a hint that the serializer gets from the map's `Serialize` implementation
@@ -35,6 +51,34 @@ impl MapScope {
         self.map_len
     }
 
+    /// Sets the number of entries in the serialized map, i.e. after all the actions are applied.
+    ///
+    /// Skipping, retaining or inserting entries makes the map serialize as one of an unknown
+    /// length, which some serializers, e.g. binary formats, do not support. If you know the
+    /// final number of entries, give it here to feed it to the serializer as a length hint.
+    ///
+    /// The hint is not checked, giving a wrong one is your responsibility and may lead to
+    /// serialization errors or corrupted output.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn set_len_hint(&mut self, len: usize) -> &mut Self {
+        self.actions.push(MapEntryAction::LenHint(len));
+        self
+    }
+
+    /// Attaches an annotation to this map.
+    ///
+    /// Annotations are metadata for annotation-capable formats (e.g. comments in YAML).
+    /// Serde has no API for those, so annotations are not fed to the serializer. Instead,
+    /// they are collected at the map path and can be retrieved with
+    /// [`EndScope::annotations`](crate::ser::EndScope::annotations) after serialization.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn set_annotation(&mut self, key: &str, value: &str) -> &mut Self {
+        self.annotations.push((key.to_string(), value.to_string()));
+        self
+    }
+
     /// Skips an entry during serialization.
     ///
     /// This is similar to `#[serde(skip)]` or `#[serde(skip_serializing)]`, but
@@ -52,6 +96,38 @@ impl MapScope {
         self
     }
 
+    /// Skips all entries with keys matching a predicate.
+    ///
+    /// The `predicate` is called during serialization for the key of each entry, as the key is
+    /// captured in [`Value`], e.g. to drop all keys with a prefix:
+    /// ```
+    /// # use serde_hooks::{ser, Path, Value};
+    /// struct Hooks;
+    /// impl ser::Hooks for Hooks {
+    ///     fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+    ///         map.skip_entries_matching(
+    ///             |key| matches!(key, Value::Str(s) if s.starts_with("_internal")),
+    ///         );
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Entries retained with [`retain_entry`](Self::retain_entry) are not skipped,
+    /// even if they match.
+    ///
+    /// Same as with [`skip_entry`](Self::skip_entry), the underlying serializer will
+    /// be given `None` as the map length hint if you call this method.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn skip_entries_matching(
+        &mut self,
+        predicate: impl Fn(&Value<'_>) -> bool + 'static,
+    ) -> &mut Self {
+        self.actions
+            .push(MapEntryAction::SkipMatching(Box::new(predicate)));
+        self
+    }
+
     /// Retains an entry.
     ///
     /// Calling this method switches processing to a 'retain' mode, in which
@@ -72,6 +148,22 @@ impl MapScope {
         self
     }
 
+    /// Skips all original entries of the map.
+    ///
+    /// Entries inserted with [`insert_entry`](Self::insert_entry) are still serialized,
+    /// which allows replacing the map contents wholesale. Insertions relative to the
+    /// original keys are placed as if the entries were not skipped.
+    ///
+    /// Same as for [`skip_entry`](Self::skip_entry), the underlying serializer will be given
+    /// `None` as the map length hint if you call this method, unless the length is set
+    /// with [`set_len_hint`](Self::set_len_hint).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn clear(&mut self) -> &mut Self {
+        self.actions.push(MapEntryAction::Clear);
+        self
+    }
+
     /// Insert a new entry at a given location during serialization.
     ///
     /// There is no check for key uniqueness. If you insert an entry with a key that already
@@ -108,6 +200,31 @@ impl MapScope {
         self
     }
 
+    /// Insert a new entry, checking that the key is unique.
+    ///
+    /// Same as [`insert_entry`](Self::insert_entry), but produces
+    /// [`HooksError::DuplicateKey`](crate::ser::HooksError::DuplicateKey) error if
+    /// any other entry in the map has the same key. If the error is ignored, the entry is
+    /// still inserted.
+    ///
+    /// Keys are compared as they are after renaming and replacement by this scope,
+    /// but before [`on_map_key`](crate::ser::Hooks::on_map_key) hooks.
+    /// Only the keys of checked insertions are tracked during serialization, so the check
+    /// has a cost only for maps that use it.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn insert_entry_checked(
+        &mut self,
+        key: impl Into<StaticValue>,
+        value: impl Into<StaticValue>,
+        location: MapInsertLocation,
+    ) -> &mut Self {
+        let key = key.into();
+        self.actions
+            .push(MapEntryAction::CheckUniqueKey(key.clone()));
+        self.insert_entry(key, value, location)
+    }
+
     /// Replace value of an existing entry.
     ///
     /// Primitive values are copied, and are later fed to the serializer.
@@ -132,6 +249,40 @@ impl MapScope {
         self
     }
 
+    /// Replace values of all entries with keys matching a predicate.
+    ///
+    /// The `predicate` is called during serialization for the original key of each entry,
+    /// as the key is captured in [`Value`], e.g. to redact all tokens:
+    /// ```
+    /// # use serde_hooks::{ser, Path, Value};
+    /// struct Hooks;
+    /// impl ser::Hooks for Hooks {
+    ///     fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+    ///         map.replace_values_matching(
+    ///             |key| matches!(key, Value::Str(s) if s.ends_with("_token")),
+    ///             "***",
+    ///         );
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Same restrictions as for [`replace_value`](Self::replace_value) apply to `new_value`.
+    /// Values replaced with [`replace_value`](Self::replace_value) take precedence. If an
+    /// entry matches more than one predicate, the last given value is used.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn replace_values_matching(
+        &mut self,
+        predicate: impl Fn(&Value<'_>) -> bool + 'static,
+        new_value: impl Into<StaticValue>,
+    ) -> &mut Self {
+        self.actions.push(MapEntryAction::ReplaceValueMatching(
+            Box::new(predicate),
+            new_value.into(),
+        ));
+        self
+    }
+
     /// Replace key of an existing entry.
     ///
     /// There is no check for key uniqueness. If you use a key that is used for some other entry,
@@ -196,18 +347,88 @@ impl MapScope {
         self
     }
 
-    /// Rename all keys all entries according to the given case convention.
+    /// Rename keys of all entries according to the given case convention.
     ///
     /// This applies [`rename_key_case`](Self::rename_key_case), to all string type
     /// keys ([`Value::Str`](crate::Value::Str)) in the map that are not renamed or replaced explicitly.
     /// Keys of other types are not affected.
     ///
+    /// If specified multiple times, the last case convention is used.
+    ///
     /// Returns `self` to allow chaining calls.
-    pub fn rename_all_fields_case(&mut self, case: impl Into<Case>) -> &mut Self {
+    pub fn rename_all_keys_case(&mut self, case: impl Into<Case>) -> &mut Self {
         self.actions
             .push(MapEntryAction::RenameAllCase(case.into()));
         self
     }
+
+    /// Same as [`rename_all_keys_case`](Self::rename_all_keys_case).
+    ///
+    /// Named after [`StructScope::rename_all_fields_case`](crate::ser::StructScope::rename_all_fields_case),
+    /// for hooks that treat structs and maps alike.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn rename_all_fields_case(&mut self, case: impl Into<Case>) -> &mut Self {
+        self.rename_all_keys_case(case)
+    }
+
+    /// Serialize map entries sorted by key.
+    ///
+    /// This makes the output deterministic for maps without a stable iteration order,
+    /// like `HashMap`. Entries are buffered until the end of the map, and then fed to the
+    /// serializer in order. Sorting is stable, and is applied to the final keys, i.e. after
+    /// any renaming, replacement or insertion.
+    ///
+    /// Keys are ordered as follows:
+    /// - keys of different kinds are ordered by kind: booleans, numbers, strings and chars,
+    ///   bytes, and then the rest in the order of [`Value`](crate::Value) variants declaration;
+    /// - numbers are compared by their numeric value, regardless of their type, so `2u8` goes
+    ///   before `10i64` and `-1i32` before `0.5f64`. Integers are compared exactly, `NaN`
+    ///   goes after all other numbers. Numerically equal keys of different types are ordered
+    ///   by type, in the order of variants declaration;
+    /// - strings are compared lexicographically, chars are compared as one-char strings;
+    /// - compound keys are compared by their metadata only (names, indices and lengths).
+    ///
+    /// Use [`sort_entries_by`](Self::sort_entries_by) for a custom order.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn sort_entries(&mut self) -> &mut Self {
+        self.sort_entries_by(|a, b| a.total_cmp(b))
+    }
+
+    /// Serialize map entries sorted by key with a custom comparison function.
+    ///
+    /// Same as [`sort_entries`](Self::sort_entries), but the keys are compared with `cmp`.
+    ///
+    /// If specified multiple times, the last comparison function is used.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn sort_entries_by(
+        &mut self,
+        cmp: impl Fn(&Value<'_>, &Value<'_>) -> Ordering + 'static,
+    ) -> &mut Self {
+        self.actions.push(MapEntryAction::Sort(Box::new(cmp)));
+        self
+    }
+
+    /// Normalize all string keys to the given Unicode normalization form.
+    ///
+    /// Only keys of string type ([`Value::Str`](crate::Value::Str)) are normalized, keys of
+    /// other types are not affected. Normalization is applied to the final key, i.e.
+    /// after any renaming or replacement.
+    ///
+    /// Keys are matched by other actions using their original, non-normalized form.
+    ///
+    /// If specified multiple times, the last normalization form is used.
+    ///
+    /// Requires the `unicode-normalization` feature.
+    ///
+    /// Returns `self` to allow chaining calls.
+    #[cfg(feature = "unicode-normalization")]
+    pub fn normalize_string_keys(&mut self, form: UnicodeForm) -> &mut Self {
+        self.actions.push(MapEntryAction::NormalizeStringKeys(form));
+        self
+    }
 }
 
 /// Selector for map entries.
@@ -252,13 +473,44 @@ pub enum MapKeySelector {
     /// This is the position in the order in which the original map entries are fed into the
     /// serializer. Selecting by index obviously only makes sense for ordered maps.
     ByIndex(usize),
+
+    /// Select entry by matching a string key, ignoring case.
+    ///
+    /// Useful for tolerant targeting of keys coming from external sources.
+    /// You'd normally construct this variant with [`MapKeySelector::key_ci`]:
+    /// ```
+    /// # use serde_hooks::{ser, Path};
+    /// # struct Hooks;
+    /// impl ser::Hooks for Hooks {
+    ///     fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+    ///         map.skip_entry(ser::MapKeySelector::key_ci("Content-Type"));
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Keys are compared by their Unicode lowercase mapping. Only string keys are matched.
+    ByValueCaseInsensitive(String),
 }
 
 impl MapKeySelector {
+    /// Creates a selector matching a string key, ignoring case.
+    ///
+    /// See [`MapKeySelector::ByValueCaseInsensitive`].
+    pub fn key_ci(key: &str) -> Self {
+        MapKeySelector::ByValueCaseInsensitive(key.to_string())
+    }
+
     pub(crate) fn matches_path_key(&self, value: &crate::Value, index: usize) -> bool {
         match self {
             MapKeySelector::ByValue(v) => value == v,
             MapKeySelector::ByIndex(i) => index == *i,
+            MapKeySelector::ByValueCaseInsensitive(key) => match value {
+                crate::Value::Str(s) => s
+                    .chars()
+                    .flat_map(char::to_lowercase)
+                    .eq(key.chars().flat_map(char::to_lowercase)),
+                _ => false,
+            },
         }
     }
 }
@@ -268,6 +520,9 @@ impl Display for MapKeySelector {
         match self {
             MapKeySelector::ByValue(value) => f.write_fmt(format_args!("[{value}]")),
             MapKeySelector::ByIndex(index) => f.write_fmt(format_args!("[{index}]")),
+            MapKeySelector::ByValueCaseInsensitive(key) => {
+                f.write_fmt(format_args!("[{key:?} (case-insensitive)]"))
+            }
         }
     }
 }
@@ -284,6 +539,35 @@ impl From<usize> for MapKeySelector {
     }
 }
 
+/// Unicode normalization form.
+///
+/// See [Unicode Normalization Forms](https://www.unicode.org/reports/tr15/) for more info.
+#[cfg(feature = "unicode-normalization")]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum UnicodeForm {
+    /// Canonical Decomposition, followed by Canonical Composition.
+    Nfc,
+    /// Canonical Decomposition.
+    Nfd,
+    /// Compatibility Decomposition, followed by Canonical Composition.
+    Nfkc,
+    /// Compatibility Decomposition.
+    Nfkd,
+}
+
+#[cfg(feature = "unicode-normalization")]
+impl UnicodeForm {
+    pub(crate) fn normalize(self, s: &str) -> String {
+        use unicode_normalization::UnicodeNormalization;
+        match self {
+            UnicodeForm::Nfc => s.nfc().collect(),
+            UnicodeForm::Nfd => s.nfd().collect(),
+            UnicodeForm::Nfkc => s.nfkc().collect(),
+            UnicodeForm::Nfkd => s.nfkd().collect(),
+        }
+    }
+}
+
 /// Location in the map where an entry is inserted.
 pub enum MapInsertLocation {
     /// Insert the entry before another entry specified by the selector.
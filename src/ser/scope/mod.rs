@@ -1,20 +1,31 @@
+mod bytes;
 mod end;
 mod error;
 mod map;
+mod newtype;
+mod option;
 mod seq;
 mod start;
 mod r#struct;
 mod tuple;
+mod unit;
 mod value;
 mod variant;
 
+pub use bytes::BytesScope;
 pub use end::EndScope;
+pub(crate) use end::Statistics;
 pub use error::ErrorScope;
+#[cfg(feature = "unicode-normalization")]
+pub use map::UnicodeForm;
 pub use map::{MapInsertLocation, MapKeySelector, MapScope};
+pub use newtype::NewtypeStructScope;
+pub use option::OptionScope;
 pub use r#struct::StructScope;
-pub use seq::SeqScope;
-pub use start::StartScope;
+pub use seq::{SeqInsertLocation, SeqScope};
+pub use start::{FormatHint, StartScope};
 pub use tuple::{TupleScope, TupleStructScope};
+pub use unit::UnitStructScope;
 pub use value::ValueScope;
 pub use variant::EnumVariantScope;
 
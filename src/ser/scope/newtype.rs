@@ -0,0 +1,55 @@
+use std::borrow::Cow;
+
+use crate::ser::wrapper::NewtypeStructActions;
+
+/// Inspect and modify newtype structs.
+///
+/// See [`Hooks::on_newtype_struct`](crate::ser::Hooks::on_newtype_struct).
+pub struct NewtypeStructScope {
+    struct_name: &'static str,
+    actions: NewtypeStructActions,
+}
+
+impl NewtypeStructScope {
+    pub(crate) fn new(struct_name: &'static str) -> Self {
+        Self {
+            struct_name,
+            actions: NewtypeStructActions::default(),
+        }
+    }
+
+    pub(crate) fn into_actions(self) -> NewtypeStructActions {
+        self.actions
+    }
+
+    /// Returns the newtype struct name.
+    pub fn struct_name(&self) -> &'static str {
+        self.struct_name
+    }
+
+    /// Serialize the inner value directly, without the newtype wrapper.
+    ///
+    /// Runtime equivalent to `#[serde(transparent)]`. Most formats, e.g. JSON, already
+    /// serialize newtype structs as their inner value, so this only makes a difference for the
+    /// formats that distinguish newtypes, like RON.
+    ///
+    /// Takes precedence over [`rename`](Self::rename).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn unwrap(&mut self) -> &mut Self {
+        self.actions.unwrap = true;
+        self
+    }
+
+    /// Set a new newtype struct name.
+    ///
+    /// Serde expects struct names to be known at compile time, and as such, to be static. Passing in a
+    /// borrowed `&'static str` for the new name here fulfills this. However, passing in
+    /// an owned `String` leads to special handling described in [Static strings](crate::ser#static-strings).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn rename(&mut self, new_struct_name: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.actions.rename = Some(new_struct_name.into());
+        self
+    }
+}
@@ -0,0 +1,47 @@
+use serde::Serializer;
+
+use crate::ser::wrapper::ValueAction;
+
+/// Inspect and skip optional values.
+///
+/// See [`Hooks::on_option`](crate::ser::Hooks::on_option).
+pub struct OptionScope<S: Serializer> {
+    action: Option<ValueAction<S>>,
+    is_some: bool,
+}
+
+impl<S: Serializer> OptionScope<S> {
+    pub(crate) fn new(serializer: S, is_some: bool) -> Self {
+        Self {
+            action: Some(ValueAction::ContinueSerialization(serializer)),
+            is_some,
+        }
+    }
+
+    pub(crate) fn into_action(self) -> ValueAction<S> {
+        self.action.unwrap()
+    }
+
+    /// Returns `true` if the value is `Some`.
+    pub fn is_some(&self) -> bool {
+        self.is_some
+    }
+
+    /// Returns `true` if the value is `None`.
+    pub fn is_none(&self) -> bool {
+        !self.is_some
+    }
+
+    /// Skip the optional value altogether, like `#[serde(skip_serializing_if = "Option::is_none")]`
+    /// does, but decided at runtime.
+    ///
+    /// Same as [`ValueScope::skip`](crate::ser::ValueScope::skip), see there for which values
+    /// can be skipped. Skipping must be allowed with
+    /// [`HookOptions::allow_skipping_values`](crate::ser::HookOptions::allow_skipping_values).
+    pub fn skip(&mut self) -> &mut Self {
+        if let Some(ValueAction::ContinueSerialization(s)) = self.action.take() {
+            self.action = Some(ValueAction::Skipped(s));
+        }
+        self
+    }
+}
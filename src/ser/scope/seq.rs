@@ -1,6 +1,8 @@
+use std::ops::{Bound, Range, RangeBounds};
+
 use crate::{
     ser::wrapper::{SeqElementAction, SeqElementActions},
-    StaticValue,
+    StaticValue, Value,
 };
 
 /// Inspect and modify sequences and tuple elements.
@@ -22,14 +24,18 @@ use crate::{
 /// serializers do not support this.
 pub struct SeqScope {
     seq_len: Option<usize>,
+    is_human_readable: bool,
     actions: SeqElementActions,
+    annotations: Vec<(String, String)>,
 }
 
 impl SeqScope {
-    pub(crate) fn new(seq_len: Option<usize>) -> Self {
+    pub(crate) fn new(seq_len: Option<usize>, is_human_readable: bool) -> Self {
         Self {
             seq_len,
+            is_human_readable,
             actions: Default::default(),
+            annotations: Vec::new(),
         }
     }
 
@@ -37,6 +43,18 @@ impl SeqScope {
         self.actions
     }
 
+    pub(crate) fn take_annotations(&mut self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.annotations)
+    }
+
+    /// Returns `true` if the serializer is expected to produce a human-readable format.
+    ///
+    /// Same as [`StartScope::is_format_human_readable`](crate::ser::StartScope::is_format_human_readable),
+    /// see [`serde::ser::Serializer::is_human_readable`] for more info.
+    pub fn is_human_readable(&self) -> bool {
+        self.is_human_readable
+    }
+
     /// Returns the original sequence length if known during serialization.
     ///
     /// The returned value is not affected by any retain or skip actions.
@@ -46,6 +64,18 @@ impl SeqScope {
         self.seq_len
     }
 
+    /// Attaches an annotation to this sequence.
+    ///
+    /// Annotations are not fed to the serializer, they are collected at the sequence
+    /// path and can be retrieved with [`EndScope::annotations`](crate::ser::EndScope::annotations)
+    /// after serialization, e.g. to add comments in a second pass.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn set_annotation(&mut self, key: &str, value: &str) -> &mut Self {
+        self.annotations.push((key.to_string(), value.to_string()));
+        self
+    }
+
     /// Skips an element at the given index.
     ///
     /// The index passed is the index in the original sequence.
@@ -70,6 +100,145 @@ impl SeqScope {
         self
     }
 
+    /// Skips all elements with indices in the given range.
+    ///
+    /// Indices are the indices in the original sequence. Open-ended ranges, such as `3..`,
+    /// skip all remaining elements, even if the sequence length is not known.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn skip_range(&mut self, range: impl RangeBounds<usize>) -> &mut Self {
+        self.actions
+            .push(SeqElementAction::SkipRange(range_to_indices(range)));
+        self
+    }
+
+    /// Skips all elements whose values match the predicate.
+    ///
+    /// The predicate is evaluated for every element, against the element value
+    /// captured as [`Value`]. Only primitive values are captured: the predicate is not called
+    /// for compound values, like structs or nested sequences, and such elements are never
+    /// skipped. `Some` values are unwrapped, so the predicate sees the contained value,
+    /// and byte arrays are passed in as empty.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn skip_elements_matching(
+        &mut self,
+        predicate: impl Fn(&Value) -> bool + 'static,
+    ) -> &mut Self {
+        self.actions
+            .push(SeqElementAction::SkipMatching(Box::new(predicate)));
+        self
+    }
+
+    /// Retains all elements with indices in the given range.
+    ///
+    /// Like [`retain_element`](Self::retain_element), this switches processing to a
+    /// 'retain' mode, in which all not retained elements are skipped.
+    ///
+    /// Indices are the indices in the original sequence. Open-ended ranges, such as `3..`,
+    /// retain all remaining elements, even if the sequence length is not known.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn retain_range(&mut self, range: impl RangeBounds<usize>) -> &mut Self {
+        self.actions
+            .push(SeqElementAction::RetainRange(range_to_indices(range)));
+        self
+    }
+
+    /// Sets the number of elements in the serialized sequence, i.e. after all the actions
+    /// are applied.
+    ///
+    /// Skipping, retaining or inserting elements makes the sequence serialize as one of an
+    /// unknown length, and tuples as sequences, which some serializers, e.g. binary formats,
+    /// do not support. If you know the final number of elements, give it here to feed it to
+    /// the serializer as a length hint. Tuples are then serialized as tuples of this length.
+    ///
+    /// The hint is not checked, giving a wrong one is your responsibility and may lead to
+    /// serialization errors or corrupted output.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn set_len_hint(&mut self, len: usize) -> &mut Self {
+        self.actions.push(SeqElementAction::LenHint(len));
+        self
+    }
+
+    /// Skips all elements at indices starting from `max_len`, so that at most `max_len`
+    /// elements of the original sequence are serialized.
+    ///
+    /// Indices are the indices in the original sequence, so this composes with other
+    /// skip and retain actions, e.g. skipping an element before `max_len` makes the
+    /// output one element shorter. Elements inserted with
+    /// [`insert_element`](Self::insert_element) are not counted.
+    ///
+    /// If specified multiple times, the smallest length is used.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn truncate(&mut self, max_len: usize) -> &mut Self {
+        self.actions.push(SeqElementAction::Truncate(max_len));
+        self
+    }
+
+    /// Inserts a new element before or after the element at the given index, or at the end
+    /// of the sequence.
+    ///
+    /// The index passed is the index in the original sequence. It is ignored when inserting
+    /// at [`SeqInsertLocation::End`].
+    ///
+    /// Primitive values are copied, and are later fed to the serializer.
+    /// For compound values, only metadata is stored, therefore it's not possible to
+    /// serialize the actual values from the contents of [`StaticValue`]. Passing in a
+    /// compound value here would result in an
+    /// [`HooksError::ValueNotSerializable`](crate::ser::HooksError::ValueNotSerializable) error.
+    /// The trick to insert a compound value is to first insert a primitive one
+    /// (e.g. a unit), subscribe to `on_value` hook, and replace the value there again with the
+    /// compound one. The inserted element is reported to hooks at the path of the element
+    /// it is inserted next to.
+    ///
+    /// Inserting elements makes the sequence serialize as one of an unknown length.
+    ///
+    /// Will produce [`HooksError::IndexNotFound`](crate::ser::HooksError::IndexNotFound) error
+    /// if the insertion location refers to an index that does not occur during serialization.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn insert_element(
+        &mut self,
+        index: usize,
+        value: impl Into<StaticValue>,
+        location: SeqInsertLocation,
+    ) -> &mut Self {
+        self.actions
+            .push(SeqElementAction::Insert(index, value.into(), location));
+        self
+    }
+
+    /// Skips the last element of the sequence.
+    ///
+    /// Unlike [`skip_element`](Self::skip_element), this works when the sequence length
+    /// is unknown. Each element is buffered until the next one comes, or the sequence ends,
+    /// and only then it is either fed to the serializer or dropped. This means that hooks
+    /// for elements are called before it is known whether the element is skipped.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn skip_last(&mut self) -> &mut Self {
+        self.actions.push(SeqElementAction::SkipLast);
+        self
+    }
+
+    /// Retains the last element of the sequence.
+    ///
+    /// Like [`retain_element`](Self::retain_element), this switches processing to
+    /// a 'retain' mode, and can be combined with retaining elements at explicit indices.
+    ///
+    /// This works when the sequence length is unknown, by buffering each not otherwise
+    /// retained element until the next one comes, or the sequence ends. Hooks are
+    /// called for all such elements, including those that end up being skipped.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn retain_last(&mut self) -> &mut Self {
+        self.actions.push(SeqElementAction::RetainLast);
+        self
+    }
+
     /// Replace a value at the given index.
     ///
     /// The index passed is the index in the original sequence.
@@ -99,3 +268,30 @@ impl SeqScope {
         self
     }
 }
+
+/// Location in the sequence where an element is inserted.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SeqInsertLocation {
+    /// Insert the element before the element at the given index.
+    Before,
+
+    /// Insert the element after the element at the given index.
+    After,
+
+    /// Insert the element to the very end of the sequence.
+    End,
+}
+
+fn range_to_indices(range: impl RangeBounds<usize>) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.saturating_add(1),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => usize::MAX,
+    };
+    start..end
+}
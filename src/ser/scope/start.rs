@@ -1,13 +1,50 @@
+use std::borrow::Cow;
+
 /// Inspect serializer information before serialization begins.
 ///
 /// See [`Hooks::on_start`](crate::ser::Hooks::on_start).
 pub struct StartScope {
     is_human_readable: bool,
+    format: Option<Cow<'static, str>>,
+    schema_fingerprint: bool,
+    abort_message: Option<String>,
+    human_readable_override: Option<bool>,
+}
+
+/// Best-effort description of the serialization format.
+///
+/// See [`StartScope::format_hint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatHint<'f> {
+    /// Format tag given with [`hook_with_format`](crate::ser::hook_with_format).
+    Tagged(&'f str),
+    /// No tag was given, and the serializer produces a human-readable format.
+    HumanReadable,
+    /// No tag was given, and the serializer produces a compact, non human-readable format.
+    Compact,
 }
 
 impl StartScope {
-    pub(crate) fn new(is_human_readable: bool) -> Self {
-        Self { is_human_readable }
+    pub(crate) fn new(is_human_readable: bool, format: Option<Cow<'static, str>>) -> Self {
+        Self {
+            is_human_readable,
+            format,
+            schema_fingerprint: false,
+            abort_message: None,
+            human_readable_override: None,
+        }
+    }
+
+    pub(crate) fn take_abort_message(&mut self) -> Option<String> {
+        self.abort_message.take()
+    }
+
+    pub(crate) fn is_schema_fingerprint_enabled(&self) -> bool {
+        self.schema_fingerprint
+    }
+
+    pub(crate) fn human_readable_override(&self) -> Option<bool> {
+        self.human_readable_override
     }
 
     /// Returns `true` if used serializer is expected to produce a human-readable format.
@@ -16,4 +53,65 @@ impl StartScope {
     pub fn is_format_human_readable(&self) -> bool {
         self.is_human_readable
     }
+
+    /// Returns a hint about the format produced by the serializer.
+    ///
+    /// Serde gives no way to identify the serializer, so the format has to be tagged by
+    /// the caller with [`hook_with_format`](crate::ser::hook_with_format) or
+    /// [`HookOptions::format`](crate::ser::HookOptions::format). Without the tag,
+    /// the hint is derived from [`is_format_human_readable`](Self::is_format_human_readable).
+    pub fn format_hint(&self) -> FormatHint<'_> {
+        match (&self.format, self.is_human_readable) {
+            (Some(format), _) => FormatHint::Tagged(format),
+            (None, true) => FormatHint::HumanReadable,
+            (None, false) => FormatHint::Compact,
+        }
+    }
+
+    /// Enables computing a fingerprint of the shape of serialized data.
+    ///
+    /// The fingerprint is a hash of the struct names, field names and value types
+    /// encountered during serialization, not of the values themselves. Payloads of the same
+    /// shape get the same fingerprint, which allows detecting changes of the payload shape,
+    /// e.g. when a field is added or changes its type.
+    ///
+    /// Sequence indices and map keys are not part of the shape, neither are the lengths
+    /// of sequences and maps, nor the selected enum variants. However, only the values that
+    /// are actually serialized contribute to the fingerprint, so e.g. an empty sequence,
+    /// a `None` option, or a different enum variant can still change it.
+    ///
+    /// The fingerprint is retrieved with
+    /// [`EndScope::schema_fingerprint`](crate::ser::EndScope::schema_fingerprint).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn enable_schema_fingerprint(&mut self) -> &mut Self {
+        self.schema_fingerprint = true;
+        self
+    }
+
+    /// Override the human-readable flag reported by the scopes.
+    ///
+    /// The overridden value is returned by `is_human_readable` of the struct, map and sequence
+    /// scopes, e.g. [`StructScope::is_human_readable`](crate::ser::StructScope::is_human_readable),
+    /// so that hooks can decide on a representation regardless of the serializer.
+    ///
+    /// This does not change the serializer itself: serde's
+    /// [`is_human_readable`](serde::ser::Serializer::is_human_readable) is defined by the serializer,
+    /// and `Serialize` implementations still see the real value. Neither does it change
+    /// [`is_format_human_readable`](Self::is_format_human_readable) of this scope.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn override_human_readable(&mut self, is_human_readable: bool) -> &mut Self {
+        self.human_readable_override = Some(is_human_readable);
+        self
+    }
+
+    /// Abort serialization before it begins.
+    ///
+    /// Serialization fails with a custom serialization error with the given message,
+    /// without making any calls to the serializer. [`on_end`](crate::ser::Hooks::on_end)
+    /// is still called with this error.
+    pub fn abort(&mut self, message: impl Into<String>) {
+        self.abort_message = Some(message.into());
+    }
 }
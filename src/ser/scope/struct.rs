@@ -1,8 +1,8 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, cmp::Ordering};
 
 use crate::{
     ser::wrapper::{StructActions, StructFieldAction, StructFieldActions},
-    Case, StaticValue,
+    Case, StaticValue, Value,
 };
 
 /// Inspect structs and modify their contents.
@@ -12,18 +12,29 @@ use crate::{
 pub struct StructScope {
     struct_len: usize,
     struct_name: &'static str,
+    is_human_readable: bool,
     struct_actions: StructActions,
     field_actions: StructFieldActions,
+    annotations: Vec<(String, String)>,
 }
 
 impl StructScope {
-    pub(crate) fn new(struct_len: usize, struct_name: &'static str) -> Self {
+    pub(crate) fn new(
+        struct_len: usize,
+        struct_name: &'static str,
+        is_human_readable: bool,
+    ) -> Self {
         Self {
             struct_len,
             struct_name,
+            is_human_readable,
             field_actions: Default::default(),
+            annotations: Vec::new(),
             struct_actions: StructActions {
                 serialize_as_map: false,
+                serialize_as_seq: false,
+                sort_map_entries: None,
+                field_order: None,
             },
         }
     }
@@ -32,6 +43,18 @@ impl StructScope {
         (self.struct_actions, self.field_actions)
     }
 
+    pub(crate) fn take_annotations(&mut self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.annotations)
+    }
+
+    /// Returns `true` if the serializer is expected to produce a human-readable format.
+    ///
+    /// Same as [`StartScope::is_format_human_readable`](crate::ser::StartScope::is_format_human_readable),
+    /// see [`serde::ser::Serializer::is_human_readable`] for more info.
+    pub fn is_human_readable(&self) -> bool {
+        self.is_human_readable
+    }
+
     /// Returns the original number of fields in this struct.
     ///
     /// The returned value is not affected by any retain or skip actions.
@@ -44,6 +67,18 @@ impl StructScope {
         self.struct_name
     }
 
+    /// Attaches an annotation to this struct.
+    ///
+    /// The annotation is collected at the struct path, and can be retrieved with
+    /// [`EndScope::annotations`](crate::ser::EndScope::annotations) after serialization.
+    /// It is not fed to the serializer.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn set_annotation(&mut self, key: &str, value: &str) -> &mut Self {
+        self.annotations.push((key.to_string(), value.to_string()));
+        self
+    }
+
     /// Skips a field during serialization.
     ///
     /// Runtime equivalent to `#[serde(skip)]` or `#[serde(skip_serializing)]`.
@@ -58,6 +93,32 @@ impl StructScope {
         self
     }
 
+    /// Skips a field during serialization if its value matches the predicate.
+    ///
+    /// Runtime equivalent to `#[serde(skip_serializing_if = "...")]`.
+    ///
+    /// The predicate is evaluated when the field is serialized, against the field value
+    /// captured as [`Value`]. Only primitive values are captured: the predicate is not called
+    /// for compound values, like structs or sequences, and such fields are never skipped.
+    /// `Some` values are unwrapped, so the predicate sees the contained value, and byte arrays
+    /// are passed in as empty, same as for map keys in
+    /// [`PathMapKey::as_value`](crate::PathMapKey::as_value).
+    ///
+    /// If the field is not found in the struct, [`HooksError::FieldNotFound`](crate::ser::HooksError::FieldNotFound)
+    /// is produced _after_ the struct is serialized. You can process or ignore this error in
+    /// [`Hooks::on_scope_error`](crate::ser::Hooks::on_scope_error).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn skip_field_if_value(
+        &mut self,
+        key: impl Into<Cow<'static, str>>,
+        predicate: impl Fn(&Value) -> bool + 'static,
+    ) -> &mut Self {
+        self.field_actions
+            .push(StructFieldAction::SkipIf(key.into(), Box::new(predicate)));
+        self
+    }
+
     /// Retains a field.
     ///
     /// Calling this method switches processing to a 'retain' mode, in which
@@ -105,6 +166,50 @@ impl StructScope {
         self
     }
 
+    /// Rename a field, computing the new key from the original one.
+    ///
+    /// Same as [`rename_field`](Self::rename_field), but the new key is returned by `f`,
+    /// which is called with `key`. Use this when the new key can't be expressed with
+    /// a [`Case`], e.g. to add a prefix or a suffix.
+    ///
+    /// Since the new key is produced in runtime, this leads to special handling described
+    /// in [Static strings](crate::ser#static-strings).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn rename_field_with(
+        &mut self,
+        key: impl Into<Cow<'static, str>>,
+        f: impl FnOnce(&str) -> String,
+    ) -> &mut Self {
+        let key = key.into();
+        let new_key = f(&key);
+        self.rename_field(key, new_key)
+    }
+
+    /// Rename a field at the given index.
+    ///
+    /// The `index` is the position of the field in the order in which the struct fields
+    /// are fed to the serializer, starting at 0. This is useful when field names are not
+    /// known in advance.
+    ///
+    /// If the struct has fewer fields than `index + 1`, [`HooksError::FieldIndexNotFound`](crate::ser::HooksError::FieldIndexNotFound)
+    /// is produced _after_ the struct is serialized. You can process or ignore this error in
+    /// [`Hooks::on_scope_error`](crate::ser::Hooks::on_scope_error).
+    ///
+    /// See [`rename_field`](Self::rename_field) for details on passing in owned strings
+    /// for the new key.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn rename_field_at(
+        &mut self,
+        index: usize,
+        new_key: impl Into<Cow<'static, str>>,
+    ) -> &mut Self {
+        self.field_actions
+            .push(StructFieldAction::RenameAt(index, new_key.into()));
+        self
+    }
+
     /// Rename a field according to the given case convention.
     ///
     /// The `key` refers to the original field key in the struct, even if [`rename_all_fields_case`](Self::rename_all_fields_case)
@@ -156,6 +261,45 @@ impl StructScope {
         self
     }
 
+    /// Rename structure fields matching a predicate according to the given case convention.
+    ///
+    /// The `predicate` is called with the original field key, the same as `key` in
+    /// [`rename_field`](Self::rename_field). Fields for which it returns `true` are renamed,
+    /// e.g. to recase all fields except the ones starting with an underscore:
+    /// ```
+    /// # use serde_hooks::{ser, Case, Path};
+    /// struct Hooks;
+    /// impl ser::Hooks for Hooks {
+    ///     fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+    ///         st.rename_fields_matching(|key| !key.starts_with('_'), Case::Camel);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// If a field matches more than one predicate, the last case convention is used.
+    /// The case convention given here overrides [`rename_all_fields_case`](Self::rename_all_fields_case),
+    /// and calling [`rename_field`](Self::rename_field) on specific fields overrides both.
+    ///
+    /// If you use serde's `#[derive(Serialize)]` and `#[serde(rename=...)]` or
+    /// `#[serde(rename_all=...)]`, those renames will be applied first.
+    ///
+    /// Same as for [`rename_all_fields_case`](Self::rename_all_fields_case), this leads to
+    /// special handling described in [Static strings](crate::ser#static-strings).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn rename_fields_matching(
+        &mut self,
+        predicate: impl Fn(&str) -> bool + 'static,
+        case: impl Into<Case>,
+    ) -> &mut Self {
+        self.field_actions
+            .push(StructFieldAction::RenameMatchingCase(
+                Box::new(predicate),
+                case.into(),
+            ));
+        self
+    }
+
     /// Replace a value for a field.
     ///
     /// The passed in [`StaticValue`] can represent both primitive and compound value types.
@@ -192,6 +336,48 @@ impl StructScope {
         self
     }
 
+    /// Insert a new field at the end of the struct.
+    ///
+    /// As with [`replace_value`](Self::replace_value), only primitive values can be serialized
+    /// from [`StaticValue`]. Passing in a compound value results in an
+    /// [`HooksError::ValueNotSerializable`](crate::ser::HooksError::ValueNotSerializable) error.
+    ///
+    /// Hooks are called for the inserted value, at the path of the new field.
+    ///
+    /// Serde expects struct fields to be known at compile time, so inserting a field causes
+    /// this struct to be serialized as a map with no length hint to the serializer.
+    /// Some serializers do not support this.
+    /// See [`serialize_as_map`](Self::serialize_as_map) for more details and implications.
+    ///
+    /// Passing in an owned `String` for the key leads to special handling described in
+    /// [Static strings](crate::ser#static-strings).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn insert_field(
+        &mut self,
+        key: impl Into<Cow<'static, str>>,
+        value: impl Into<StaticValue>,
+    ) -> &mut Self {
+        self.field_actions
+            .push(StructFieldAction::Insert(key.into(), value.into()));
+        self
+    }
+
+    /// Skips all original fields of the struct.
+    ///
+    /// Combined with [`insert_field`](Self::insert_field), this replaces the whole field set,
+    /// e.g. to produce a computed projection of the struct. Fields retained with
+    /// [`retain_field`](Self::retain_field) are still serialized.
+    ///
+    /// Same as inserting a field, clearing causes this struct to be serialized as a map with
+    /// no length hint to the serializer, see [`serialize_as_map`](Self::serialize_as_map).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn clear_fields(&mut self) -> &mut Self {
+        self.field_actions.push(StructFieldAction::Clear);
+        self
+    }
+
     /// Serialize this struct as a map.
     ///
     /// Calling this method makes the struct to be fed to the serializer as a map
@@ -209,12 +395,101 @@ impl StructScope {
     /// in `on_map` hook, the actions applied to the struct scope will have precedence.
     /// It generally would lead to confusing effects and is not recommended. Pick one.
     ///
+    /// The map entries are fed to the serializer in the order of struct fields declaration.
+    /// See [`serialize_as_sorted_map`](Self::serialize_as_sorted_map) if you need the
+    /// entries to be sorted by key instead.
+    ///
     /// Returns `self` to allow chaining calls.
     pub fn serialize_as_map(&mut self) -> &mut Self {
         self.struct_actions.serialize_as_map = true;
         self
     }
 
+    /// Serialize this struct as a sequence of its field values.
+    ///
+    /// Calling this method makes the struct to be fed to the serializer as a sequence,
+    /// e.g. a JSON array, with the field keys dropped. The elements are fed in the order
+    /// of struct fields declaration.
+    ///
+    /// Skipping and retaining fields still applies and changes the number of elements,
+    /// inserted fields are appended to the end of the sequence, and flattened fields
+    /// are serialized as regular elements. Since the keys are dropped, renaming fields
+    /// has no effect on the output.
+    ///
+    /// This takes precedence over [`serialize_as_map`](Self::serialize_as_map), so
+    /// sorting and reordering fields is ignored too. Hooks are called for the field
+    /// values at the paths of the struct fields.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn serialize_as_seq(&mut self) -> &mut Self {
+        self.struct_actions.serialize_as_seq = true;
+        self
+    }
+
+    /// Serialize this struct as a map with entries sorted by key.
+    ///
+    /// Same as [`serialize_as_map`](Self::serialize_as_map), but the map entries are fed
+    /// to the serializer sorted by their final keys, i.e. after any renames and flattening.
+    ///
+    /// To sort the entries, all of them are buffered in memory until the end of the
+    /// struct, which costs allocations for each of the field values.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn serialize_as_sorted_map(&mut self) -> &mut Self {
+        self.struct_actions.serialize_as_map = true;
+        self.sort_fields_by(str::cmp)
+    }
+
+    /// Sort struct fields alphabetically by their final keys.
+    ///
+    /// This is the same as [`serialize_as_sorted_map`](Self::serialize_as_sorted_map),
+    /// see there for details.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn sort_fields(&mut self) -> &mut Self {
+        self.serialize_as_sorted_map()
+    }
+
+    /// Sort struct fields by their final keys with a custom comparison function.
+    ///
+    /// Same as [`serialize_as_sorted_map`](Self::serialize_as_sorted_map), but the map
+    /// entries are sorted with `cmp` instead of alphabetically. The keys are compared after
+    /// any renames and flattening. The sort is stable, so entries with keys that compare as
+    /// equal keep their relative order.
+    ///
+    /// If [`reorder_fields`](Self::reorder_fields) is also called, the fields given there go
+    /// first, and the rest are sorted with `cmp`.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn sort_fields_by(&mut self, cmp: impl Fn(&str, &str) -> Ordering + 'static) -> &mut Self {
+        self.struct_actions.serialize_as_map = true;
+        self.struct_actions.sort_map_entries = Some(Box::new(cmp));
+        self
+    }
+
+    /// Serialize this struct as a map with fields in the given order.
+    ///
+    /// Same as [`serialize_as_map`](Self::serialize_as_map), but the map entries
+    /// listed in `order` are fed to the serializer first, in that order. The rest of the
+    /// entries follow in their original relative order, or sorted by key if
+    /// [`serialize_as_sorted_map`](Self::serialize_as_sorted_map) is also called.
+    ///
+    /// The fields are matched by their final keys, i.e. after any renames and flattening.
+    /// Names in `order` that do not match any field are ignored.
+    ///
+    /// To reorder the entries, all of them are buffered in memory until the end of the
+    /// struct, which costs allocations for each of the field values.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn reorder_fields(
+        &mut self,
+        order: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+    ) -> &mut Self {
+        self.struct_actions.serialize_as_map = true;
+        self.struct_actions.field_order = Some(order.into_iter().map(Into::into).collect());
+        self
+    }
+
     /// Flatten a field into this structure.
     ///
     /// Runtime equivalent to `#[serde(flatten)]`.
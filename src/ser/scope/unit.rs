@@ -0,0 +1,39 @@
+use std::borrow::Cow;
+
+/// Inspect and modify unit structs.
+///
+/// See [`Hooks::on_unit_struct`](crate::ser::Hooks::on_unit_struct).
+pub struct UnitStructScope {
+    struct_name: &'static str,
+    new_struct_name: Option<Cow<'static, str>>,
+}
+
+impl UnitStructScope {
+    pub(crate) fn new(struct_name: &'static str) -> Self {
+        Self {
+            struct_name,
+            new_struct_name: None,
+        }
+    }
+
+    pub(crate) fn into_new_struct_name(self) -> Option<Cow<'static, str>> {
+        self.new_struct_name
+    }
+
+    /// Returns the unit struct name.
+    pub fn struct_name(&self) -> &'static str {
+        self.struct_name
+    }
+
+    /// Set a new unit struct name.
+    ///
+    /// Serde expects struct names to be known at compile time, and as such, to be static. Passing in a
+    /// borrowed `&'static str` for the new name here fulfills this. However, passing in
+    /// an owned `String` leads to special handling described in [Static strings](crate::ser#static-strings).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn rename(&mut self, new_struct_name: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.new_struct_name = Some(new_struct_name.into());
+        self
+    }
+}
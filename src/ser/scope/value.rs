@@ -2,7 +2,13 @@ use std::fmt::Display;
 
 use serde::{Serialize, Serializer};
 
-use crate::{ser::wrapper::ValueAction, Value};
+use crate::{
+    ser::{
+        value::{check_if_can_serialize, is_value},
+        wrapper::ValueAction,
+    },
+    StaticValue, Value,
+};
 
 /// Inspect and modify serialized values.
 ///
@@ -11,20 +17,37 @@ pub struct ValueScope<'v, S: Serializer> {
     action: Option<ValueAction<S>>,
     value: Value<'v>,
     result: Result<(), String>,
+    is_seq_element: bool,
+    is_expanding_allowed: bool,
+    expanded_elements: Vec<StaticValue>,
+    annotations: Vec<(String, String)>,
 }
 
 impl<'v, S: Serializer> ValueScope<'v, S> {
-    pub(crate) fn new(serializer: S, value: Value<'v>) -> Self {
+    pub(crate) fn new(
+        serializer: S,
+        value: Value<'v>,
+        is_seq_element: bool,
+        is_expanding_allowed: bool,
+    ) -> Self {
         Self {
             action: Some(ValueAction::ContinueSerialization(serializer)),
             value,
             result: Ok(()),
+            is_seq_element,
+            is_expanding_allowed,
+            expanded_elements: Vec::new(),
+            annotations: Vec::new(),
         }
     }
 
-    pub(crate) fn into_action(self) -> Result<ValueAction<S>, S::Error> {
+    pub(crate) fn take_annotations(&mut self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.annotations)
+    }
+
+    pub(crate) fn into_action(self) -> Result<(ValueAction<S>, Vec<StaticValue>), S::Error> {
         self.result
-            .map(|_| self.action.unwrap())
+            .map(|_| (self.action.unwrap(), self.expanded_elements))
             .map_err(serde::ser::Error::custom)
     }
 
@@ -36,6 +59,18 @@ impl<'v, S: Serializer> ValueScope<'v, S> {
         &self.value
     }
 
+    /// Attaches an annotation to this value.
+    ///
+    /// Annotations do not affect the serialized output. They are collected at the value
+    /// path, and can be retrieved with [`EndScope::annotations`](crate::ser::EndScope::annotations)
+    /// to be applied by an annotation-capable pipeline afterwards.
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn set_annotation(&mut self, key: &str, value: &str) -> &mut Self {
+        self.annotations.push((key.to_string(), value.to_string()));
+        self
+    }
+
     /// Fail serialization with a custom error.
     pub fn fail_serialization(&mut self, error: impl Display) {
         self.result = Err(error.to_string());
@@ -52,16 +87,214 @@ impl<'v, S: Serializer> ValueScope<'v, S> {
     /// attach hooks to the new value as well, you need to explicitly do it by calling
     /// [`ser::hook()`](crate::ser::hook) on it.
     ///
+    /// Passing in a compound [`Value`] (e.g. [`Value::Struct`]) fails serialization with a
+    /// [`HooksError::ValueNotSerializable`](crate::ser::HooksError::ValueNotSerializable)
+    /// message, since compound values only carry metadata and cannot be serialized.
+    ///
     /// # Panics
     ///
-    /// A value can only be replaced once. This method will panic if the value has already been replaced.
+    /// A value can only be replaced once. This method will panic if the value has already been replaced
+    /// or skipped.
     pub fn replace<T: Serialize + ?Sized>(&mut self, new_value: &T) -> &mut Self {
         let serializer = match self.action.take().unwrap() {
             ValueAction::ContinueSerialization(s) => s,
             ValueAction::ValueReplaced(_) => panic!("value already replaced"),
+            ValueAction::Skipped(_) => panic!("value already skipped"),
         };
+
+        // Only a compound `Value` can fail this check, other types are not checked,
+        // so that they are not serialized twice.
+        if is_value::<T>() {
+            if let Err(err) = check_if_can_serialize(new_value) {
+                self.action = Some(ValueAction::ContinueSerialization(serializer));
+                self.fail_serialization(err);
+                return self;
+            }
+        }
+
         let res = new_value.serialize(serializer);
         self.action = Some(ValueAction::ValueReplaced(res));
         self
     }
+
+    /// Replace the value with another serializable value, built from the current one.
+    ///
+    /// Same as [`replace`](Self::replace), but the new value is returned by `f`, which is
+    /// given the current [`value`](Self::value). This allows computing the replacement
+    /// without borrowing the scope twice:
+    /// ```
+    /// # use serde_hooks::{ser, Path};
+    /// struct Hooks;
+    /// impl ser::Hooks for Hooks {
+    ///     fn on_value<S: serde::Serializer>(&self, _path: &Path, value: &mut ser::ValueScope<S>) {
+    ///         if value.value().as_i64().is_some() {
+    ///             // doubling all integers
+    ///             value.replace_with(|v| v.as_i64().map(|v| v * 2));
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// A value can only be replaced once. This method will panic if the value has already been replaced
+    /// or skipped.
+    pub fn replace_with<T: Serialize>(&mut self, f: impl FnOnce(&Value) -> T) -> &mut Self {
+        let new_value = f(&self.value);
+        self.replace(&new_value)
+    }
+
+    /// Skip the value, so that nothing is serialized for it.
+    ///
+    /// Skipping a struct field omits the field, skipping a sequence element omits
+    /// the element, and skipping a map value omits the whole map entry, as if the
+    /// value was not there at all.
+    ///
+    /// **Skipping only works if it's allowed with
+    /// [`HookOptions::allow_skipping_values`](crate::ser::HookOptions::allow_skipping_values)**,
+    /// which is off by default, see there for the performance implications. With default
+    /// options, calling this method never omits the value, and reports an error instead.
+    ///
+    /// Values that are not contained in a struct, a sequence or a map (e.g. the root value),
+    /// values of maps serialized with separate `serialize_key` and `serialize_value` calls,
+    /// as well as values inserted by hooks, can't be skipped either.
+    ///
+    /// In all these cases a [`HooksError::CannotSkipValue`](crate::ser::HooksError::CannotSkipValue)
+    /// error is passed to [`on_scope_error`](crate::ser::Hooks::on_scope_error), which fails
+    /// serialization, unless the error is ignored. If ignored, the value is serialized as usual.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the value has already been replaced or skipped.
+    pub fn skip(&mut self) -> &mut Self {
+        let serializer = match self.action.take().unwrap() {
+            ValueAction::ContinueSerialization(s) => s,
+            ValueAction::ValueReplaced(_) => panic!("value already replaced"),
+            ValueAction::Skipped(_) => panic!("value already skipped"),
+        };
+        self.action = Some(ValueAction::Skipped(serializer));
+        self
+    }
+
+    /// Transform a numeric value with a function on `f64`.
+    ///
+    /// If the value is a number ([`Value::I8`] to [`Value::F64`]), it's converted to `f64`,
+    /// passed to `f`, and the result is converted back to the original type, which is
+    /// then fed to the serializer instead of the original value. E.g. scaling a `u32` by `1.5`
+    /// still produces a `u32`. Non-numeric values are left untouched.
+    ///
+    /// Conversions follow the `as` casting rules: the result is rounded towards zero and
+    /// saturated to the range of integer types, `NaN` becomes `0`. Note that `i64`, `u64`, `i128`
+    /// and `u128` values can't all be represented exactly as `f64`, so large integers may
+    /// lose precision even if `f` does nothing. Use [`map_integer`](Self::map_integer)
+    /// for exact integer arithmetic.
+    ///
+    /// # Panics
+    ///
+    /// A value can only be replaced once. This method will panic if the value has already been
+    /// replaced, and is numeric.
+    pub fn map_number(&mut self, f: impl FnOnce(f64) -> f64) -> &mut Self {
+        macro_rules! map {
+            ($($variant:ident => $type:ty),*) => {
+                match self.value {
+                    $(Value::$variant(v) => Value::$variant(f(v as f64) as $type),)*
+                    _ => return self,
+                }
+            };
+        }
+
+        let new_value = map!(
+            I8 => i8, I16 => i16, I32 => i32, I64 => i64, I128 => i128,
+            U8 => u8, U16 => u16, U32 => u32, U64 => u64, U128 => u128,
+            F32 => f32, F64 => f64
+        );
+        self.replace(&new_value);
+        self.value = new_value;
+        self
+    }
+
+    /// Transform an integer value with a function on `i128`.
+    ///
+    /// If the value is an integer ([`Value::I8`] to [`Value::U128`]), it's passed to `f`,
+    /// and the result is converted back to the original type, saturating to its range.
+    /// The converted value is then fed to the serializer instead of the original one.
+    /// Floating point and non-numeric values are left untouched, as well as `u128` values
+    /// above `i128::MAX`.
+    ///
+    /// # Panics
+    ///
+    /// A value can only be replaced once. This method will panic if the value has already been
+    /// replaced, and is an integer.
+    pub fn map_integer(&mut self, f: impl FnOnce(i128) -> i128) -> &mut Self {
+        macro_rules! map {
+            ($($variant:ident => $type:ty),*) => {
+                match self.value {
+                    $(Value::$variant(v) => match i128::try_from(v) {
+                        Ok(v) => {
+                            let max = i128::try_from(<$type>::MAX).unwrap_or(i128::MAX);
+                            Value::$variant(f(v).clamp(<$type>::MIN as i128, max) as $type)
+                        },
+                        Err(_) => return self,
+                    },)*
+                    _ => return self,
+                }
+            };
+        }
+
+        let new_value = map!(
+            I8 => i8, I16 => i16, I32 => i32, I64 => i64, I128 => i128,
+            U8 => u8, U16 => u16, U32 => u32, U64 => u64, U128 => u128
+        );
+        self.replace(&new_value);
+        self.value = new_value;
+        self
+    }
+
+    /// Replace this sequence element with several elements.
+    ///
+    /// The first of the `values` takes the place of the current element, the rest are
+    /// inserted right after it. The elements are fed directly into the serializer,
+    /// and hooks will **not** be called for them.
+    ///
+    /// Expanding must be allowed with [`HookOptions::allow_expanding_elements`](crate::ser::HookOptions::allow_expanding_elements),
+    /// so that the serializer is not given a length hint that the expansion would break.
+    /// Otherwise serialization fails with a custom error.
+    ///
+    /// This is only possible when the value is an element of a sequence or a tuple,
+    /// i.e. when the last segment of the path is a [`PathSegment::SeqElement`](crate::PathSegment::SeqElement).
+    /// In any other context serialization fails with a custom error.
+    ///
+    /// As with [`SeqScope::replace_value`](crate::ser::SeqScope::replace_value), only primitive
+    /// values can be serialized from [`StaticValue`]. Passing in a compound value, or an empty
+    /// list of values, fails serialization with a custom error.
+    ///
+    /// # Panics
+    ///
+    /// A value can only be replaced once. This method will panic if the value has already been replaced.
+    pub fn expand_into_elements(&mut self, values: Vec<StaticValue>) -> &mut Self {
+        if !self.is_seq_element {
+            self.fail_serialization("cannot expand a value that is not a sequence element");
+            return self;
+        }
+
+        if !self.is_expanding_allowed {
+            self.fail_serialization("expanding elements is not allowed");
+            return self;
+        }
+
+        if let Err(err) = values.iter().try_for_each(|v| v.check_if_can_serialize()) {
+            self.fail_serialization(err);
+            return self;
+        }
+
+        let mut values = values.into_iter();
+        match values.next() {
+            Some(first) => {
+                self.replace(&first);
+                self.expanded_elements = values.collect();
+            }
+            None => self.fail_serialization("cannot expand a value into no elements"),
+        }
+        self
+    }
 }
@@ -78,6 +78,21 @@ impl EnumVariantScope {
         self
     }
 
+    /// Set a new enum name, computed from the current one.
+    ///
+    /// `f` is called with the original [enum name](Self::enum_name), and returns
+    /// the new name. Use this when the new name can't be expressed with a [`Case`],
+    /// e.g. to add a version suffix.
+    ///
+    /// Since the new name is produced in runtime, this leads to special handling described
+    /// in [Static strings](crate::ser#static-strings).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn rename_enum_with(&mut self, f: impl FnOnce(&str) -> String) -> &mut Self {
+        let new_enum_name = f(self.enum_name);
+        self.rename_enum(new_enum_name)
+    }
+
     /// Rename enum name according to the given case convention.
     ///
     /// This effectively changes the enum type that is fed into your serializer
@@ -110,6 +125,21 @@ impl EnumVariantScope {
         self
     }
 
+    /// Set a new variant name, computed from the current one.
+    ///
+    /// `f` is called with the original [variant name](Self::variant_name), and returns
+    /// the new name. Use this when the new name can't be expressed with a [`Case`],
+    /// e.g. to add a prefix or a suffix.
+    ///
+    /// Since the new name is produced in runtime, this leads to special handling described
+    /// in [Static strings](crate::ser#static-strings).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn rename_variant_with(&mut self, f: impl FnOnce(&str) -> String) -> &mut Self {
+        let new_variant_name = f(self.variant_name);
+        self.rename_variant(new_variant_name)
+    }
+
     /// Rename variant name according to the given case convention.
     ///
     /// Serde expects enum variant names to be known at compile time, and as such, to be static.
@@ -131,4 +161,29 @@ impl EnumVariantScope {
             .push(VariantAction::ChangeVariantIndex(new_variant_index));
         self
     }
+
+    /// Serialize this variant as internally tagged.
+    ///
+    /// Runtime equivalent to `#[serde(tag = "...")]` on the enum: instead of wrapping the
+    /// variant content with the variant name, the variant name is written to the `tag`
+    /// field, next to the fields of the content.
+    ///
+    /// Struct variants are fed to the serializer as a struct with the tag field first,
+    /// unit variants as a struct with only the tag field. The content of newtype variants
+    /// is flattened into a map after the tag entry, so it must be a struct or a map,
+    /// otherwise [`CannotFlattenUnsupportedDataType`](crate::ser::HooksError::CannotFlattenUnsupportedDataType)
+    /// is produced. Tuple variants cannot be internally tagged and are serialized as is.
+    ///
+    /// The tag value is the variant name after any renames. Passing in an owned `String` for
+    /// the tag leads to special handling described in [Static strings](crate::ser#static-strings).
+    ///
+    /// Returns `self` to allow chaining calls.
+    pub fn serialize_as_internally_tagged(
+        &mut self,
+        tag: impl Into<Cow<'static, str>>,
+    ) -> &mut Self {
+        self.actions
+            .push(VariantAction::InternallyTagged(tag.into()));
+        self
+    }
 }
@@ -0,0 +1,182 @@
+use serde::Serializer;
+
+use super::{
+    BytesScope, EndScope, EnumVariantScope, ErrorScope, Hooks, MapKeyScope, MapScope,
+    NewtypeStructScope, OptionScope, SeqScope, StartScope, StructScope, TupleScope,
+    TupleStructScope, UnitStructScope, ValueScope,
+};
+use crate::{Path, Value};
+
+/// Hooks that are only called within a subtree of the serialized data.
+///
+/// Created with [`scoped`].
+#[derive(Debug, Clone)]
+pub struct ScopedHooks<H> {
+    prefix: String,
+    hooks: H,
+}
+
+/// Wrap `hooks` so that they are only called at and below the `prefix` path.
+///
+/// The `prefix` is a path in its [string representation](Path::borrow_str), e.g.
+/// `"response.data"` or `"items[0]"`, and matches whole path segments only, so
+/// `"response.data"` does not match `"response.database"`. An empty prefix matches
+/// every path.
+///
+/// The wrapped hooks see paths relative to the prefix, i.e. the value at the prefix
+/// is the root. [`on_start`](Hooks::on_start) and [`on_end`](Hooks::on_end) have no
+/// path and are always called.
+///
+/// Scoped hooks can be nested, the prefixes are then joined.
+///
+/// ```
+/// use serde::Serialize;
+/// use serde_hooks::{ser, Path};
+///
+/// #[derive(Serialize)]
+/// struct Response {
+///     id: u32,
+///     data: Data,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Data {
+///     id: u32,
+/// }
+///
+/// struct Hooks;
+/// impl ser::Hooks for Hooks {
+///     fn on_struct(&self, path: &Path, st: &mut ser::StructScope) {
+///         assert!(path.is_root());
+///         st.rename_field("id", "data_id");
+///     }
+/// }
+///
+/// let response = Response {
+///     id: 1,
+///     data: Data { id: 2 },
+/// };
+/// let json = serde_json::to_string(&ser::hook(&response, &ser::scoped("data", Hooks))).unwrap();
+/// assert_eq!(json, r#"{"id":1,"data":{"data_id":2}}"#);
+/// ```
+pub fn scoped<H: Hooks>(prefix: &str, hooks: H) -> ScopedHooks<H> {
+    ScopedHooks {
+        prefix: prefix.to_owned(),
+        hooks,
+    }
+}
+
+impl<H> ScopedHooks<H> {
+    /// Returns the path prefix the hooks are scoped to.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns the wrapped hooks.
+    pub fn inner(&self) -> &H {
+        &self.hooks
+    }
+
+    /// Consumes the scoped hooks and returns the wrapped hooks.
+    pub fn into_inner(self) -> H {
+        self.hooks
+    }
+
+    fn forward(&self, path: &Path, f: impl FnOnce(&H, &Path)) {
+        if let Some(depth) = path.prefix_depth(&self.prefix) {
+            f(&self.hooks, &path.relative_to(depth));
+        }
+    }
+}
+
+impl<H: Hooks> Hooks for ScopedHooks<H> {
+    const ACTIVE: bool = H::ACTIVE;
+
+    fn on_start(&self, start: &mut StartScope) {
+        self.hooks.on_start(start);
+    }
+
+    fn on_end<Error: serde::ser::Error>(&self, end: &mut EndScope<Error>) {
+        self.hooks.on_end(end);
+    }
+
+    fn on_value<S: Serializer>(&self, path: &Path, value: &mut ValueScope<S>) {
+        self.forward(path, |hooks, path| hooks.on_value(path, value));
+    }
+
+    fn on_bytes<S: Serializer>(&self, path: &Path, bytes: &mut BytesScope<S>) {
+        self.forward(path, |hooks, path| hooks.on_bytes(path, bytes));
+    }
+
+    fn on_option<S: Serializer>(&self, path: &Path, opt: &mut OptionScope<S>) {
+        self.forward(path, |hooks, path| hooks.on_option(path, opt));
+    }
+
+    fn on_struct(&self, path: &Path, st: &mut StructScope) {
+        self.forward(path, |hooks, path| hooks.on_struct(path, st));
+    }
+
+    fn on_struct_end(&self, path: &Path, emitted_len: usize) {
+        self.forward(path, |hooks, path| hooks.on_struct_end(path, emitted_len));
+    }
+
+    fn on_unit_struct(&self, path: &Path, us: &mut UnitStructScope) {
+        self.forward(path, |hooks, path| hooks.on_unit_struct(path, us));
+    }
+
+    fn on_newtype_struct(&self, path: &Path, nt: &mut NewtypeStructScope) {
+        self.forward(path, |hooks, path| hooks.on_newtype_struct(path, nt));
+    }
+
+    fn on_seq(&self, path: &Path, seq: &mut SeqScope) {
+        self.forward(path, |hooks, path| hooks.on_seq(path, seq));
+    }
+
+    fn on_seq_element_end(&self, path: &Path, index: usize) {
+        self.forward(path, |hooks, path| hooks.on_seq_element_end(path, index));
+    }
+
+    fn on_tuple(&self, path: &Path, tpl: &mut TupleScope, seq: &mut SeqScope) {
+        self.forward(path, |hooks, path| hooks.on_tuple(path, tpl, seq));
+    }
+
+    fn on_tuple_struct(&self, path: &Path, tpl: &mut TupleStructScope, seq: &mut SeqScope) {
+        self.forward(path, |hooks, path| hooks.on_tuple_struct(path, tpl, seq));
+    }
+
+    fn on_map(&self, path: &Path, map: &mut MapScope) {
+        self.forward(path, |hooks, path| hooks.on_map(path, map));
+    }
+
+    fn on_map_key<S: Serializer>(&self, path: &Path, map_key: &mut MapKeyScope<S>) {
+        self.forward(path, |hooks, path| hooks.on_map_key(path, map_key));
+    }
+
+    fn on_map_entry_end(&self, path: &Path, key: &Value, index: usize) {
+        self.forward(path, |hooks, path| hooks.on_map_entry_end(path, key, index));
+    }
+
+    fn on_enum_variant(&self, path: &Path, ev: &mut EnumVariantScope) {
+        self.forward(path, |hooks, path| hooks.on_enum_variant(path, ev));
+    }
+
+    fn on_struct_variant(&self, path: &Path, ev: &mut EnumVariantScope, st: &mut StructScope) {
+        self.forward(path, |hooks, path| hooks.on_struct_variant(path, ev, st));
+    }
+
+    fn on_tuple_variant(
+        &self,
+        path: &Path,
+        ev: &mut EnumVariantScope,
+        tpl: &mut TupleScope,
+        seq: &mut SeqScope,
+    ) {
+        self.forward(path, |hooks, path| {
+            hooks.on_tuple_variant(path, ev, tpl, seq)
+        });
+    }
+
+    fn on_scope_error(&self, path: &Path, err: &mut ErrorScope) {
+        self.forward(path, |hooks, path| hooks.on_scope_error(path, err));
+    }
+}
@@ -0,0 +1,426 @@
+//! Tools for unit-testing hooks without a data format crate.
+//!
+//! [`RecordingSerializer`] records the calls that it receives as a log of
+//! [`SerializerEvent`]s, so that tests can assert exactly what a serializer gets to see.
+//!
+//! ```
+//! use serde::Serialize;
+//! use serde_hooks::{ser, ser::testing::SerializerEvent, Path, Value};
+//!
+//! #[derive(Serialize)]
+//! struct User {
+//!     name: &'static str,
+//!     password: &'static str,
+//! }
+//!
+//! struct Hooks;
+//! impl ser::Hooks for Hooks {
+//!     fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+//!         st.skip_field("password");
+//!     }
+//! }
+//!
+//! let user = User {
+//!     name: "Alice",
+//!     password: "secret",
+//! };
+//! let events = ser::testing::record_events(&ser::hook(&user, &Hooks)).unwrap();
+//!
+//! assert_eq!(
+//!     events,
+//!     [
+//!         SerializerEvent::Serialize(Value::Struct {
+//!             name: "User",
+//!             len: 2
+//!         }),
+//!         SerializerEvent::Field("name"),
+//!         SerializerEvent::Serialize(Value::Str("Alice".into())),
+//!         SerializerEvent::SkipField("password"),
+//!         SerializerEvent::End,
+//!     ]
+//! );
+//! ```
+
+use std::fmt::Display;
+
+use serde::ser::{
+    SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant,
+};
+use serde::{Serialize, Serializer};
+
+use crate::{StaticValue, Value};
+
+/// A call received by [`RecordingSerializer`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SerializerEvent {
+    /// A `serialize_*` call of the serializer, e.g. `serialize_i32` or `serialize_struct`,
+    /// described by the corresponding value.
+    ///
+    /// For compound values, the contents are recorded in the follow-up events.
+    Serialize(StaticValue),
+    /// `serialize_element` of a sequence or a tuple, or `serialize_field` of a tuple struct
+    /// or a tuple variant. The element value follows.
+    Element,
+    /// `serialize_key` of a map. The key value follows.
+    Key,
+    /// `serialize_value` of a map. The value follows.
+    Value,
+    /// `serialize_field` of a struct or a struct variant. The field value follows.
+    Field(&'static str),
+    /// `skip_field` of a struct or a struct variant.
+    SkipField(&'static str),
+    /// `end` of a compound value.
+    End,
+}
+
+/// Error produced by [`RecordingSerializer`].
+///
+/// The serializer itself never fails, this carries custom errors, e.g. the ones
+/// produced by hooks.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct Error(String);
+
+impl serde::ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self(msg.to_string())
+    }
+}
+
+/// Serializes the value with [`RecordingSerializer`] and returns the recorded events.
+pub fn record_events<T: Serialize + ?Sized>(value: &T) -> Result<Vec<SerializerEvent>, Error> {
+    let mut events = Vec::new();
+    value.serialize(RecordingSerializer::new(&mut events))?;
+    Ok(events)
+}
+
+/// A serializer that records the calls it receives, see [`SerializerEvent`].
+pub struct RecordingSerializer<'e> {
+    events: &'e mut Vec<SerializerEvent>,
+}
+
+impl<'e> RecordingSerializer<'e> {
+    /// Creates a serializer that appends events to `events`.
+    pub fn new(events: &'e mut Vec<SerializerEvent>) -> Self {
+        Self { events }
+    }
+
+    fn record(&mut self, event: SerializerEvent) -> Result<(), Error> {
+        self.events.push(event);
+        Ok(())
+    }
+
+    fn record_value(&mut self, value: Value) -> Result<(), Error> {
+        self.record(SerializerEvent::Serialize(value.into_owned()))
+    }
+
+    fn serialize_nested<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(RecordingSerializer::new(self.events))
+    }
+}
+
+impl<'e> Serializer for RecordingSerializer<'e> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(mut self, v: bool) -> Result<(), Error> {
+        self.record_value(Value::Bool(v))
+    }
+
+    fn serialize_i8(mut self, v: i8) -> Result<(), Error> {
+        self.record_value(Value::I8(v))
+    }
+
+    fn serialize_i16(mut self, v: i16) -> Result<(), Error> {
+        self.record_value(Value::I16(v))
+    }
+
+    fn serialize_i32(mut self, v: i32) -> Result<(), Error> {
+        self.record_value(Value::I32(v))
+    }
+
+    fn serialize_i64(mut self, v: i64) -> Result<(), Error> {
+        self.record_value(Value::I64(v))
+    }
+
+    fn serialize_i128(mut self, v: i128) -> Result<(), Error> {
+        self.record_value(Value::I128(v))
+    }
+
+    fn serialize_u8(mut self, v: u8) -> Result<(), Error> {
+        self.record_value(Value::U8(v))
+    }
+
+    fn serialize_u16(mut self, v: u16) -> Result<(), Error> {
+        self.record_value(Value::U16(v))
+    }
+
+    fn serialize_u32(mut self, v: u32) -> Result<(), Error> {
+        self.record_value(Value::U32(v))
+    }
+
+    fn serialize_u64(mut self, v: u64) -> Result<(), Error> {
+        self.record_value(Value::U64(v))
+    }
+
+    fn serialize_u128(mut self, v: u128) -> Result<(), Error> {
+        self.record_value(Value::U128(v))
+    }
+
+    fn serialize_f32(mut self, v: f32) -> Result<(), Error> {
+        self.record_value(Value::F32(v))
+    }
+
+    fn serialize_f64(mut self, v: f64) -> Result<(), Error> {
+        self.record_value(Value::F64(v))
+    }
+
+    fn serialize_char(mut self, v: char) -> Result<(), Error> {
+        self.record_value(Value::Char(v))
+    }
+
+    fn serialize_str(mut self, v: &str) -> Result<(), Error> {
+        self.record_value(Value::Str(v.into()))
+    }
+
+    fn serialize_bytes(mut self, v: &[u8]) -> Result<(), Error> {
+        self.record_value(Value::Bytes(v.into()))
+    }
+
+    fn serialize_none(mut self) -> Result<(), Error> {
+        self.record_value(Value::None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(mut self, value: &T) -> Result<(), Error> {
+        self.record_value(Value::Some)?;
+        self.serialize_nested(value)
+    }
+
+    fn serialize_unit(mut self) -> Result<(), Error> {
+        self.record_value(Value::Unit)
+    }
+
+    fn serialize_unit_struct(mut self, name: &'static str) -> Result<(), Error> {
+        self.record_value(Value::UnitStruct(name))
+    }
+
+    fn serialize_unit_variant(
+        mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.record_value(Value::UnitVariant {
+            name,
+            variant_index,
+            variant,
+        })
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        mut self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.record_value(Value::NewtypeStruct(name))?;
+        self.serialize_nested(value)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.record_value(Value::NewtypeVariant {
+            name,
+            variant_index,
+            variant,
+        })?;
+        self.serialize_nested(value)
+    }
+
+    fn serialize_seq(mut self, len: Option<usize>) -> Result<Self, Error> {
+        self.record_value(Value::Seq(len))?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(mut self, len: usize) -> Result<Self, Error> {
+        self.record_value(Value::Tuple(len))?;
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(mut self, name: &'static str, len: usize) -> Result<Self, Error> {
+        self.record_value(Value::TupleStruct { name, len })?;
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self, Error> {
+        self.record_value(Value::TupleVariant {
+            name,
+            variant_index,
+            variant,
+            len,
+        })?;
+        Ok(self)
+    }
+
+    fn serialize_map(mut self, len: Option<usize>) -> Result<Self, Error> {
+        self.record_value(Value::Map(len))?;
+        Ok(self)
+    }
+
+    fn serialize_struct(mut self, name: &'static str, len: usize) -> Result<Self, Error> {
+        self.record_value(Value::Struct { name, len })?;
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self, Error> {
+        self.record_value(Value::StructVariant {
+            name,
+            variant_index,
+            variant,
+            len,
+        })?;
+        Ok(self)
+    }
+}
+
+impl<'e> SerializeSeq for RecordingSerializer<'e> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.record(SerializerEvent::Element)?;
+        self.serialize_nested(value)
+    }
+
+    fn end(mut self) -> Result<(), Error> {
+        self.record(SerializerEvent::End)
+    }
+}
+
+impl<'e> SerializeTuple for RecordingSerializer<'e> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'e> SerializeTupleStruct for RecordingSerializer<'e> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'e> SerializeTupleVariant for RecordingSerializer<'e> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<'e> SerializeMap for RecordingSerializer<'e> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.record(SerializerEvent::Key)?;
+        self.serialize_nested(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.record(SerializerEvent::Value)?;
+        self.serialize_nested(value)
+    }
+
+    fn end(mut self) -> Result<(), Error> {
+        self.record(SerializerEvent::End)
+    }
+}
+
+impl<'e> SerializeStruct for RecordingSerializer<'e> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.record(SerializerEvent::Field(key))?;
+        self.serialize_nested(value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        self.record(SerializerEvent::SkipField(key))
+    }
+
+    fn end(mut self) -> Result<(), Error> {
+        self.record(SerializerEvent::End)
+    }
+}
+
+impl<'e> SerializeStructVariant for RecordingSerializer<'e> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        SerializeStruct::skip_field(self, key)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        SerializeStruct::end(self)
+    }
+}
@@ -45,198 +45,233 @@ impl Serialize for Value<'_> {
 
 impl Value<'_> {
     pub(crate) fn check_if_can_serialize(&self) -> Result<(), HooksError> {
-        struct FauxSerializer;
+        check_if_can_serialize(self)
+    }
+}
 
-        #[derive(Debug, thiserror::Error)]
+/// Returns `true` if `T` is a [`Value`], of any lifetime.
+///
+/// [`TypeId`](std::any::TypeId) requires `'static` types, so the type names are compared
+/// instead. This is only used to skip unnecessary checks, so a false positive is harmless.
+pub(crate) fn is_value<T: ?Sized>() -> bool {
+    std::any::type_name::<T>() == std::any::type_name::<Value>()
+}
+
+/// Checks if a value can be serialized, without actually serializing it.
+///
+/// The check only looks at the very first serializer call the value makes. Compound
+/// types (structs, sequences etc.) are always considered serializable, their contents are
+/// not inspected. The main purpose is to catch compound [`Value`]s, that only carry metadata
+/// and fail serialization right away.
+pub(crate) fn check_if_can_serialize<T: Serialize + ?Sized>(value: &T) -> Result<(), HooksError> {
+    struct FauxSerializer;
+
+    #[derive(Debug, thiserror::Error)]
+    enum FauxError {
         #[error("{0}")]
-        struct FauxError(String);
-
-        impl serde::ser::Error for FauxError {
-            fn custom<T>(msg: T) -> Self
-            where
-                T: Display,
-            {
-                Self(msg.to_string())
-            }
-        }
-
-        impl Serializer for FauxSerializer {
-            type Ok = ();
-            type Error = FauxError;
-            type SerializeSeq = Impossible<Self::Ok, Self::Error>;
-            type SerializeTuple = Impossible<Self::Ok, Self::Error>;
-            type SerializeTupleStruct = Impossible<Self::Ok, Self::Error>;
-            type SerializeTupleVariant = Impossible<Self::Ok, Self::Error>;
-            type SerializeMap = Impossible<Self::Ok, Self::Error>;
-            type SerializeStruct = Impossible<Self::Ok, Self::Error>;
-            type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
-
-            fn serialize_bool(self, _v: bool) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_i8(self, _v: i8) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_i16(self, _v: i16) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_i32(self, _v: i32) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_i64(self, _v: i64) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_i128(self, _v: i128) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_u8(self, _v: u8) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_u16(self, _v: u16) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_u32(self, _v: u32) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_u64(self, _v: u64) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_u128(self, _v: u128) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_f64(self, _v: f64) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_char(self, _v: char) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_str(self, _v: &str) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_some<T: ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error>
-            where
-                T: Serialize,
-            {
-                unreachable!()
-            }
-
-            fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_unit_variant(
-                self,
-                _name: &'static str,
-                _variant_index: u32,
-                _variant: &'static str,
-            ) -> Result<Self::Ok, Self::Error> {
-                Ok(())
-            }
-
-            fn serialize_newtype_struct<T: ?Sized>(
-                self,
-                _name: &'static str,
-                _value: &T,
-            ) -> Result<Self::Ok, Self::Error>
-            where
-                T: Serialize,
-            {
-                unreachable!()
-            }
-
-            fn serialize_newtype_variant<T: ?Sized>(
-                self,
-                _name: &'static str,
-                _variant_index: u32,
-                _variant: &'static str,
-                _value: &T,
-            ) -> Result<Self::Ok, Self::Error>
-            where
-                T: Serialize,
-            {
-                unreachable!()
-            }
-
-            fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-                unreachable!()
-            }
-
-            fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-                unreachable!()
-            }
-
-            fn serialize_tuple_struct(
-                self,
-                _name: &'static str,
-                _len: usize,
-            ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-                unreachable!()
-            }
-
-            fn serialize_tuple_variant(
-                self,
-                _name: &'static str,
-                _variant_index: u32,
-                _variant: &'static str,
-                _len: usize,
-            ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-                unreachable!()
-            }
-
-            fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-                unreachable!()
-            }
-
-            fn serialize_struct(
-                self,
-                _name: &'static str,
-                _len: usize,
-            ) -> Result<Self::SerializeStruct, Self::Error> {
-                unreachable!()
-            }
-
-            fn serialize_struct_variant(
-                self,
-                _name: &'static str,
-                _variant_index: u32,
-                _variant: &'static str,
-                _len: usize,
-            ) -> Result<Self::SerializeStructVariant, Self::Error> {
-                unreachable!()
-            }
-        }
-
-        self.serialize(FauxSerializer)
-            .map_err(|err| HooksError::ValueNotSerializable(err.0))
+        Custom(String),
+        #[error("compound")]
+        Compound,
+    }
+
+    impl serde::ser::Error for FauxError {
+        fn custom<T>(msg: T) -> Self
+        where
+            T: Display,
+        {
+            Self::Custom(msg.to_string())
+        }
     }
+
+    impl Serializer for FauxSerializer {
+        type Ok = ();
+        type Error = FauxError;
+        type SerializeSeq = Impossible<Self::Ok, Self::Error>;
+        type SerializeTuple = Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleStruct = Impossible<Self::Ok, Self::Error>;
+        type SerializeTupleVariant = Impossible<Self::Ok, Self::Error>;
+        type SerializeMap = Impossible<Self::Ok, Self::Error>;
+        type SerializeStruct = Impossible<Self::Ok, Self::Error>;
+        type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
+
+        fn serialize_bool(self, _v: bool) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_i8(self, _v: i8) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_i16(self, _v: i16) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_i32(self, _v: i32) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_i64(self, _v: i64) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_i128(self, _v: i128) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_u8(self, _v: u8) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_u16(self, _v: u16) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_u32(self, _v: u32) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_u64(self, _v: u64) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_u128(self, _v: u128) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_f64(self, _v: f64) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_char(self, _v: char) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_str(self, _v: &str) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_some<T: ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+        where
+            T: Serialize,
+        {
+            Ok(())
+        }
+
+        fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_unit_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+        ) -> Result<Self::Ok, Self::Error> {
+            Ok(())
+        }
+
+        fn serialize_newtype_struct<T: ?Sized>(
+            self,
+            _name: &'static str,
+            _value: &T,
+        ) -> Result<Self::Ok, Self::Error>
+        where
+            T: Serialize,
+        {
+            Ok(())
+        }
+
+        fn serialize_newtype_variant<T: ?Sized>(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            _value: &T,
+        ) -> Result<Self::Ok, Self::Error>
+        where
+            T: Serialize,
+        {
+            Ok(())
+        }
+
+        fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+            Err(FauxError::Compound)
+        }
+
+        fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+            Err(FauxError::Compound)
+        }
+
+        fn serialize_tuple_struct(
+            self,
+            _name: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+            Err(FauxError::Compound)
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+            Err(FauxError::Compound)
+        }
+
+        fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+            Err(FauxError::Compound)
+        }
+
+        fn serialize_struct(
+            self,
+            _name: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeStruct, Self::Error> {
+            Err(FauxError::Compound)
+        }
+
+        fn serialize_struct_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeStructVariant, Self::Error> {
+            Err(FauxError::Compound)
+        }
+    }
+
+    match value.serialize(FauxSerializer) {
+        Ok(()) | Err(FauxError::Compound) => Ok(()),
+        Err(FauxError::Custom(msg)) => Err(HooksError::ValueNotSerializable(msg)),
+    }
+}
+
+#[test]
+fn test_is_value() {
+    use crate::StaticValue;
+
+    assert!(is_value::<Value>());
+    assert!(is_value::<StaticValue>());
+    assert!(!is_value::<u32>());
+    assert!(!is_value::<Option<Value>>());
+    assert!(!is_value::<str>());
 }
@@ -0,0 +1,632 @@
+use std::fmt::Display;
+
+use serde::ser::{
+    Error, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant,
+};
+use serde::{Serialize, Serializer};
+
+use crate::Value;
+
+/// Owned copy of serialized data, used to buffer values that need to be fed
+/// to the serializer later (e.g. to reorder them).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Content {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    None,
+    Some(Box<Content>),
+    Unit,
+    UnitStruct(&'static str),
+    UnitVariant(&'static str, u32, &'static str),
+    NewtypeStruct(&'static str, Box<Content>),
+    NewtypeVariant(&'static str, u32, &'static str, Box<Content>),
+    Seq(Vec<Content>),
+    Tuple(Vec<Content>),
+    TupleStruct(&'static str, Vec<Content>),
+    TupleVariant(&'static str, u32, &'static str, Vec<Content>),
+    Map(Vec<(Content, Content)>),
+    Struct(&'static str, Vec<(&'static str, Content)>),
+    StructVariant(
+        &'static str,
+        u32,
+        &'static str,
+        Vec<(&'static str, Content)>,
+    ),
+}
+
+impl Content {
+    /// Captures a serializable value, preserving `is_human_readable` of the
+    /// serializer the content is going to be fed into later.
+    ///
+    /// Errors are converted into `E` by their message.
+    pub(crate) fn capture<T: Serialize + ?Sized, E: serde::ser::Error>(
+        value: &T,
+        is_human_readable: bool,
+    ) -> Result<Content, E> {
+        value
+            .serialize(ContentSerializer::new(is_human_readable))
+            .map_err(|ContentError(msg)| E::custom(msg))
+    }
+
+    /// Returns the content as a [`Value`], with metadata only for compound contents.
+    pub(crate) fn as_value(&self) -> Value<'_> {
+        match self {
+            Content::Bool(v) => Value::Bool(*v),
+            Content::I8(v) => Value::I8(*v),
+            Content::I16(v) => Value::I16(*v),
+            Content::I32(v) => Value::I32(*v),
+            Content::I64(v) => Value::I64(*v),
+            Content::I128(v) => Value::I128(*v),
+            Content::U8(v) => Value::U8(*v),
+            Content::U16(v) => Value::U16(*v),
+            Content::U32(v) => Value::U32(*v),
+            Content::U64(v) => Value::U64(*v),
+            Content::U128(v) => Value::U128(*v),
+            Content::F32(v) => Value::F32(*v),
+            Content::F64(v) => Value::F64(*v),
+            Content::Char(v) => Value::Char(*v),
+            Content::Str(v) => Value::Str(v.as_str().into()),
+            Content::Bytes(v) => Value::Bytes(v.as_slice().into()),
+            Content::None => Value::None,
+            Content::Some(_) => Value::Some,
+            Content::Unit => Value::Unit,
+            Content::UnitStruct(name) => Value::UnitStruct(name),
+            Content::UnitVariant(name, variant_index, variant) => Value::UnitVariant {
+                name,
+                variant_index: *variant_index,
+                variant,
+            },
+            Content::NewtypeStruct(name, _) => Value::NewtypeStruct(name),
+            Content::NewtypeVariant(name, variant_index, variant, _) => Value::NewtypeVariant {
+                name,
+                variant_index: *variant_index,
+                variant,
+            },
+            Content::Seq(v) => Value::Seq(Some(v.len())),
+            Content::Tuple(v) => Value::Tuple(v.len()),
+            Content::TupleStruct(name, v) => Value::TupleStruct { name, len: v.len() },
+            Content::TupleVariant(name, variant_index, variant, v) => Value::TupleVariant {
+                name,
+                variant_index: *variant_index,
+                variant,
+                len: v.len(),
+            },
+            Content::Map(v) => Value::Map(Some(v.len())),
+            Content::Struct(name, v) => Value::Struct { name, len: v.len() },
+            Content::StructVariant(name, variant_index, variant, v) => Value::StructVariant {
+                name,
+                variant_index: *variant_index,
+                variant,
+                len: v.len(),
+            },
+        }
+    }
+}
+
+impl Serialize for Content {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Content::Bool(v) => serializer.serialize_bool(*v),
+            Content::I8(v) => serializer.serialize_i8(*v),
+            Content::I16(v) => serializer.serialize_i16(*v),
+            Content::I32(v) => serializer.serialize_i32(*v),
+            Content::I64(v) => serializer.serialize_i64(*v),
+            Content::I128(v) => serializer.serialize_i128(*v),
+            Content::U8(v) => serializer.serialize_u8(*v),
+            Content::U16(v) => serializer.serialize_u16(*v),
+            Content::U32(v) => serializer.serialize_u32(*v),
+            Content::U64(v) => serializer.serialize_u64(*v),
+            Content::U128(v) => serializer.serialize_u128(*v),
+            Content::F32(v) => serializer.serialize_f32(*v),
+            Content::F64(v) => serializer.serialize_f64(*v),
+            Content::Char(v) => serializer.serialize_char(*v),
+            Content::Str(v) => serializer.serialize_str(v),
+            Content::Bytes(v) => serializer.serialize_bytes(v),
+            Content::None => serializer.serialize_none(),
+            Content::Some(v) => serializer.serialize_some(v.as_ref()),
+            Content::Unit => serializer.serialize_unit(),
+            Content::UnitStruct(name) => serializer.serialize_unit_struct(name),
+            Content::UnitVariant(name, variant_index, variant) => {
+                serializer.serialize_unit_variant(name, *variant_index, variant)
+            }
+            Content::NewtypeStruct(name, v) => {
+                serializer.serialize_newtype_struct(name, v.as_ref())
+            }
+            Content::NewtypeVariant(name, variant_index, variant, v) => {
+                serializer.serialize_newtype_variant(name, *variant_index, variant, v.as_ref())
+            }
+            Content::Seq(elements) => {
+                let mut s = serializer.serialize_seq(Some(elements.len()))?;
+                for e in elements {
+                    s.serialize_element(e)?;
+                }
+                s.end()
+            }
+            Content::Tuple(elements) => {
+                let mut s = serializer.serialize_tuple(elements.len())?;
+                for e in elements {
+                    s.serialize_element(e)?;
+                }
+                s.end()
+            }
+            Content::TupleStruct(name, fields) => {
+                let mut s = serializer.serialize_tuple_struct(name, fields.len())?;
+                for f in fields {
+                    s.serialize_field(f)?;
+                }
+                s.end()
+            }
+            Content::TupleVariant(name, variant_index, variant, fields) => {
+                let mut s = serializer.serialize_tuple_variant(
+                    name,
+                    *variant_index,
+                    variant,
+                    fields.len(),
+                )?;
+                for f in fields {
+                    s.serialize_field(f)?;
+                }
+                s.end()
+            }
+            Content::Map(entries) => {
+                let mut s = serializer.serialize_map(Some(entries.len()))?;
+                for (k, v) in entries {
+                    s.serialize_entry(k, v)?;
+                }
+                s.end()
+            }
+            Content::Struct(name, fields) => {
+                let mut s = serializer.serialize_struct(name, fields.len())?;
+                for (k, v) in fields {
+                    s.serialize_field(k, v)?;
+                }
+                s.end()
+            }
+            Content::StructVariant(name, variant_index, variant, fields) => {
+                let mut s = serializer.serialize_struct_variant(
+                    name,
+                    *variant_index,
+                    variant,
+                    fields.len(),
+                )?;
+                for (k, v) in fields {
+                    s.serialize_field(k, v)?;
+                }
+                s.end()
+            }
+        }
+    }
+}
+
+// Using a concrete error type here, rather than the one of the target serializer,
+// because the captured values are serialized with hooks, and the wrappers nest
+// error types (e.g. for flattening), which would lead to infinitely nested generics.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub(crate) struct ContentError(String);
+
+impl serde::ser::Error for ContentError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        ContentError(msg.to_string())
+    }
+}
+
+pub(crate) struct ContentSerializer {
+    is_human_readable: bool,
+}
+
+impl ContentSerializer {
+    fn new(is_human_readable: bool) -> Self {
+        Self { is_human_readable }
+    }
+
+    fn capture<T: Serialize + ?Sized>(&self, value: &T) -> Result<Content, ContentError> {
+        value.serialize(ContentSerializer::new(self.is_human_readable))
+    }
+}
+
+impl Serializer for ContentSerializer {
+    type Ok = Content;
+    type Error = ContentError;
+    type SerializeSeq = SerializeContentSeq;
+    type SerializeTuple = SerializeContentSeq;
+    type SerializeTupleStruct = SerializeContentSeq;
+    type SerializeTupleVariant = SerializeContentSeq;
+    type SerializeMap = SerializeContentMap;
+    type SerializeStruct = SerializeContentStruct;
+    type SerializeStructVariant = SerializeContentStruct;
+
+    fn serialize_bool(self, v: bool) -> Result<Content, ContentError> {
+        Ok(Content::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Content, ContentError> {
+        Ok(Content::I8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Content, ContentError> {
+        Ok(Content::I16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Content, ContentError> {
+        Ok(Content::I32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Content, ContentError> {
+        Ok(Content::I64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Content, ContentError> {
+        Ok(Content::I128(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Content, ContentError> {
+        Ok(Content::U8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Content, ContentError> {
+        Ok(Content::U16(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Content, ContentError> {
+        Ok(Content::U32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Content, ContentError> {
+        Ok(Content::U64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Content, ContentError> {
+        Ok(Content::U128(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Content, ContentError> {
+        Ok(Content::F32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Content, ContentError> {
+        Ok(Content::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Content, ContentError> {
+        Ok(Content::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Content, ContentError> {
+        Ok(Content::Str(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Content, ContentError> {
+        Ok(Content::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Content, ContentError> {
+        Ok(Content::None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Content, ContentError> {
+        Ok(Content::Some(Box::new(self.capture(value)?)))
+    }
+
+    fn serialize_unit(self) -> Result<Content, ContentError> {
+        Ok(Content::Unit)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Content, ContentError> {
+        Ok(Content::UnitStruct(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Content, ContentError> {
+        Ok(Content::UnitVariant(name, variant_index, variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Content, ContentError> {
+        Ok(Content::NewtypeStruct(name, Box::new(self.capture(value)?)))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Content, ContentError> {
+        Ok(Content::NewtypeVariant(
+            name,
+            variant_index,
+            variant,
+            Box::new(self.capture(value)?),
+        ))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, ContentError> {
+        Ok(SerializeContentSeq::new(
+            self,
+            SeqKind::Seq,
+            len.unwrap_or_default(),
+        ))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, ContentError> {
+        Ok(SerializeContentSeq::new(self, SeqKind::Tuple, len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, ContentError> {
+        Ok(SerializeContentSeq::new(
+            self,
+            SeqKind::TupleStruct(name),
+            len,
+        ))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, ContentError> {
+        Ok(SerializeContentSeq::new(
+            self,
+            SeqKind::TupleVariant(name, variant_index, variant),
+            len,
+        ))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, ContentError> {
+        Ok(SerializeContentMap {
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+            pending_key: None,
+            serializer: self,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, ContentError> {
+        Ok(SerializeContentStruct {
+            kind: StructKind::Struct(name),
+            fields: Vec::with_capacity(len),
+            serializer: self,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, ContentError> {
+        Ok(SerializeContentStruct {
+            kind: StructKind::StructVariant(name, variant_index, variant),
+            fields: Vec::with_capacity(len),
+            serializer: self,
+        })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.is_human_readable
+    }
+}
+
+enum SeqKind {
+    Seq,
+    Tuple,
+    TupleStruct(&'static str),
+    TupleVariant(&'static str, u32, &'static str),
+}
+
+pub(crate) struct SerializeContentSeq {
+    kind: SeqKind,
+    elements: Vec<Content>,
+    serializer: ContentSerializer,
+}
+
+impl SerializeContentSeq {
+    fn new(serializer: ContentSerializer, kind: SeqKind, len: usize) -> Self {
+        Self {
+            kind,
+            elements: Vec::with_capacity(len),
+            serializer,
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ContentError> {
+        self.elements.push(self.serializer.capture(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Content, ContentError> {
+        Ok(match self.kind {
+            SeqKind::Seq => Content::Seq(self.elements),
+            SeqKind::Tuple => Content::Tuple(self.elements),
+            SeqKind::TupleStruct(name) => Content::TupleStruct(name, self.elements),
+            SeqKind::TupleVariant(name, variant_index, variant) => {
+                Content::TupleVariant(name, variant_index, variant, self.elements)
+            }
+        })
+    }
+}
+
+impl SerializeSeq for SerializeContentSeq {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ContentError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        self.finish()
+    }
+}
+
+impl SerializeTuple for SerializeContentSeq {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ContentError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleStruct for SerializeContentSeq {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ContentError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleVariant for SerializeContentSeq {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ContentError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        self.finish()
+    }
+}
+
+pub(crate) struct SerializeContentMap {
+    entries: Vec<(Content, Content)>,
+    pending_key: Option<Content>,
+    serializer: ContentSerializer,
+}
+
+impl SerializeMap for SerializeContentMap {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), ContentError> {
+        self.pending_key = Some(self.serializer.capture(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ContentError> {
+        let key = self
+            .pending_key
+            .take()
+            .ok_or_else(|| ContentError::custom("serialize_value called before serialize_key"))?;
+        self.entries.push((key, self.serializer.capture(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        Ok(Content::Map(self.entries))
+    }
+}
+
+enum StructKind {
+    Struct(&'static str),
+    StructVariant(&'static str, u32, &'static str),
+}
+
+pub(crate) struct SerializeContentStruct {
+    kind: StructKind,
+    fields: Vec<(&'static str, Content)>,
+    serializer: ContentSerializer,
+}
+
+impl SerializeContentStruct {
+    fn push<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ContentError> {
+        self.fields.push((key, self.serializer.capture(value)?));
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Content, ContentError> {
+        Ok(match self.kind {
+            StructKind::Struct(name) => Content::Struct(name, self.fields),
+            StructKind::StructVariant(name, variant_index, variant) => {
+                Content::StructVariant(name, variant_index, variant, self.fields)
+            }
+        })
+    }
+}
+
+impl SerializeStruct for SerializeContentStruct {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ContentError> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        self.finish()
+    }
+}
+
+impl SerializeStructVariant for SerializeContentStruct {
+    type Ok = Content;
+    type Error = ContentError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ContentError> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Content, ContentError> {
+        self.finish()
+    }
+}
@@ -1,9 +1,9 @@
 use std::fmt::Display;
 
-use serde::{
-    ser::{Impossible, SerializeMap},
-    Serializer,
-};
+use serde::{ser::Impossible, Serializer};
+
+use super::map::SerializeMapWrapper;
+use super::SerializerWrapperHooks;
 
 #[derive(Debug, thiserror::Error)]
 pub(super) enum FlattenError<E: serde::ser::Error> {
@@ -22,17 +22,23 @@ impl<E: serde::ser::Error> serde::ser::Error for FlattenError<E> {
     }
 }
 
-pub(super) struct FlattenSerializer<'s, S: SerializeMap> {
-    serialize_map: &'s mut S,
+/// Feeds entries of a flattened value into the map of the enclosing struct.
+///
+/// The flattened value is hooked on its own, so the entries are passed to the
+/// enclosing map as is, without hooking them once again.
+pub(super) struct FlattenSerializer<'s, 'h, S: Serializer, H: SerializerWrapperHooks> {
+    serialize_map: &'s mut SerializeMapWrapper<'h, S, H>,
 }
 
-impl<'s, S: SerializeMap> FlattenSerializer<'s, S> {
-    pub(super) fn new(serialize_map: &'s mut S) -> Self {
+impl<'s, 'h, S: Serializer, H: SerializerWrapperHooks> FlattenSerializer<'s, 'h, S, H> {
+    pub(super) fn new(serialize_map: &'s mut SerializeMapWrapper<'h, S, H>) -> Self {
         Self { serialize_map }
     }
 }
 
-impl<'s, S: SerializeMap> Serializer for FlattenSerializer<'s, S> {
+impl<'s, 'h, S: Serializer, H: SerializerWrapperHooks> Serializer
+    for FlattenSerializer<'s, 'h, S, H>
+{
     type Ok = ();
     type Error = FlattenError<S::Error>;
     type SerializeSeq = Impossible<Self::Ok, Self::Error>;
@@ -208,7 +214,9 @@ impl<'s, S: SerializeMap> Serializer for FlattenSerializer<'s, S> {
     }
 }
 
-impl<'s, S: SerializeMap> serde::ser::SerializeMap for FlattenSerializer<'s, S> {
+impl<'s, 'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeMap
+    for FlattenSerializer<'s, 'h, S, H>
+{
     type Ok = ();
     type Error = FlattenError<S::Error>;
 
@@ -217,7 +225,7 @@ impl<'s, S: SerializeMap> serde::ser::SerializeMap for FlattenSerializer<'s, S>
         T: serde::Serialize,
     {
         self.serialize_map
-            .serialize_key(key)
+            .serialize_unhooked_key(key)
             .map_err(FlattenError::SerializerError)
     }
 
@@ -226,7 +234,7 @@ impl<'s, S: SerializeMap> serde::ser::SerializeMap for FlattenSerializer<'s, S>
         T: serde::Serialize,
     {
         self.serialize_map
-            .serialize_value(value)
+            .serialize_unhooked_value(value)
             .map_err(FlattenError::SerializerError)
     }
 
@@ -236,7 +244,9 @@ impl<'s, S: SerializeMap> serde::ser::SerializeMap for FlattenSerializer<'s, S>
     }
 }
 
-impl<'s, S: SerializeMap> serde::ser::SerializeStruct for FlattenSerializer<'s, S> {
+impl<'s, 'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeStruct
+    for FlattenSerializer<'s, 'h, S, H>
+{
     type Ok = ();
     type Error = FlattenError<S::Error>;
 
@@ -249,10 +259,10 @@ impl<'s, S: SerializeMap> serde::ser::SerializeStruct for FlattenSerializer<'s,
         T: serde::Serialize,
     {
         self.serialize_map
-            .serialize_key(key)
+            .serialize_unhooked_key(key)
             .map_err(FlattenError::SerializerError)?;
         self.serialize_map
-            .serialize_value(value)
+            .serialize_unhooked_value(value)
             .map_err(FlattenError::SerializerError)
     }
 
@@ -262,7 +272,9 @@ impl<'s, S: SerializeMap> serde::ser::SerializeStruct for FlattenSerializer<'s,
     }
 }
 
-impl<'s, S: SerializeMap> serde::ser::SerializeStructVariant for FlattenSerializer<'s, S> {
+impl<'s, 'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeStructVariant
+    for FlattenSerializer<'s, 'h, S, H>
+{
     type Ok = ();
     type Error = FlattenError<S::Error>;
 
@@ -275,10 +287,10 @@ impl<'s, S: SerializeMap> serde::ser::SerializeStructVariant for FlattenSerializ
         T: serde::Serialize,
     {
         self.serialize_map
-            .serialize_key(key)
+            .serialize_unhooked_key(key)
             .map_err(FlattenError::SerializerError)?;
         self.serialize_map
-            .serialize_value(value)
+            .serialize_unhooked_value(value)
             .map_err(FlattenError::SerializerError)
     }
 
@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::marker::PhantomData;
 use std::{cell::Cell, fmt::Display};
 
@@ -6,12 +7,15 @@ use serde::{ser::Impossible, Serialize, Serializer};
 use smallvec::SmallVec;
 
 use crate::path::PathMapKey;
-use crate::ser::{HooksError, MapInsertLocation};
+#[cfg(feature = "unicode-normalization")]
+use crate::ser::UnicodeForm;
+use crate::ser::{HooksError, MapInsertLocation, MapKeySelector};
 use crate::{Case, PathSegment, StaticValue, Value};
 
+use super::content::Content;
 use super::{
     MapEntryAction, MapEntryActions, SerializableKind, SerializableWithHooks,
-    SerializerWrapperHooks,
+    SerializerWrapperHooks, ValueComparator,
 };
 
 #[allow(clippy::large_enum_variant)]
@@ -21,9 +25,22 @@ pub(crate) enum SerializeMapWrapper<'h, S: Serializer, H: SerializerWrapperHooks
         hooks: &'h H,
         actions: MapEntryActions,
         have_retains: bool,
+        // all original entries are skipped, inserted ones are kept
+        clear_all: bool,
         entry_index: Cell<usize>,
         str_key_buffer: String, // reusable String for &str type keys to reduce allocations
         rename_all: Option<Case>,
+        #[cfg(feature = "unicode-normalization")]
+        normalize_keys: Option<UnicodeForm>,
+        is_human_readable: bool,
+        // entries are buffered here instead of being fed to the serializer, when sorting
+        sorted_entries: Option<Vec<(Content, Content)>>,
+        sort_by_key: Option<ValueComparator>,
+        // string keys that go first, when reordering
+        key_order: Vec<Cow<'static, str>>,
+        unique_keys: UniqueKeys,
+        // set by serialize_key, consumed by the following serialize_value
+        pending_value: Option<PendingValue>,
     },
     Skipped {
         end_result: Result<S::Ok, S::Error>,
@@ -37,57 +54,225 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeMapWrapper<'h, S, H>
         hooks: &'h H,
         actions: MapEntryActions,
     ) -> Result<Self, S::Error> {
+        let len_hint = actions.iter().rev().find_map(|a| match a {
+            MapEntryAction::LenHint(len) => Some(*len),
+            _ => None,
+        });
+
         // If there's any potential of entries being skipped or added, don't feed map length hint
-        // to the serializer.
-        let len = len.and_then(|len| {
-            if actions.iter().any(|a| {
-                matches!(
-                    a,
-                    MapEntryAction::Retain(_)
-                        | MapEntryAction::Skip(_)
-                        | MapEntryAction::Insert(_, _, _)
-                )
-            }) {
-                None
-            } else {
-                Some(len)
-            }
+        // to the serializer, unless the final length is given explicitly.
+        let len = len_hint.or_else(|| {
+            len.and_then(|len| {
+                if hooks.is_value_skipping_allowed()
+                    || actions.iter().any(|a| {
+                        matches!(
+                            a,
+                            MapEntryAction::Retain(_)
+                                | MapEntryAction::Skip(_)
+                                | MapEntryAction::SkipMatching(_)
+                                | MapEntryAction::Insert(_, _, _)
+                                | MapEntryAction::Clear
+                        )
+                    })
+                {
+                    None
+                } else {
+                    Some(len)
+                }
+            })
         });
 
+        let is_human_readable = serializer.is_human_readable();
+
+        let mut sort_by_key = None;
+        let mut unique_keys = UniqueKeys::default();
+        let mut clear_all = false;
+        let actions = actions
+            .into_iter()
+            .filter_map(|a| match a {
+                MapEntryAction::Sort(cmp) => {
+                    sort_by_key = Some(cmp);
+                    None
+                }
+                MapEntryAction::CheckUniqueKey(key) => {
+                    unique_keys.checked.push(key);
+                    None
+                }
+                MapEntryAction::LenHint(_) => None,
+                MapEntryAction::Clear => {
+                    clear_all = true;
+                    None
+                }
+                a => Some(a),
+            })
+            .collect();
+
         Ok(Self::Wrapped {
             serialize_map: serializer.serialize_map(len)?,
             hooks,
             have_retains: have_retains(&actions),
+            clear_all,
             rename_all: rename_all(&actions),
+            #[cfg(feature = "unicode-normalization")]
+            normalize_keys: normalize_keys(&actions),
             actions,
             entry_index: Cell::new(0),
             str_key_buffer: String::default(),
+            is_human_readable,
+            sorted_entries: sort_by_key.as_ref().map(|_| Vec::new()),
+            sort_by_key,
+            key_order: Vec::new(),
+            unique_keys,
+            pending_value: None,
         })
     }
 
+    /// Buffers all entries until `end()`, and then feeds them to the serializer sorted by key.
+    pub(super) fn sort_entries(&mut self, cmp: ValueComparator) {
+        if let SerializeMapWrapper::Wrapped {
+            sorted_entries,
+            sort_by_key,
+            ..
+        } = self
+        {
+            sorted_entries.get_or_insert_with(Vec::new);
+            *sort_by_key = Some(cmp);
+        }
+    }
+
+    /// Buffers all entries until `end()`, and then feeds the entries with the given string keys
+    /// to the serializer first, in the given order.
+    pub(super) fn reorder_entries(&mut self, order: Vec<Cow<'static, str>>) {
+        if let SerializeMapWrapper::Wrapped {
+            sorted_entries,
+            key_order,
+            ..
+        } = self
+        {
+            sorted_entries.get_or_insert_with(Vec::new);
+            *key_order = order;
+        }
+    }
+
     pub(super) fn new_skipped(end_result: Result<S::Ok, S::Error>) -> Self {
         Self::Skipped { end_result }
     }
 
+    fn emit_entry<K: Serialize + ?Sized, V: Serialize + ?Sized>(
+        serialize_map: &mut S::SerializeMap,
+        sorted_entries: &mut Option<Vec<(Content, Content)>>,
+        is_human_readable: bool,
+        key: &K,
+        value: &V,
+    ) -> Result<(), S::Error> {
+        use serde::ser::SerializeMap;
+
+        match sorted_entries {
+            Some(entries) => {
+                entries.push((
+                    Content::capture(key, is_human_readable)?,
+                    Content::capture(value, is_human_readable)?,
+                ));
+                Ok(())
+            }
+            None => serialize_map.serialize_entry(key, value),
+        }
+    }
+
+    /// Emits an entry, unless its value is skipped by the hooks.
+    ///
+    /// The key is captured before the value, so that hooks are called in the usual order.
+    /// Returns whether the entry was emitted.
+    fn emit_skippable_entry<K: Serialize + ?Sized, V: Serialize + ?Sized>(
+        serialize_map: &mut S::SerializeMap,
+        sorted_entries: &mut Option<Vec<(Content, Content)>>,
+        is_human_readable: bool,
+        hooks: &'h H,
+        key: &K,
+        value: &V,
+    ) -> Result<bool, S::Error> {
+        let key = Content::capture(key, is_human_readable)?;
+        match super::capture_skippable_value::<V, S, H>(value, hooks, is_human_readable)? {
+            Some(value) => Self::emit_entry(
+                serialize_map,
+                sorted_entries,
+                is_human_readable,
+                &key,
+                &value,
+            )
+            .map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    fn emit_key<K: Serialize + ?Sized>(
+        serialize_map: &mut S::SerializeMap,
+        sorted_entries: &mut Option<Vec<(Content, Content)>>,
+        is_human_readable: bool,
+        key: &K,
+    ) -> Result<(), S::Error> {
+        use serde::ser::SerializeMap;
+
+        match sorted_entries {
+            Some(entries) => {
+                // the value is filled in by the following emit_value call
+                entries.push((Content::capture(key, is_human_readable)?, Content::Unit));
+                Ok(())
+            }
+            None => serialize_map.serialize_key(key),
+        }
+    }
+
+    fn emit_value<V: Serialize + ?Sized>(
+        serialize_map: &mut S::SerializeMap,
+        sorted_entries: &mut Option<Vec<(Content, Content)>>,
+        is_human_readable: bool,
+        value: &V,
+    ) -> Result<(), S::Error> {
+        use serde::ser::SerializeMap;
+
+        match sorted_entries {
+            Some(entries) => {
+                let value = Content::capture(value, is_human_readable)?;
+                match entries.last_mut() {
+                    Some((_, v)) => *v = value,
+                    None => {
+                        return Err(serde::ser::Error::custom(
+                            "serialize_value called before serialize_key",
+                        ))
+                    }
+                }
+                Ok(())
+            }
+            None => serialize_map.serialize_value(value),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn insert_entry(
         serialize_map: &mut S::SerializeMap,
+        sorted_entries: &mut Option<Vec<(Content, Content)>>,
+        is_human_readable: bool,
         hooks: &'h H,
+        unique_keys: &mut UniqueKeys,
         entry_index: usize,
         key: StaticValue,
         value: StaticValue,
     ) -> Result<(), S::Error> {
-        use serde::ser::SerializeMap;
-
         key.check_if_can_serialize()
             .or_else(|err| hooks.on_error::<S>(err))?;
+        unique_keys.check::<S, H>(hooks, &key)?;
 
         value
             .check_if_can_serialize()
             .or_else(|err| hooks.on_error::<S>(err))?;
 
         let path_map_key = PathMapKey::new(entry_index, key.clone());
-        hooks.path_push(path_map_key.into());
-        let res = serialize_map.serialize_entry(
+        hooks.path_push::<S>(path_map_key.into())?;
+        let res = Self::emit_entry(
+            serialize_map,
+            sorted_entries,
+            is_human_readable,
             &key,
             &SerializableWithHooks::new(&value, hooks, SerializableKind::Value),
         );
@@ -96,6 +281,152 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeMapWrapper<'h, S, H>
 
         Ok(())
     }
+
+    /// Serializes a map key as is, for entries of flattened values, which are hooked on their own.
+    pub(super) fn serialize_unhooked_key<K: Serialize + ?Sized>(
+        &mut self,
+        key: &K,
+    ) -> Result<(), S::Error> {
+        match self {
+            SerializeMapWrapper::Skipped { .. } => Ok(()),
+            SerializeMapWrapper::Wrapped {
+                serialize_map,
+                is_human_readable,
+                sorted_entries,
+                ..
+            } => Self::emit_key(serialize_map, sorted_entries, *is_human_readable, key),
+        }
+    }
+
+    /// Serializes a map value as is, see `serialize_unhooked_key`.
+    pub(super) fn serialize_unhooked_value<V: Serialize + ?Sized>(
+        &mut self,
+        value: &V,
+    ) -> Result<(), S::Error> {
+        match self {
+            SerializeMapWrapper::Skipped { .. } => Ok(()),
+            SerializeMapWrapper::Wrapped {
+                serialize_map,
+                is_human_readable,
+                sorted_entries,
+                ..
+            } => Self::emit_value(serialize_map, sorted_entries, *is_human_readable, value),
+        }
+    }
+
+    /// Serializes a struct field as a map entry, for structs that are serialized as maps.
+    ///
+    /// Unlike `serialize_entry`, this does not attach hooks to the key and the value, nor
+    /// pushes a path segment, as the struct wrapper has already done so for the field.
+    pub(super) fn serialize_field_entry<V: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &V,
+    ) -> Result<(), S::Error> {
+        match self {
+            SerializeMapWrapper::Skipped { .. } => Ok(()),
+            SerializeMapWrapper::Wrapped {
+                serialize_map,
+                hooks,
+                actions,
+                have_retains,
+                clear_all,
+                entry_index,
+                rename_all,
+                #[cfg(feature = "unicode-normalization")]
+                normalize_keys,
+                is_human_readable,
+                sorted_entries,
+                unique_keys,
+                ..
+            } => {
+                let plan = EntryPlan::new(
+                    actions,
+                    *have_retains,
+                    *clear_all,
+                    entry_index.get(),
+                    *rename_all,
+                    #[cfg(feature = "unicode-normalization")]
+                    *normalize_keys,
+                    Value::Str(Cow::Borrowed(key)),
+                );
+
+                for (k, v) in plan.insert_before {
+                    Self::insert_entry(
+                        serialize_map,
+                        sorted_entries,
+                        *is_human_readable,
+                        hooks,
+                        unique_keys,
+                        entry_index.get(),
+                        k,
+                        v,
+                    )?;
+                }
+
+                let res = if plan.skip_entry {
+                    Ok(())
+                } else {
+                    // Checking the key after renaming and replacement.
+                    let final_key = plan.replacement_key.as_ref();
+                    unique_keys.check::<S, H>(hooks, final_key.unwrap_or(&plan.map_key_value))?;
+                    check_replacements::<S, H>(
+                        hooks,
+                        &plan.replacement_key,
+                        &plan.replacement_value,
+                    )?;
+
+                    match (&plan.replacement_key, &plan.replacement_value) {
+                        (None, None) => Self::emit_entry(
+                            serialize_map,
+                            sorted_entries,
+                            *is_human_readable,
+                            key,
+                            value,
+                        ),
+                        (None, Some(v)) => Self::emit_entry(
+                            serialize_map,
+                            sorted_entries,
+                            *is_human_readable,
+                            key,
+                            v,
+                        ),
+                        (Some(k), None) => Self::emit_entry(
+                            serialize_map,
+                            sorted_entries,
+                            *is_human_readable,
+                            k,
+                            value,
+                        ),
+                        (Some(k), Some(v)) => Self::emit_entry(
+                            serialize_map,
+                            sorted_entries,
+                            *is_human_readable,
+                            k,
+                            v,
+                        ),
+                    }
+                };
+
+                for (k, v) in plan.insert_after {
+                    Self::insert_entry(
+                        serialize_map,
+                        sorted_entries,
+                        *is_human_readable,
+                        hooks,
+                        unique_keys,
+                        entry_index.get(),
+                        k,
+                        v,
+                    )?;
+                }
+
+                entry_index.replace(entry_index.get() + 1);
+
+                res
+            }
+        }
+    }
 }
 
 impl<'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeMap
@@ -110,7 +441,95 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeMap
     {
         match self {
             SerializeMapWrapper::Skipped { .. } => Ok(()),
-            SerializeMapWrapper::Wrapped { serialize_map, .. } => serialize_map.serialize_key(key),
+            SerializeMapWrapper::Wrapped {
+                serialize_map,
+                hooks,
+                actions,
+                have_retains,
+                clear_all,
+                entry_index,
+                str_key_buffer,
+                rename_all,
+                #[cfg(feature = "unicode-normalization")]
+                normalize_keys,
+                is_human_readable,
+                sorted_entries,
+                unique_keys,
+                pending_value,
+                ..
+            } => {
+                let plan = EntryPlan::new(
+                    actions,
+                    *have_retains,
+                    *clear_all,
+                    entry_index.get(),
+                    *rename_all,
+                    #[cfg(feature = "unicode-normalization")]
+                    *normalize_keys,
+                    MapKeyCapture::capture(
+                        key,
+                        std::mem::take(str_key_buffer),
+                        hooks.captures_byte_keys(),
+                    )
+                    .map_err(serde::ser::Error::custom)?,
+                );
+
+                // Insert entries before
+                for (k, v) in plan.insert_before {
+                    Self::insert_entry(
+                        serialize_map,
+                        sorted_entries,
+                        *is_human_readable,
+                        hooks,
+                        unique_keys,
+                        entry_index.get(),
+                        k,
+                        v,
+                    )?;
+                }
+
+                if !plan.skip_entry {
+                    // Checking the key after renaming and replacement.
+                    let final_key = plan.replacement_key.as_ref();
+                    unique_keys.check::<S, H>(hooks, final_key.unwrap_or(&plan.map_key_value))?;
+
+                    // The path stays pushed until the matching serialize_value call.
+                    let path_map_key = PathMapKey::new(entry_index.get(), plan.map_key_value);
+                    hooks.path_push::<S>(path_map_key.into())?;
+
+                    check_replacements::<S, H>(
+                        hooks,
+                        &plan.replacement_key,
+                        &plan.replacement_value,
+                    )?;
+
+                    let res = match &plan.replacement_key {
+                        None => Self::emit_key(
+                            serialize_map,
+                            sorted_entries,
+                            *is_human_readable,
+                            &SerializableWithHooks::new(key, *hooks, SerializableKind::MapKey),
+                        ),
+                        Some(k) => {
+                            Self::emit_key(serialize_map, sorted_entries, *is_human_readable, k)
+                        }
+                    };
+
+                    if res.is_err() {
+                        hooks.path_pop();
+                        return res;
+                    }
+                }
+
+                *pending_value = Some(PendingValue {
+                    skip_entry: plan.skip_entry,
+                    replacement_key: plan.replacement_key,
+                    replacement_value: plan.replacement_value,
+                    insert_after: plan.insert_after,
+                });
+
+                Ok(())
+            }
         }
     }
 
@@ -120,8 +539,73 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeMap
     {
         match self {
             SerializeMapWrapper::Skipped { .. } => Ok(()),
-            SerializeMapWrapper::Wrapped { serialize_map, .. } => {
-                serialize_map.serialize_value(value)
+            SerializeMapWrapper::Wrapped {
+                serialize_map,
+                hooks,
+                entry_index,
+                str_key_buffer,
+                is_human_readable,
+                sorted_entries,
+                unique_keys,
+                pending_value,
+                ..
+            } => {
+                let Some(pending) = pending_value.take() else {
+                    return Err(serde::ser::Error::custom(
+                        "serialize_value called before serialize_key",
+                    ));
+                };
+
+                let res = if pending.skip_entry {
+                    Ok(())
+                } else {
+                    let res = match &pending.replacement_value {
+                        None => Self::emit_value(
+                            serialize_map,
+                            sorted_entries,
+                            *is_human_readable,
+                            &SerializableWithHooks::new(value, *hooks, SerializableKind::Value),
+                        ),
+                        Some(v) => {
+                            Self::emit_value(serialize_map, sorted_entries, *is_human_readable, v)
+                        }
+                    };
+
+                    if res.is_ok() {
+                        hooks.on_map_entry_end(pending.replacement_key.as_ref());
+                    }
+
+                    let segment = hooks.path_pop();
+
+                    // Trying to reclaim the reusable string buffer from the popped path segment
+                    if let PathSegment::MapEntry(PathMapKey {
+                        value: Value::Str(Cow::Owned(mut s)),
+                        ..
+                    }) = segment
+                    {
+                        std::mem::swap(str_key_buffer, &mut s)
+                    }
+
+                    res
+                };
+
+                // Insert entries after
+                for (k, v) in pending.insert_after {
+                    Self::insert_entry(
+                        serialize_map,
+                        sorted_entries,
+                        *is_human_readable,
+                        hooks,
+                        unique_keys,
+                        entry_index.get(),
+                        k,
+                        v,
+                    )?;
+                }
+
+                entry_index.replace(entry_index.get() + 1);
+
+                res
             }
         }
     }
@@ -142,132 +626,123 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeMap
                 hooks,
                 actions,
                 have_retains,
+                clear_all,
                 entry_index,
                 str_key_buffer,
                 rename_all,
+                #[cfg(feature = "unicode-normalization")]
+                normalize_keys,
+                is_human_readable,
+                sorted_entries,
+                unique_keys,
+                ..
             } => {
-                let mut map_key_value = MapKeyCapture::capture(key, std::mem::take(str_key_buffer));
-
-                let mut retain_entry = false;
-                let mut skip_entry = false;
-                let mut replacement_value: Option<Value> = None;
-                let mut replacement_key: Option<Value> = None;
-                let mut insert_before: SmallVec<[(StaticValue, StaticValue); 2]> =
-                    Default::default();
-                let mut insert_after: SmallVec<[(StaticValue, StaticValue); 2]> =
-                    Default::default();
-
-                actions.retain_mut(|a| match a {
-                    MapEntryAction::Retain(k) => {
-                        let matches = k.matches_path_key(&map_key_value, entry_index.get());
-                        if matches {
-                            retain_entry = true;
-                        }
-                        !matches
-                    }
-                    MapEntryAction::Skip(k) => {
-                        let matches = k.matches_path_key(&map_key_value, entry_index.get());
-                        if matches {
-                            skip_entry = true;
-                        }
-                        !matches
-                    }
-                    MapEntryAction::Insert(k, v, location) => match location {
-                        MapInsertLocation::Before(before) => {
-                            let matches =
-                                before.matches_path_key(&map_key_value, entry_index.get());
-                            if matches {
-                                insert_before.push((k.clone(), v.clone()));
-                            }
-                            !matches
-                        }
-                        MapInsertLocation::After(after) => {
-                            let matches = after.matches_path_key(&map_key_value, entry_index.get());
-                            if matches {
-                                insert_after.push((k.clone(), v.clone()));
-                            }
-                            !matches
-                        }
-                        MapInsertLocation::End => true,
-                    },
-                    MapEntryAction::ReplaceValue(k, v) => {
-                        let matches = k.matches_path_key(&map_key_value, entry_index.get());
-                        if matches {
-                            replacement_value = Some(v.clone());
-                        }
-                        !matches
-                    }
-                    MapEntryAction::ReplaceKey(k, v) => {
-                        let matches = k.matches_path_key(&map_key_value, entry_index.get());
-                        if matches {
-                            map_key_value = v.clone();
-                            replacement_key = Some(v.clone());
-                        }
-                        !matches
-                    }
-                    MapEntryAction::RenameAllCase(_) => false,
-                    MapEntryAction::RenameCase(k, case) => {
-                        let matches = k.matches_path_key(&map_key_value, entry_index.get());
-                        if matches {
-                            if let Value::Str(s) = &map_key_value {
-                                replacement_key = Some(Case::string_to_case(s, *case).into());
-                            }
-                        }
-                        !matches
-                    }
-                });
-
-                if *have_retains && !retain_entry {
-                    skip_entry = true;
-                }
+                let plan = EntryPlan::new(
+                    actions,
+                    *have_retains,
+                    *clear_all,
+                    entry_index.get(),
+                    *rename_all,
+                    #[cfg(feature = "unicode-normalization")]
+                    *normalize_keys,
+                    MapKeyCapture::capture(
+                        key,
+                        std::mem::take(str_key_buffer),
+                        hooks.captures_byte_keys(),
+                    )
+                    .map_err(serde::ser::Error::custom)?,
+                );
 
                 // Insert entries before
-                for (k, v) in insert_before {
-                    Self::insert_entry(serialize_map, hooks, entry_index.get(), k, v)?;
+                for (k, v) in plan.insert_before {
+                    Self::insert_entry(
+                        serialize_map,
+                        sorted_entries,
+                        *is_human_readable,
+                        hooks,
+                        unique_keys,
+                        entry_index.get(),
+                        k,
+                        v,
+                    )?;
                 }
 
-                let res = if skip_entry {
+                let res = if plan.skip_entry {
                     Ok(())
                 } else {
-                    if replacement_key.is_none() {
-                        if let Some(case) = rename_all {
-                            if let Value::Str(s) = &map_key_value {
-                                replacement_key = Some(Case::string_to_case(s, *case).into());
-                            }
+                    // Checking the key after renaming and replacement.
+                    let final_key = plan.replacement_key.as_ref();
+                    unique_keys.check::<S, H>(hooks, final_key.unwrap_or(&plan.map_key_value))?;
+
+                    let path_map_key = PathMapKey::new(entry_index.get(), plan.map_key_value);
+                    hooks.path_push::<S>(path_map_key.into())?;
+
+                    check_replacements::<S, H>(
+                        hooks,
+                        &plan.replacement_key,
+                        &plan.replacement_value,
+                    )?;
+
+                    // Whether the entry was emitted, it can be skipped in `on_value`.
+                    let res = match (&plan.replacement_key, &plan.replacement_value) {
+                        (None, None) if hooks.is_value_skipping_allowed() => {
+                            Self::emit_skippable_entry(
+                                serialize_map,
+                                sorted_entries,
+                                *is_human_readable,
+                                hooks,
+                                &SerializableWithHooks::new(key, *hooks, SerializableKind::MapKey),
+                                value,
+                            )
                         }
-                    }
-
-                    let path_map_key = PathMapKey::new(entry_index.get(), map_key_value);
-                    hooks.path_push(path_map_key.into());
-
-                    if let Some(replacement_value) = &replacement_value {
-                        replacement_value
-                            .check_if_can_serialize()
-                            .or_else(|err| hooks.on_error::<S>(err))?;
-                    }
-
-                    if let Some(replacement_key) = &replacement_key {
-                        replacement_key
-                            .check_if_can_serialize()
-                            .or_else(|err| hooks.on_error::<S>(err))?;
-                    }
-
-                    let res = match (&replacement_key, &replacement_value) {
-                        (None, None) => serialize_map.serialize_entry(
+                        (Some(k), None) if hooks.is_value_skipping_allowed() => {
+                            Self::emit_skippable_entry(
+                                serialize_map,
+                                sorted_entries,
+                                *is_human_readable,
+                                hooks,
+                                k,
+                                value,
+                            )
+                        }
+                        (None, None) => Self::emit_entry(
+                            serialize_map,
+                            sorted_entries,
+                            *is_human_readable,
                             &SerializableWithHooks::new(key, *hooks, SerializableKind::MapKey),
                             &SerializableWithHooks::new(value, *hooks, SerializableKind::Value),
-                        ),
-                        (None, Some(v)) => serialize_map.serialize_entry(
+                        )
+                        .map(|_| true),
+                        (None, Some(v)) => Self::emit_entry(
+                            serialize_map,
+                            sorted_entries,
+                            *is_human_readable,
                             &SerializableWithHooks::new(key, *hooks, SerializableKind::MapKey),
                             v,
-                        ),
-                        (Some(k), None) => serialize_map.serialize_entry(
+                        )
+                        .map(|_| true),
+                        (Some(k), None) => Self::emit_entry(
+                            serialize_map,
+                            sorted_entries,
+                            *is_human_readable,
                             k,
                             &SerializableWithHooks::new(value, *hooks, SerializableKind::Value),
-                        ),
-                        (Some(k), Some(v)) => serialize_map.serialize_entry(k, v),
+                        )
+                        .map(|_| true),
+                        (Some(k), Some(v)) => Self::emit_entry(
+                            serialize_map,
+                            sorted_entries,
+                            *is_human_readable,
+                            k,
+                            v,
+                        )
+                        .map(|_| true),
                     };
 
+                    if let Ok(true) = res {
+                        hooks.on_map_entry_end(plan.replacement_key.as_ref());
+                    }
+
                     let segment = hooks.path_pop();
 
                     // Trying to reclaim the reusable string buffer from the popped path segment
@@ -279,12 +754,21 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeMap
                         std::mem::swap(str_key_buffer, &mut s)
                     }
 
-                    res
+                    res.map(|_| ())
                 };
 
                 // Insert entries after
-                for (k, v) in insert_after {
-                    Self::insert_entry(serialize_map, hooks, entry_index.get(), k, v)?;
+                for (k, v) in plan.insert_after {
+                    Self::insert_entry(
+                        serialize_map,
+                        sorted_entries,
+                        *is_human_readable,
+                        hooks,
+                        unique_keys,
+                        entry_index.get(),
+                        k,
+                        v,
+                    )?;
                 }
 
                 entry_index.replace(entry_index.get() + 1);
@@ -302,6 +786,11 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeMap
                 hooks,
                 actions,
                 entry_index,
+                is_human_readable,
+                mut sorted_entries,
+                sort_by_key,
+                key_order,
+                mut unique_keys,
                 ..
             } => {
                 for a in actions {
@@ -313,7 +802,10 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeMap
                             }
                             MapInsertLocation::End => Self::insert_entry(
                                 &mut serialize_map,
+                                &mut sorted_entries,
+                                is_human_readable,
                                 hooks,
+                                &mut unique_keys,
                                 entry_index.get(),
                                 k,
                                 v,
@@ -326,7 +818,36 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeMap
                         | MapEntryAction::RenameCase(k, _) => {
                             hooks.on_error::<S>(HooksError::KeyNotFound(k))?
                         }
-                        MapEntryAction::RenameAllCase(_) => {}
+                        MapEntryAction::RenameAllCase(_)
+                        | MapEntryAction::SkipMatching(_)
+                        | MapEntryAction::ReplaceValueMatching(_, _)
+                        | MapEntryAction::Sort(_)
+                        | MapEntryAction::CheckUniqueKey(_)
+                        | MapEntryAction::LenHint(_)
+                        | MapEntryAction::Clear => {}
+                        #[cfg(feature = "unicode-normalization")]
+                        MapEntryAction::NormalizeStringKeys(_) => {}
+                    }
+                }
+
+                if let Some(mut entries) = sorted_entries {
+                    let position = |key: &Content| match key {
+                        Content::Str(key) => key_order
+                            .iter()
+                            .position(|k| k == key)
+                            .unwrap_or(key_order.len()),
+                        _ => key_order.len(),
+                    };
+                    entries.sort_by(|(a, _), (b, _)| {
+                        position(a)
+                            .cmp(&position(b))
+                            .then_with(|| match &sort_by_key {
+                                Some(cmp) => cmp(&a.as_value(), &b.as_value()),
+                                None => Ordering::Equal,
+                            })
+                    });
+                    for (k, v) in entries {
+                        serialize_map.serialize_entry(&k, &v)?;
                     }
                 }
 
@@ -336,35 +857,250 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeMap
     }
 }
 
+/// Part of an [`EntryPlan`] that is applied when the value of a map entry comes,
+/// for maps serialized with separate `serialize_key` and `serialize_value` calls.
+pub(crate) struct PendingValue {
+    skip_entry: bool,
+    replacement_key: Option<StaticValue>,
+    replacement_value: Option<StaticValue>,
+    insert_after: SmallVec<[(StaticValue, StaticValue); 2]>,
+}
+
+/// Outcome of applying map entry actions to a single entry.
+struct EntryPlan<'k> {
+    map_key_value: Value<'k>,
+    skip_entry: bool,
+    replacement_key: Option<StaticValue>,
+    replacement_value: Option<StaticValue>,
+    insert_before: SmallVec<[(StaticValue, StaticValue); 2]>,
+    insert_after: SmallVec<[(StaticValue, StaticValue); 2]>,
+}
+
+impl<'k> EntryPlan<'k> {
+    fn new(
+        actions: &mut MapEntryActions,
+        have_retains: bool,
+        clear_all: bool,
+        entry_index: usize,
+        rename_all: Option<Case>,
+        #[cfg(feature = "unicode-normalization")] normalize_keys: Option<UnicodeForm>,
+        mut map_key_value: Value<'k>,
+    ) -> Self {
+        let mut retain_entry = false;
+        let mut skip_entry = false;
+        let mut skip_matching = false;
+        let mut matching_value: Option<StaticValue> = None;
+        let mut replacement_value: Option<StaticValue> = None;
+        let mut replacement_key: Option<StaticValue> = None;
+        let mut insert_before: SmallVec<[(StaticValue, StaticValue); 2]> = Default::default();
+        let mut insert_after: SmallVec<[(StaticValue, StaticValue); 2]> = Default::default();
+
+        // Predicates are evaluated against the original key, before it's replaced.
+        for a in actions.iter() {
+            match a {
+                MapEntryAction::SkipMatching(predicate) if predicate(&map_key_value) => {
+                    skip_matching = true;
+                }
+                MapEntryAction::ReplaceValueMatching(predicate, v) if predicate(&map_key_value) => {
+                    matching_value = Some(v.clone());
+                }
+                _ => {}
+            }
+        }
+
+        actions.retain_mut(|a| match a {
+            MapEntryAction::Retain(k) => {
+                let matches = k.matches_path_key(&map_key_value, entry_index);
+                if matches {
+                    retain_entry = true;
+                }
+                !matches
+            }
+            MapEntryAction::Skip(k) => {
+                let matches = k.matches_path_key(&map_key_value, entry_index);
+                if matches {
+                    skip_entry = true;
+                }
+                !matches
+            }
+            MapEntryAction::SkipMatching(_) | MapEntryAction::ReplaceValueMatching(_, _) => true,
+            MapEntryAction::Insert(k, v, location) => match location {
+                MapInsertLocation::Before(before) => {
+                    let matches = before.matches_path_key(&map_key_value, entry_index);
+                    if matches {
+                        insert_before.push((k.clone(), v.clone()));
+                    }
+                    !matches
+                }
+                MapInsertLocation::After(after) => {
+                    let matches = after.matches_path_key(&map_key_value, entry_index);
+                    if matches {
+                        insert_after.push((k.clone(), v.clone()));
+                    }
+                    !matches
+                }
+                MapInsertLocation::End => true,
+            },
+            MapEntryAction::ReplaceValue(k, v) => {
+                let matches = k.matches_path_key(&map_key_value, entry_index);
+                if matches {
+                    replacement_value = Some(v.clone());
+                }
+                !matches
+            }
+            MapEntryAction::ReplaceKey(k, v) => {
+                let matches = k.matches_path_key(&map_key_value, entry_index);
+                if matches {
+                    map_key_value = v.clone();
+                    replacement_key = Some(v.clone());
+                }
+                !matches
+            }
+            MapEntryAction::RenameAllCase(_)
+            | MapEntryAction::Sort(_)
+            | MapEntryAction::CheckUniqueKey(_)
+            | MapEntryAction::LenHint(_)
+            | MapEntryAction::Clear => false,
+            #[cfg(feature = "unicode-normalization")]
+            MapEntryAction::NormalizeStringKeys(_) => false,
+            MapEntryAction::RenameCase(k, case) => {
+                let matches = k.matches_path_key(&map_key_value, entry_index);
+                if matches {
+                    if let Value::Str(s) = &map_key_value {
+                        replacement_key = Some(Case::string_to_case(s, *case).into());
+                    }
+                }
+                !matches
+            }
+        });
+
+        if ((have_retains || skip_matching) && !retain_entry) || clear_all {
+            skip_entry = true;
+        }
+
+        // Values replaced explicitly take precedence.
+        let replacement_value = replacement_value.or(matching_value);
+
+        if !skip_entry && replacement_key.is_none() {
+            if let Some(case) = rename_all {
+                if let Value::Str(s) = &map_key_value {
+                    replacement_key = Some(Case::string_to_case(s, case).into());
+                }
+            }
+        }
+
+        #[cfg(feature = "unicode-normalization")]
+        if !skip_entry {
+            if let Some(form) = normalize_keys {
+                if let Value::Str(s) = replacement_key.as_ref().unwrap_or(&map_key_value) {
+                    let normalized = form.normalize(s);
+                    if normalized != *s {
+                        replacement_key = Some(normalized.into());
+                    }
+                }
+            }
+        }
+
+        Self {
+            map_key_value,
+            skip_entry,
+            replacement_key,
+            replacement_value,
+            insert_before,
+            insert_after,
+        }
+    }
+}
+
+/// Keys of entries inserted with uniqueness check, and the ones of them already serialized.
+#[derive(Default)]
+pub(crate) struct UniqueKeys {
+    checked: Vec<StaticValue>,
+    seen: Vec<StaticValue>,
+}
+
+impl UniqueKeys {
+    /// Reports an error if `key` is a checked key that has already been serialized.
+    fn check<S: Serializer, H: SerializerWrapperHooks>(
+        &mut self,
+        hooks: &H,
+        key: &StaticValue,
+    ) -> Result<(), S::Error> {
+        if !self.checked.contains(key) {
+            return Ok(());
+        }
+        if self.seen.contains(key) {
+            return hooks.on_error::<S>(HooksError::DuplicateKey(MapKeySelector::ByValue(
+                key.clone(),
+            )));
+        }
+        self.seen.push(key.clone());
+        Ok(())
+    }
+}
+
+fn check_replacements<S: Serializer, H: SerializerWrapperHooks>(
+    hooks: &H,
+    replacement_key: &Option<StaticValue>,
+    replacement_value: &Option<StaticValue>,
+) -> Result<(), S::Error> {
+    if let Some(replacement_value) = replacement_value {
+        replacement_value
+            .check_if_can_serialize()
+            .or_else(|err| hooks.on_error::<S>(err))?;
+    }
+
+    if let Some(replacement_key) = replacement_key {
+        replacement_key
+            .check_if_can_serialize()
+            .or_else(|err| hooks.on_error::<S>(err))?;
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, thiserror::Error)]
-#[error("")]
-struct MapKeyCaptureError<'b>(Value<'b>);
+pub(super) enum MapKeyCaptureError<'b> {
+    // not an error, compound values are returned this way to stop their serialization
+    #[error("")]
+    Compound(Value<'b>),
+    #[error("{0}")]
+    Custom(String),
+}
 
 impl serde::ser::Error for MapKeyCaptureError<'_> {
-    fn custom<T>(_msg: T) -> Self
+    fn custom<T>(msg: T) -> Self
     where
         T: Display,
     {
-        unimplemented!()
+        Self::Custom(msg.to_string())
     }
 }
 
-struct MapKeyCapture<'b> {
+pub(super) struct MapKeyCapture<'b> {
     str_buffer: String,
+    capture_bytes: bool,
     marker: PhantomData<&'b ()>,
 }
 
 impl MapKeyCapture<'_> {
-    fn capture<'b, K>(key: &K, str_buffer: String) -> Value<'b>
+    /// Captures the value, or returns the error message if its serialization fails.
+    pub(super) fn capture<'b, K>(
+        key: &K,
+        str_buffer: String,
+        capture_bytes: bool,
+    ) -> Result<Value<'b>, String>
     where
         K: Serialize + ?Sized,
     {
         match key.serialize(MapKeyCapture {
             str_buffer,
+            capture_bytes,
             marker: PhantomData,
         }) {
-            Ok(v) => v,                      // primitive keys via Ok
-            Err(MapKeyCaptureError(v)) => v, // complex keys via Err
+            Ok(v) => Ok(v),                                   // primitive keys via Ok
+            Err(MapKeyCaptureError::Compound(v)) => Ok(v),    // complex keys via Err
+            Err(MapKeyCaptureError::Custom(msg)) => Err(msg), // failed serialization
         }
     }
 }
@@ -443,8 +1179,12 @@ impl<'b> Serializer for MapKeyCapture<'b> {
         Ok(Value::Str(Cow::Owned(self.str_buffer)))
     }
 
-    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Bytes(Cow::Borrowed(&[])))
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        if self.capture_bytes {
+            Ok(Value::Bytes(Cow::Owned(v.to_vec())))
+        } else {
+            Ok(Value::Bytes(Cow::Borrowed(&[])))
+        }
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
@@ -508,11 +1248,11 @@ impl<'b> Serializer for MapKeyCapture<'b> {
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        Err(MapKeyCaptureError(Value::Seq(len)))
+        Err(MapKeyCaptureError::Compound(Value::Seq(len)))
     }
 
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-        Err(MapKeyCaptureError(Value::Tuple(len)))
+        Err(MapKeyCaptureError::Compound(Value::Tuple(len)))
     }
 
     fn serialize_tuple_struct(
@@ -520,7 +1260,10 @@ impl<'b> Serializer for MapKeyCapture<'b> {
         name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-        Err(MapKeyCaptureError(Value::TupleStruct { name, len }))
+        Err(MapKeyCaptureError::Compound(Value::TupleStruct {
+            name,
+            len,
+        }))
     }
 
     fn serialize_tuple_variant(
@@ -530,7 +1273,7 @@ impl<'b> Serializer for MapKeyCapture<'b> {
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        Err(MapKeyCaptureError(Value::TupleVariant {
+        Err(MapKeyCaptureError::Compound(Value::TupleVariant {
             name,
             variant_index,
             variant,
@@ -539,7 +1282,7 @@ impl<'b> Serializer for MapKeyCapture<'b> {
     }
 
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        Err(MapKeyCaptureError(Value::Map(len)))
+        Err(MapKeyCaptureError::Compound(Value::Map(len)))
     }
 
     fn serialize_struct(
@@ -547,7 +1290,7 @@ impl<'b> Serializer for MapKeyCapture<'b> {
         name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
-        Err(MapKeyCaptureError(Value::Struct { name, len }))
+        Err(MapKeyCaptureError::Compound(Value::Struct { name, len }))
     }
 
     fn serialize_struct_variant(
@@ -557,7 +1300,7 @@ impl<'b> Serializer for MapKeyCapture<'b> {
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        Err(MapKeyCaptureError(Value::StructVariant {
+        Err(MapKeyCaptureError::Compound(Value::StructVariant {
             name,
             variant_index,
             variant,
@@ -578,3 +1321,11 @@ fn rename_all(entry_actions: &MapEntryActions) -> Option<Case> {
         _ => None,
     })
 }
+
+#[cfg(feature = "unicode-normalization")]
+fn normalize_keys(entry_actions: &MapEntryActions) -> Option<UnicodeForm> {
+    entry_actions.iter().rev().find_map(|a| match a {
+        MapEntryAction::NormalizeStringKeys(form) => Some(*form),
+        _ => None,
+    })
+}
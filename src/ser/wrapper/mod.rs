@@ -1,22 +1,26 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, ops::Range};
 
 use serde::{Serialize, Serializer};
 use smallvec::SmallVec;
 
+mod content;
 mod flatten;
 mod map;
 mod seq;
 mod serializer;
 mod r#struct;
 
-use super::{HooksError, MapKeySelector};
+use super::{HooksError, MapKeySelector, SeqInsertLocation};
 use crate::ser::MapInsertLocation;
-use crate::{path::PathSegment, Case, StaticValue};
+#[cfg(feature = "unicode-normalization")]
+use crate::ser::UnicodeForm;
+use crate::{path::PathSegment, Case, StaticValue, Value};
 
 pub(crate) use serializer::SerializerWrapper;
 
 pub(crate) trait SerializerWrapperHooks {
-    fn path_push(&self, segment: PathSegment);
+    /// Pushes a segment to the current path, reporting an error if the path gets too deep.
+    fn path_push<S: Serializer>(&self, segment: PathSegment) -> Result<(), S::Error>;
 
     fn path_pop(&self) -> PathSegment;
 
@@ -24,6 +28,10 @@ pub(crate) trait SerializerWrapperHooks {
 
     fn on_map(&self, map_len: Option<usize>) -> MapEntryActions;
 
+    /// Called for the map entry at the current path, `replacement_key` is the key
+    /// that the map entry actions have replaced the original one with.
+    fn on_map_entry_end(&self, replacement_key: Option<&StaticValue>);
+
     fn on_unit_variant(
         &self,
         enum_name: &'static str,
@@ -44,6 +52,12 @@ pub(crate) trait SerializerWrapperHooks {
         struct_name: &'static str,
     ) -> (StructActions, StructFieldActions);
 
+    fn on_struct_end(&self, emitted_len: usize);
+
+    fn on_newtype_struct(&self, name: &'static str) -> NewtypeStructActions;
+
+    fn on_unit_struct(&self, name: &'static str) -> Option<Cow<'static, str>>;
+
     fn on_struct_variant(
         &self,
         struct_len: usize,
@@ -64,8 +78,40 @@ pub(crate) trait SerializerWrapperHooks {
         value: crate::Value,
     ) -> Result<ValueAction<S>, S::Error>;
 
+    fn on_bytes<S: Serializer>(
+        &self,
+        serializer: S,
+        bytes: &[u8],
+    ) -> Result<ValueAction<S>, S::Error>;
+
+    fn on_option<S: Serializer>(
+        &self,
+        serializer: S,
+        is_some: bool,
+    ) -> Result<ValueAction<S>, S::Error>;
+
+    fn on_float<S: Serializer>(
+        &self,
+        serializer: S,
+        value: f64,
+    ) -> Result<ValueAction<S>, S::Error>;
+
+    fn take_expanded_elements(&self) -> Vec<StaticValue>;
+
+    fn is_value_skipping_allowed(&self) -> bool;
+
+    fn is_element_expanding_allowed(&self) -> bool;
+
+    fn captures_byte_keys(&self) -> bool;
+
+    fn begin_skippable_value(&self);
+
+    fn end_skippable_value(&self) -> bool;
+
     fn on_seq(&self, len: Option<usize>) -> SeqElementActions;
 
+    fn on_seq_element_end(&self, index: usize);
+
     fn on_tuple(&self, len: usize) -> SeqElementActions;
 
     fn on_tuple_struct(&self, name: &'static str, len: usize) -> SeqElementActions;
@@ -81,44 +127,87 @@ pub(crate) trait SerializerWrapperHooks {
     fn make_static_str(&self, key: Cow<'static, str>) -> &'static str;
 }
 
+/// Number of actions a scope keeps inline before spilling to the heap.
+#[cfg(not(feature = "large-action-buffers"))]
+pub(crate) const INLINE_ACTIONS: usize = 8;
+#[cfg(feature = "large-action-buffers")]
+pub(crate) const INLINE_ACTIONS: usize = 32;
+
 pub(crate) enum StructFieldAction {
     Retain(Cow<'static, str>),
     Skip(Cow<'static, str>),
+    SkipIf(Cow<'static, str>, Box<dyn Fn(&Value) -> bool>),
     Rename(Cow<'static, str>, Cow<'static, str>),
+    RenameAt(usize, Cow<'static, str>),
     ReplaceValue(Cow<'static, str>, StaticValue),
     RenameAllCase(Case),
+    RenameMatchingCase(Box<dyn Fn(&str) -> bool>, Case),
     Flatten(Cow<'static, str>),
+    Insert(Cow<'static, str>, StaticValue),
+    Clear,
 }
 
-pub(crate) type StructFieldActions = SmallVec<[StructFieldAction; 8]>;
+pub(crate) type StructFieldActions = SmallVec<[StructFieldAction; INLINE_ACTIONS]>;
+
+/// Comparison function for sorting string map keys.
+pub(crate) type KeyComparator = Box<dyn Fn(&str, &str) -> std::cmp::Ordering>;
+
+/// Comparison function for sorting map keys of any type.
+pub(crate) type ValueComparator = Box<dyn Fn(&Value<'_>, &Value<'_>) -> std::cmp::Ordering>;
+
+#[derive(Default)]
+pub(crate) struct NewtypeStructActions {
+    pub(crate) unwrap: bool,
+    pub(crate) rename: Option<Cow<'static, str>>,
+}
 
 pub(crate) struct StructActions {
     pub(crate) serialize_as_map: bool,
+    pub(crate) serialize_as_seq: bool,
+    pub(crate) sort_map_entries: Option<KeyComparator>,
+    pub(crate) field_order: Option<Vec<Cow<'static, str>>>,
 }
 
 pub(crate) enum MapEntryAction {
     Retain(MapKeySelector),
     Skip(MapKeySelector),
+    SkipMatching(Box<dyn Fn(&Value<'_>) -> bool>),
     Insert(StaticValue, StaticValue, MapInsertLocation),
     ReplaceValue(MapKeySelector, StaticValue),
+    ReplaceValueMatching(Box<dyn Fn(&Value<'_>) -> bool>, StaticValue),
     ReplaceKey(MapKeySelector, StaticValue),
     RenameCase(MapKeySelector, Case),
     RenameAllCase(Case),
+    #[cfg(feature = "unicode-normalization")]
+    NormalizeStringKeys(UnicodeForm),
+    Sort(ValueComparator),
+    CheckUniqueKey(StaticValue),
+    LenHint(usize),
+    Clear,
 }
 
-pub(crate) type MapEntryActions = SmallVec<[MapEntryAction; 8]>;
+pub(crate) type MapEntryActions = SmallVec<[MapEntryAction; INLINE_ACTIONS]>;
 
 pub(crate) enum SeqElementAction {
     Retain(usize),
     Skip(usize),
+    RetainRange(Range<usize>),
+    SkipRange(Range<usize>),
+    SkipMatching(Box<dyn Fn(&Value) -> bool>),
     ReplaceValue(usize, StaticValue),
+    Insert(usize, StaticValue, SeqInsertLocation),
+    Truncate(usize),
+    SkipLast,
+    RetainLast,
+    LenHint(usize),
 }
 
-pub(crate) type SeqElementActions = SmallVec<[SeqElementAction; 8]>;
+pub(crate) type SeqElementActions = SmallVec<[SeqElementAction; INLINE_ACTIONS]>;
 
 pub(crate) enum ValueAction<S: Serializer> {
     ContinueSerialization(S),
     ValueReplaced(Result<S::Ok, S::Error>),
+    Skipped(S),
 }
 
 pub(crate) enum VariantAction {
@@ -127,9 +216,10 @@ pub(crate) enum VariantAction {
     RenameVariantCase(Case),
     RenameVariant(Cow<'static, str>),
     ChangeVariantIndex(u32),
+    InternallyTagged(Cow<'static, str>),
 }
 
-pub(crate) type VariantActions = SmallVec<[VariantAction; 8]>;
+pub(crate) type VariantActions = SmallVec<[VariantAction; INLINE_ACTIONS]>;
 
 #[derive(Copy, Clone)]
 pub(crate) enum SerializableKind {
@@ -164,3 +254,28 @@ impl<T: Serialize + ?Sized, H: SerializerWrapperHooks> Serialize
             .serialize(SerializerWrapper::new(serializer, self.hooks, self.kind))
     }
 }
+
+/// Captures a hooked value, which can be skipped by the hooks.
+///
+/// Returns `None` if the value has been skipped.
+fn capture_skippable_value<T: Serialize + ?Sized, S: Serializer, H: SerializerWrapperHooks>(
+    value: &T,
+    hooks: &H,
+    is_human_readable: bool,
+) -> Result<Option<content::Content>, S::Error> {
+    hooks.begin_skippable_value();
+    let content = content::Content::capture(
+        &SerializableWithHooks::new(value, hooks, SerializableKind::Value),
+        is_human_readable,
+    );
+    let skipped = hooks.end_skippable_value();
+    content.map(|content| (!skipped).then_some(content))
+}
+
+#[test]
+fn test_inline_actions() {
+    assert_eq!(StructFieldActions::new().inline_size(), INLINE_ACTIONS);
+    assert_eq!(MapEntryActions::new().inline_size(), INLINE_ACTIONS);
+    assert_eq!(SeqElementActions::new().inline_size(), INLINE_ACTIONS);
+    assert_eq!(VariantActions::new().inline_size(), INLINE_ACTIONS);
+}
@@ -1,9 +1,12 @@
 use serde::ser::{SerializeSeq, SerializeTuple, SerializeTupleStruct, SerializeTupleVariant};
 use serde::{Serialize, Serializer};
+use smallvec::SmallVec;
 
-use crate::ser::HooksError;
-use crate::Value;
+use crate::ser::{HooksError, SeqInsertLocation};
+use crate::{StaticValue, Value};
 
+use super::content::Content;
+use super::map::MapKeyCapture;
 use super::{
     PathSegment, SeqElementAction, SeqElementActions, SerializableKind, SerializableWithHooks,
     SerializerWrapperHooks,
@@ -48,6 +51,10 @@ pub(crate) enum SerializeSeqWrapper<'h, S: Serializer, H: SerializerWrapperHooks
         actions: SeqElementActions,
         have_retains: bool,
         current_index: usize,
+        last_element: Option<LastElementAction>,
+        is_human_readable: bool,
+        // the current element, held back until it's known whether it is the last one
+        pending: Option<PendingElement>,
     },
     Skipped {
         end_result: Result<S::Ok, S::Error>,
@@ -61,15 +68,15 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeSeqWrapper<'h, S, H>
         hooks: &'h H,
         actions: SeqElementActions,
     ) -> Result<Self, S::Error> {
-        Ok(Self::Wrapped {
-            wrap: Wrap::SerializeSeq(
-                serializer.serialize_seq(len_hint_with_actions(len, &actions))?,
+        let is_human_readable = serializer.is_human_readable();
+        Ok(Self::new_wrapped(
+            Wrap::SerializeSeq(
+                serializer.serialize_seq(len_hint_with_actions(len, &actions, hooks))?,
             ),
             hooks,
-            have_retains: have_retains(&actions),
             actions,
-            current_index: 0,
-        })
+            is_human_readable,
+        ))
     }
 
     pub(super) fn serialize_tuple(
@@ -79,17 +86,17 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeSeqWrapper<'h, S, H>
         actions: SeqElementActions,
     ) -> Result<Self, S::Error> {
         // If length may be changed, we force serialization of this tuple as seq.
-        if len_hint_with_actions(Some(len), &actions).is_none() {
+        let Some(len) = len_hint_with_actions(Some(len), &actions, hooks) else {
             return Self::serialize_seq(serializer, None, hooks, actions);
-        }
+        };
 
-        Ok(Self::Wrapped {
-            wrap: Wrap::SerializeTuple(serializer.serialize_tuple(len)?),
+        let is_human_readable = serializer.is_human_readable();
+        Ok(Self::new_wrapped(
+            Wrap::SerializeTuple(serializer.serialize_tuple(len)?),
             hooks,
-            have_retains: have_retains(&actions),
             actions,
-            current_index: 0,
-        })
+            is_human_readable,
+        ))
     }
 
     pub(super) fn serialize_tuple_struct(
@@ -100,17 +107,17 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeSeqWrapper<'h, S, H>
         actions: SeqElementActions,
     ) -> Result<Self, S::Error> {
         // If length may be changed, we force serialization of this tuple as seq.
-        if len_hint_with_actions(Some(len), &actions).is_none() {
+        let Some(len) = len_hint_with_actions(Some(len), &actions, hooks) else {
             return Self::serialize_seq(serializer, None, hooks, actions);
-        }
+        };
 
-        Ok(Self::Wrapped {
-            wrap: Wrap::SerializeTupleStruct(serializer.serialize_tuple_struct(name, len)?),
+        let is_human_readable = serializer.is_human_readable();
+        Ok(Self::new_wrapped(
+            Wrap::SerializeTupleStruct(serializer.serialize_tuple_struct(name, len)?),
             hooks,
-            have_retains: have_retains(&actions),
             actions,
-            current_index: 0,
-        })
+            is_human_readable,
+        ))
     }
 
     pub(super) fn serialize_tuple_variant(
@@ -123,28 +130,151 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeSeqWrapper<'h, S, H>
         actions: SeqElementActions,
     ) -> Result<Self, S::Error> {
         // If length may be changed, we force serialization of this tuple as seq.
-        if len_hint_with_actions(Some(len), &actions).is_none() {
+        let Some(len) = len_hint_with_actions(Some(len), &actions, hooks) else {
             return Self::serialize_seq(serializer, None, hooks, actions);
-        }
+        };
 
-        Ok(Self::Wrapped {
-            wrap: Wrap::SerializeTupleVariant(serializer.serialize_tuple_variant(
+        let is_human_readable = serializer.is_human_readable();
+        Ok(Self::new_wrapped(
+            Wrap::SerializeTupleVariant(serializer.serialize_tuple_variant(
                 name,
                 variant_index,
                 variant,
                 len,
             )?),
             hooks,
-            have_retains: have_retains(&actions),
             actions,
-            current_index: 0,
-        })
+            is_human_readable,
+        ))
     }
 
     pub(super) fn new_skipped(end_result: Result<S::Ok, S::Error>) -> Self {
         Self::Skipped { end_result }
     }
 
+    fn new_wrapped(
+        wrap: Wrap<S>,
+        hooks: &'h H,
+        mut actions: SeqElementActions,
+        is_human_readable: bool,
+    ) -> Self {
+        let have_retains = have_retains(&actions);
+
+        // Skipping wins over retaining, same as for elements with explicit indices.
+        let last_element = if actions
+            .iter()
+            .any(|a| matches!(a, SeqElementAction::SkipLast))
+        {
+            Some(LastElementAction::Skip)
+        } else if actions
+            .iter()
+            .any(|a| matches!(a, SeqElementAction::RetainLast))
+        {
+            Some(LastElementAction::Retain)
+        } else {
+            None
+        };
+        actions.retain(|a| {
+            !matches!(
+                a,
+                SeqElementAction::SkipLast
+                    | SeqElementAction::RetainLast
+                    | SeqElementAction::LenHint(_)
+            )
+        });
+
+        Self::Wrapped {
+            wrap,
+            hooks,
+            actions,
+            have_retains,
+            current_index: 0,
+            last_element,
+            is_human_readable,
+            pending: None,
+        }
+    }
+
+    fn capture_element<T: Serialize + ?Sized>(
+        value: &T,
+        replacement_value: Option<Value>,
+        hooks: &'h H,
+        is_human_readable: bool,
+    ) -> Result<Vec<Content>, S::Error> {
+        if let Some(replacement_value) = replacement_value {
+            return Ok(vec![Content::capture(
+                &replacement_value,
+                is_human_readable,
+            )?]);
+        }
+
+        let mut contents = if hooks.is_value_skipping_allowed() {
+            super::capture_skippable_value::<T, S, H>(value, hooks, is_human_readable)?
+                .into_iter()
+                .collect()
+        } else {
+            let s = SerializableWithHooks::new(value, hooks, SerializableKind::Value);
+            vec![Content::capture(&s, is_human_readable)?]
+        };
+        for v in hooks.take_expanded_elements().iter() {
+            contents.push(Content::capture(v, is_human_readable)?);
+        }
+        Ok(contents)
+    }
+
+    fn insert_element(
+        wrap: &mut Wrap<S>,
+        hooks: &'h H,
+        index: usize,
+        value: &StaticValue,
+    ) -> Result<(), S::Error> {
+        value
+            .check_if_can_serialize()
+            .or_else(|err| hooks.on_error::<S>(err))?;
+
+        hooks.path_push::<S>(PathSegment::SeqElement(index))?;
+        let res = wrap
+            .serialize_element(&SerializableWithHooks::new(
+                value,
+                hooks,
+                SerializableKind::Value,
+            ))
+            .and_then(|_| {
+                hooks
+                    .take_expanded_elements()
+                    .iter()
+                    .try_for_each(|v| wrap.serialize_element(v))
+            });
+        hooks.path_pop();
+        res
+    }
+
+    fn capture_inserted_element(
+        hooks: &'h H,
+        index: usize,
+        value: &StaticValue,
+        is_human_readable: bool,
+    ) -> Result<Vec<Content>, S::Error> {
+        value
+            .check_if_can_serialize()
+            .or_else(|err| hooks.on_error::<S>(err))?;
+
+        hooks.path_push::<S>(PathSegment::SeqElement(index))?;
+        let res = Content::capture(
+            &SerializableWithHooks::new(value, hooks, SerializableKind::Value),
+            is_human_readable,
+        )
+        .and_then(|element| {
+            let mut contents = vec![element];
+            for v in hooks.take_expanded_elements().iter() {
+                contents.push(Content::capture(v, is_human_readable)?);
+            }
+            Ok(contents)
+        });
+        hooks.path_pop();
+        res
+    }
+
     fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), S::Error>
     where
         T: Serialize,
@@ -157,10 +287,20 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeSeqWrapper<'h, S, H>
                 actions,
                 have_retains,
                 current_index,
+                last_element,
+                is_human_readable,
+                pending,
             } => {
+                // A new element has come, so the pending one was not the last.
+                if let Some(pending) = pending.take() {
+                    pending.flush(wrap, *last_element == Some(LastElementAction::Skip))?;
+                }
+
                 let mut retain_field = false;
                 let mut skip_field = false;
                 let mut replacement_value: Option<Value> = None;
+                let mut insert_before: SmallVec<[StaticValue; 2]> = SmallVec::new();
+                let mut insert_after: SmallVec<[StaticValue; 2]> = SmallVec::new();
 
                 actions.retain_mut(|a| match a {
                     SeqElementAction::Retain(index) => {
@@ -184,13 +324,64 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeSeqWrapper<'h, S, H>
                         }
                         !matches
                     }
+                    SeqElementAction::Insert(index, v, location) => {
+                        let matches = *current_index == *index;
+                        match location {
+                            SeqInsertLocation::Before if matches => {
+                                insert_before.push(v.clone());
+                                false
+                            }
+                            SeqInsertLocation::After if matches => {
+                                insert_after.push(v.clone());
+                                false
+                            }
+                            _ => true,
+                        }
+                    }
+                    SeqElementAction::RetainRange(range) => {
+                        if range.contains(current_index) {
+                            retain_field = true;
+                        }
+                        true
+                    }
+                    SeqElementAction::SkipRange(range) => {
+                        if range.contains(current_index) {
+                            skip_field = true;
+                        }
+                        true
+                    }
+                    SeqElementAction::SkipMatching(predicate) => {
+                        if !skip_field {
+                            // Only primitive values are captured, compound ones are never skipped.
+                            // Values that fail to serialize are not skipped either, so that the
+                            // error is reported when the element is serialized.
+                            let value = MapKeyCapture::capture(value, String::new(), false);
+                            if matches!(value, Ok(v) if v.is_primitive() && predicate(&v)) {
+                                skip_field = true;
+                            }
+                        }
+                        true
+                    }
+                    SeqElementAction::Truncate(max_len) => {
+                        if *current_index >= *max_len {
+                            skip_field = true;
+                        }
+                        true
+                    }
+                    SeqElementAction::SkipLast
+                    | SeqElementAction::RetainLast
+                    | SeqElementAction::LenHint(_) => true,
                 });
 
+                for v in &insert_before {
+                    Self::insert_element(wrap, hooks, *current_index, v)?;
+                }
+
                 if *have_retains && !retain_field {
                     skip_field = true;
                 }
 
-                hooks.path_push(PathSegment::SeqElement(*current_index));
+                hooks.path_push::<S>(PathSegment::SeqElement(*current_index))?;
 
                 if let Some(replacement_value) = &replacement_value {
                     replacement_value
@@ -198,19 +389,72 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeSeqWrapper<'h, S, H>
                         .or_else(|err| hooks.on_error::<S>(err))?;
                 }
 
-                let res = if skip_field {
+                // Elements that would be serialized unless they are the last one, or
+                // skipped unless they are the last one, are held back.
+                let defer = match last_element {
+                    Some(LastElementAction::Skip) => !skip_field,
+                    Some(LastElementAction::Retain) => skip_field,
+                    None => false,
+                };
+
+                let res = if defer {
+                    Self::capture_element(value, replacement_value, *hooks, *is_human_readable).map(
+                        |element| {
+                            *pending = Some(PendingElement {
+                                element,
+                                inserted_after: Vec::new(),
+                            })
+                        },
+                    )
+                } else if skip_field {
                     Ok(())
                 } else if let Some(replacement_value) = replacement_value {
                     wrap.serialize_element(&replacement_value)
+                } else if hooks.is_value_skipping_allowed() {
+                    Self::capture_element(value, None, *hooks, *is_human_readable).and_then(
+                        |element| element.iter().try_for_each(|c| wrap.serialize_element(c)),
+                    )
                 } else {
                     let s = SerializableWithHooks::new(value, *hooks, SerializableKind::Value);
-                    wrap.serialize_element(&s)
+                    wrap.serialize_element(&s).and_then(|_| {
+                        hooks
+                            .take_expanded_elements()
+                            .iter()
+                            .try_for_each(|v| wrap.serialize_element(v))
+                    })
                 };
 
+                if res.is_ok() {
+                    hooks.on_seq_element_end(*current_index);
+                }
+
                 hooks.path_pop();
+                res?;
+
+                // Elements inserted after a held back element are held back along with it.
+                match pending {
+                    Some(pending) => {
+                        for v in &insert_after {
+                            pending
+                                .inserted_after
+                                .extend(Self::capture_inserted_element(
+                                    hooks,
+                                    *current_index,
+                                    v,
+                                    *is_human_readable,
+                                )?);
+                        }
+                    }
+                    None => {
+                        for v in &insert_after {
+                            Self::insert_element(wrap, hooks, *current_index, v)?;
+                        }
+                    }
+                }
+
                 *current_index += 1;
 
-                res
+                Ok(())
             }
         }
     }
@@ -219,18 +463,37 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeSeqWrapper<'h, S, H>
         match self {
             SerializeSeqWrapper::Skipped { end_result } => end_result,
             SerializeSeqWrapper::Wrapped {
-                wrap,
+                mut wrap,
                 hooks,
                 actions,
+                current_index,
+                last_element,
+                pending,
                 ..
             } => {
-                if let Some(a) = actions.into_iter().next() {
+                // The pending element is the last one.
+                if let Some(pending) = pending {
+                    pending.flush(&mut wrap, last_element == Some(LastElementAction::Retain))?;
+                }
+
+                for a in actions {
                     match a {
+                        SeqElementAction::Insert(_, v, SeqInsertLocation::End) => {
+                            Self::insert_element(&mut wrap, hooks, current_index, &v)?
+                        }
                         SeqElementAction::Retain(index)
                         | SeqElementAction::Skip(index)
-                        | SeqElementAction::ReplaceValue(index, _) => {
+                        | SeqElementAction::ReplaceValue(index, _)
+                        | SeqElementAction::Insert(index, _, _) => {
                             hooks.on_error::<S>(HooksError::IndexNotFound(index))?
                         }
+                        SeqElementAction::RetainRange(_)
+                        | SeqElementAction::SkipRange(_)
+                        | SeqElementAction::SkipMatching(_)
+                        | SeqElementAction::Truncate(_)
+                        | SeqElementAction::SkipLast
+                        | SeqElementAction::RetainLast
+                        | SeqElementAction::LenHint(_) => {}
                     }
                 }
 
@@ -312,17 +575,72 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> serde::ser::SerializeTupleVar
     }
 }
 
+/// Element held back by `skip_last`/`retain_last`, along with elements inserted after it.
+pub(crate) struct PendingElement {
+    element: Vec<Content>,
+    inserted_after: Vec<Content>,
+}
+
+impl PendingElement {
+    fn flush<S: Serializer>(self, wrap: &mut Wrap<S>, keep_element: bool) -> Result<(), S::Error> {
+        if keep_element {
+            self.element
+                .iter()
+                .try_for_each(|c| wrap.serialize_element(c))?;
+        }
+        self.inserted_after
+            .iter()
+            .try_for_each(|c| wrap.serialize_element(c))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LastElementAction {
+    Skip,
+    Retain,
+}
+
 fn have_retains(actions: &SeqElementActions) -> bool {
-    actions
-        .iter()
-        .any(|a| matches!(a, SeqElementAction::Retain(_)))
+    actions.iter().any(|a| {
+        matches!(
+            a,
+            SeqElementAction::Retain(_)
+                | SeqElementAction::RetainRange(_)
+                | SeqElementAction::RetainLast
+        )
+    })
 }
 
-fn len_hint_with_actions(len: Option<usize>, actions: &SeqElementActions) -> Option<usize> {
+fn len_hint_with_actions<H: SerializerWrapperHooks>(
+    len: Option<usize>,
+    actions: &SeqElementActions,
+    hooks: &H,
+) -> Option<usize> {
+    let len_hint = actions.iter().rev().find_map(|a| match a {
+        SeqElementAction::LenHint(len) => Some(*len),
+        _ => None,
+    });
+    if len_hint.is_some() {
+        return len_hint;
+    }
+
     len.and_then(|len| {
-        if actions
-            .iter()
-            .any(|a| matches!(a, SeqElementAction::Retain(_) | SeqElementAction::Skip(_)))
+        if hooks.is_value_skipping_allowed()
+            || hooks.is_element_expanding_allowed()
+            || actions.iter().any(|a| {
+                matches!(
+                    a,
+                    SeqElementAction::Retain(_)
+                        | SeqElementAction::Skip(_)
+                        | SeqElementAction::Insert(_, _, _)
+                        | SeqElementAction::RetainRange(_)
+                        | SeqElementAction::SkipRange(_)
+                        | SeqElementAction::SkipMatching(_)
+                        | SeqElementAction::Truncate(_)
+                        | SeqElementAction::SkipLast
+                        | SeqElementAction::RetainLast
+                )
+            })
         {
             None
         } else {
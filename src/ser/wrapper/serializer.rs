@@ -1,12 +1,14 @@
 use std::borrow::Cow;
 
+use serde::ser::{SerializeMap, SerializeStruct};
 use serde::{Serialize, Serializer};
 
+use super::flatten::{FlattenError, FlattenSerializer};
 use super::map::SerializeMapWrapper;
 use super::r#struct::SerializeStructWrapper;
 use super::seq::SerializeSeqWrapper;
 use super::{SerializableKind, SerializerWrapperHooks, ValueAction, VariantAction, VariantActions};
-use crate::Case;
+use crate::{ser::HooksError, Case};
 
 pub(crate) struct SerializerWrapper<'h, S, H: SerializerWrapperHooks> {
     serializer: S,
@@ -61,6 +63,9 @@ macro_rules! value_serialize {
             match value_action {
                 ValueAction::ContinueSerialization(s) => s.$fn($($arg,)* $($v)?),
                 ValueAction::ValueReplaced(r) => r,
+                // Skipped values are captured by the containing wrapper, which drops
+                // the captured output, so any placeholder will do.
+                ValueAction::Skipped(s) => s.serialize_unit(),
             }
         }
     };
@@ -88,14 +93,71 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Serializer for SerializerWrap
     value_serialize!(serialize_u32, U32, v: u32);
     value_serialize!(serialize_u64, U64, v: u64);
     value_serialize!(serialize_u128, U128, v: u128);
-    value_serialize!(serialize_f32, F32, v: f32);
-    value_serialize!(serialize_f64, F64, v: f64);
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        let value_action = on_value_callback!(self F32, v: f32);
+        let value_action = match (value_action, self.kind) {
+            (ValueAction::ContinueSerialization(s), SerializableKind::Value) => {
+                self.hooks.on_float(s, v.into())?
+            }
+            (value_action, _) => value_action,
+        };
+        match value_action {
+            ValueAction::ContinueSerialization(s) => s.serialize_f32(v),
+            ValueAction::ValueReplaced(r) => r,
+            ValueAction::Skipped(s) => s.serialize_unit(),
+        }
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        let value_action = on_value_callback!(self F64, v: f64);
+        let value_action = match (value_action, self.kind) {
+            (ValueAction::ContinueSerialization(s), SerializableKind::Value) => {
+                self.hooks.on_float(s, v)?
+            }
+            (value_action, _) => value_action,
+        };
+        match value_action {
+            ValueAction::ContinueSerialization(s) => s.serialize_f64(v),
+            ValueAction::ValueReplaced(r) => r,
+            ValueAction::Skipped(s) => s.serialize_unit(),
+        }
+    }
     value_serialize!(serialize_char, Char, v: char);
     value_serialize!(serialize_str, Str, v: &str);
-    value_serialize!(serialize_bytes, Bytes, v: &[u8]);
     value_serialize!(serialize_unit, Unit);
 
-    value_serialize!(serialize_unit_struct, UnitStruct, name: &'static str);
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        let value_action = on_value_callback!(self UnitStruct, name: &'static str);
+        match value_action {
+            ValueAction::ValueReplaced(r) => r,
+            ValueAction::Skipped(s) => s.serialize_unit(),
+            ValueAction::ContinueSerialization(s) => {
+                let name = match self.kind {
+                    SerializableKind::Value => match self.hooks.on_unit_struct(name) {
+                        Some(new_name) => self.hooks.make_static_str(new_name),
+                        None => name,
+                    },
+                    SerializableKind::MapKey => name,
+                };
+                s.serialize_unit_struct(name)
+            }
+        }
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        let value_action = on_value_callback!(self Bytes, v: &[u8]);
+        let value_action = match (value_action, self.kind) {
+            (ValueAction::ContinueSerialization(s), SerializableKind::Value) => {
+                self.hooks.on_bytes(s, v)?
+            }
+            (value_action, _) => value_action,
+        };
+        match value_action {
+            ValueAction::ContinueSerialization(s) => s.serialize_bytes(v),
+            ValueAction::ValueReplaced(r) => r,
+            ValueAction::Skipped(s) => s.serialize_unit(),
+        }
+    }
 
     fn serialize_unit_variant(
         self,
@@ -111,16 +173,24 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Serializer for SerializerWrap
 
         match value_action {
             ValueAction::ValueReplaced(r) => r,
+            ValueAction::Skipped(s) => s.serialize_unit(),
             ValueAction::ContinueSerialization(s) => {
                 let variant_actions = self.hooks.on_unit_variant(name, variant, variant_index);
-                let (name, variant_index, variant) = apply_variant_actions(
+                let (name, variant_index, variant, tag) = apply_variant_actions(
                     name,
                     variant_index,
                     variant,
                     variant_actions,
                     self.hooks,
                 );
-                s.serialize_unit_variant(name, variant_index, variant)
+                match tag {
+                    Some(tag) => {
+                        let mut serialize_struct = s.serialize_struct(name, 1)?;
+                        serialize_struct.serialize_field(tag, variant)?;
+                        serialize_struct.end()
+                    }
+                    None => s.serialize_unit_variant(name, variant_index, variant),
+                }
             }
         }
     }
@@ -143,35 +213,82 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Serializer for SerializerWrap
 
         match value_action {
             ValueAction::ValueReplaced(r) => r,
+            ValueAction::Skipped(s) => s.serialize_unit(),
             ValueAction::ContinueSerialization(s) => {
                 let variant_actions = self.hooks.on_newtype_variant(name, variant, variant_index);
-                let (name, variant_index, variant) = apply_variant_actions(
+                let (name, variant_index, variant, tag) = apply_variant_actions(
                     name,
                     variant_index,
                     variant,
                     variant_actions,
                     self.hooks,
                 );
-                s.serialize_newtype_variant(name, variant_index, variant, value)
+                match tag {
+                    Some(tag) => {
+                        serialize_internally_tagged_newtype(s, tag, variant, value, self.hooks)
+                    }
+                    None => s.serialize_newtype_variant(name, variant_index, variant, value),
+                }
+            }
+        }
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        let value_action = on_value_callback!(self NewtypeStruct, name: &'static str);
+        match value_action {
+            ValueAction::ValueReplaced(r) => r,
+            ValueAction::Skipped(s) => s.serialize_unit(),
+            ValueAction::ContinueSerialization(s) => match self.kind {
+                SerializableKind::Value => {
+                    let actions = self.hooks.on_newtype_struct(name);
+                    if actions.unwrap {
+                        value.serialize(s)
+                    } else {
+                        let name = match actions.rename {
+                            Some(new_name) => self.hooks.make_static_str(new_name),
+                            None => name,
+                        };
+                        s.serialize_newtype_struct(name, value)
+                    }
+                }
+                SerializableKind::MapKey => s.serialize_newtype_struct(name, value),
+            },
+        }
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        let value_action = on_value_callback!(self None);
+        let value_action = match (value_action, self.kind) {
+            (ValueAction::ContinueSerialization(s), SerializableKind::Value) => {
+                self.hooks.on_option(s, false)?
             }
+            (value_action, _) => value_action,
+        };
+        match value_action {
+            ValueAction::ContinueSerialization(s) => s.serialize_none(),
+            ValueAction::ValueReplaced(r) => r,
+            ValueAction::Skipped(s) => s.serialize_unit(),
         }
     }
 
-    value_serialize!(
-        serialize_newtype_struct,
-        NewtypeStruct,
-        name: &'static str
-        =>
-        value: T
-    );
-
-    value_serialize!(serialize_none, None);
-    value_serialize!(
-        serialize_some,
-        Some
-        =>
-        value: T
-    );
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        let value_action = on_value_callback!(self Some);
+        let value_action = match (value_action, self.kind) {
+            (ValueAction::ContinueSerialization(s), SerializableKind::Value) => {
+                self.hooks.on_option(s, true)?
+            }
+            (value_action, _) => value_action,
+        };
+        match value_action {
+            ValueAction::ContinueSerialization(s) => s.serialize_some(value),
+            ValueAction::ValueReplaced(r) => r,
+            ValueAction::Skipped(s) => s.serialize_unit(),
+        }
+    }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
         let value_action = on_value_callback!(self Seq,
@@ -179,6 +296,7 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Serializer for SerializerWrap
         );
         match value_action {
             ValueAction::ValueReplaced(r) => Ok(SerializeSeqWrapper::new_skipped(r)),
+            ValueAction::Skipped(s) => Ok(SerializeSeqWrapper::new_skipped(s.serialize_unit())),
             ValueAction::ContinueSerialization(s) => {
                 let actions = self.hooks.on_seq(len);
                 SerializeSeqWrapper::serialize_seq(s, len, self.hooks, actions)
@@ -192,6 +310,7 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Serializer for SerializerWrap
         );
         match value_action {
             ValueAction::ValueReplaced(r) => Ok(SerializeSeqWrapper::new_skipped(r)),
+            ValueAction::Skipped(s) => Ok(SerializeSeqWrapper::new_skipped(s.serialize_unit())),
             ValueAction::ContinueSerialization(s) => {
                 let seq_actions = self.hooks.on_tuple(len);
                 SerializeSeqWrapper::serialize_tuple(s, len, self.hooks, seq_actions)
@@ -210,6 +329,7 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Serializer for SerializerWrap
         );
         match value_action {
             ValueAction::ValueReplaced(r) => Ok(SerializeSeqWrapper::new_skipped(r)),
+            ValueAction::Skipped(s) => Ok(SerializeSeqWrapper::new_skipped(s.serialize_unit())),
             ValueAction::ContinueSerialization(s) => {
                 let seq_actions = self.hooks.on_tuple_struct(name, len);
                 SerializeSeqWrapper::serialize_tuple_struct(s, name, len, self.hooks, seq_actions)
@@ -233,12 +353,14 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Serializer for SerializerWrap
 
         match value_action {
             ValueAction::ValueReplaced(r) => Ok(SerializeSeqWrapper::new_skipped(r)),
+            ValueAction::Skipped(s) => Ok(SerializeSeqWrapper::new_skipped(s.serialize_unit())),
             ValueAction::ContinueSerialization(s) => {
                 let (variant_actions, seq_actions) =
                     self.hooks
                         .on_tuple_variant(name, variant_index, variant, len);
 
-                let (name, variant_index, variant) = apply_variant_actions(
+                // Tuple variants cannot be internally tagged.
+                let (name, variant_index, variant, _) = apply_variant_actions(
                     name,
                     variant_index,
                     variant,
@@ -265,6 +387,7 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Serializer for SerializerWrap
         );
         match value_action {
             ValueAction::ValueReplaced(r) => Ok(SerializeMapWrapper::new_skipped(r)),
+            ValueAction::Skipped(s) => Ok(SerializeMapWrapper::new_skipped(s.serialize_unit())),
             ValueAction::ContinueSerialization(s) => {
                 let actions = self.hooks.on_map(len);
                 SerializeMapWrapper::serialize_map(s, len, self.hooks, actions)
@@ -283,6 +406,7 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Serializer for SerializerWrap
         );
         match value_action {
             ValueAction::ValueReplaced(r) => Ok(SerializeStructWrapper::new_skipped(r)),
+            ValueAction::Skipped(s) => Ok(SerializeStructWrapper::new_skipped(s.serialize_unit())),
             ValueAction::ContinueSerialization(s) => {
                 let (struct_actions, field_actions) = self.hooks.on_struct(len, name);
 
@@ -313,12 +437,13 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Serializer for SerializerWrap
         );
         match value_action {
             ValueAction::ValueReplaced(r) => Ok(SerializeStructWrapper::new_skipped(r)),
+            ValueAction::Skipped(s) => Ok(SerializeStructWrapper::new_skipped(s.serialize_unit())),
             ValueAction::ContinueSerialization(s) => {
                 let (variant_actions, struct_actions, field_actions) = self
                     .hooks
                     .on_struct_variant(len, name, variant, variant_index);
 
-                let (name, variant_index, variant) = apply_variant_actions(
+                let (name, variant_index, variant, tag) = apply_variant_actions(
                     name,
                     variant_index,
                     variant,
@@ -331,6 +456,7 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Serializer for SerializerWrap
                     name,
                     variant_index,
                     variant,
+                    tag,
                     len,
                     self.hooks,
                     struct_actions,
@@ -341,19 +467,47 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Serializer for SerializerWrap
     }
 }
 
-/// Applies variant actions and return (possibly) new enum name, variant index and variant name.
+/// Serializes a newtype variant as a map with the tag entry, followed by the flattened
+/// entries of the variant content.
+fn serialize_internally_tagged_newtype<S: Serializer, H: SerializerWrapperHooks, T>(
+    serializer: S,
+    tag: &'static str,
+    variant: &'static str,
+    value: &T,
+    hooks: &H,
+) -> Result<S::Ok, S::Error>
+where
+    T: Serialize + ?Sized,
+{
+    let mut serialize_map =
+        SerializeMapWrapper::serialize_map(serializer, None, hooks, Default::default())?;
+    serialize_map.serialize_unhooked_key(tag)?;
+    serialize_map.serialize_unhooked_value(variant)?;
+    match value.serialize(FlattenSerializer::new(&mut serialize_map)) {
+        Ok(()) => {}
+        Err(FlattenError::SerializerError(e)) => return Err(e),
+        Err(FlattenError::UnsupportedDataType(data_type)) => {
+            hooks.on_error::<S>(HooksError::CannotFlattenUnsupportedDataType(data_type))?
+        }
+    }
+    serialize_map.end()
+}
+
+/// Applies variant actions and return (possibly) new enum name, variant index and variant name,
+/// and the tag field name if the variant is to be serialized as internally tagged.
 fn apply_variant_actions(
     name: &'static str,
     variant_index: u32,
     variant: &'static str,
     actions: VariantActions,
     hooks: &impl SerializerWrapperHooks,
-) -> (&'static str, u32, &'static str) {
+) -> (&'static str, u32, &'static str, Option<&'static str>) {
     let mut new_name: Option<Cow<'static, str>> = None;
     let mut enum_case: Option<Case> = None;
     let mut new_variant: Option<Cow<'static, str>> = None;
     let mut variant_case: Option<Case> = None;
     let mut new_variant_index: Option<u32> = None;
+    let mut tag: Option<Cow<'static, str>> = None;
 
     actions.into_iter().rev().for_each(|a| match a {
         VariantAction::RenameEnumCase(c) => {
@@ -371,6 +525,9 @@ fn apply_variant_actions(
         VariantAction::ChangeVariantIndex(i) => {
             new_variant_index.get_or_insert(i);
         }
+        VariantAction::InternallyTagged(t) => {
+            tag.get_or_insert(t);
+        }
     });
 
     if new_name.is_none() {
@@ -389,5 +546,6 @@ fn apply_variant_actions(
         hooks.make_static_str(new_name.unwrap_or(name.into())),
         new_variant_index.unwrap_or(variant_index),
         hooks.make_static_str(new_variant.unwrap_or(variant.into())),
+        tag.map(|tag| hooks.make_static_str(tag)),
     )
 }
@@ -1,13 +1,13 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, cmp::Ordering};
 
-use serde::ser::{SerializeMap, SerializeStruct, SerializeStructVariant};
+use serde::ser::{SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant};
 use serde::{Serialize, Serializer};
 
 use crate::ser::HooksError;
-use crate::{Case, Value};
+use crate::{Case, StaticValue, Value};
 
 use super::flatten::{FlattenError, FlattenSerializer};
-use super::map::SerializeMapWrapper;
+use super::map::{MapKeyCapture, SerializeMapWrapper};
 use super::{
     PathSegment, SerializableKind, SerializableWithHooks, SerializerWrapperHooks, StructActions,
     StructFieldAction, StructFieldActions,
@@ -19,6 +19,7 @@ pub(crate) enum Wrap<'h, S: Serializer, H: SerializerWrapperHooks> {
     SerializeStruct(S::SerializeStruct),
     SerializeStructVariant(S::SerializeStructVariant),
     SerializeAsMap(SerializeMapWrapper<'h, S, H>),
+    SerializeAsSeq(S::SerializeSeq),
 }
 
 impl<'h, S: Serializer, H: SerializerWrapperHooks> Wrap<'h, S, H> {
@@ -29,7 +30,20 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Wrap<'h, S, H> {
         match self {
             Wrap::SerializeStruct(s) => s.serialize_field(key, value),
             Wrap::SerializeStructVariant(s) => s.serialize_field(key, value),
-            Wrap::SerializeAsMap(s) => s.serialize_entry(key, value),
+            Wrap::SerializeAsMap(s) => s.serialize_field_entry(key, value),
+            Wrap::SerializeAsSeq(s) => s.serialize_element(value),
+        }
+    }
+
+    fn serialize_tag(&mut self, tag: &'static str, variant: &'static str) -> Result<(), S::Error> {
+        match self {
+            Wrap::SerializeStruct(s) => s.serialize_field(tag, variant),
+            Wrap::SerializeStructVariant(s) => s.serialize_field(tag, variant),
+            Wrap::SerializeAsMap(s) => {
+                s.serialize_unhooked_key(tag)?;
+                s.serialize_unhooked_value(variant)
+            }
+            Wrap::SerializeAsSeq(s) => s.serialize_element(variant),
         }
     }
 
@@ -37,7 +51,7 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Wrap<'h, S, H> {
         match self {
             Wrap::SerializeStruct(s) => s.skip_field(key),
             Wrap::SerializeStructVariant(s) => s.skip_field(key),
-            Wrap::SerializeAsMap(_) => Ok(()),
+            Wrap::SerializeAsMap(_) | Wrap::SerializeAsSeq(_) => Ok(()),
         }
     }
 
@@ -46,6 +60,7 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> Wrap<'h, S, H> {
             Wrap::SerializeStruct(s) => s.end(),
             Wrap::SerializeStructVariant(s) => s.end(),
             Wrap::SerializeAsMap(s) => s.end(),
+            Wrap::SerializeAsSeq(s) => s.end(),
         }
     }
 }
@@ -58,6 +73,9 @@ pub(crate) enum SerializeStructWrapper<'h, S: Serializer, H: SerializerWrapperHo
         field_actions: StructFieldActions,
         have_retains: bool,
         rename_all: Option<Case>,
+        field_index: usize,
+        emitted_len: usize,
+        is_human_readable: bool,
     },
     Skipped {
         end_result: Result<S::Ok, S::Error>,
@@ -73,6 +91,10 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
         struct_actions: StructActions,
         field_actions: StructFieldActions,
     ) -> Result<Self, S::Error> {
+        if struct_actions.serialize_as_seq {
+            return Self::serialize_struct_as_seq(serializer, len, hooks, field_actions);
+        }
+
         if should_serialize_as_map(&struct_actions, &field_actions) {
             return Self::serialize_struct_as_map(
                 serializer,
@@ -83,12 +105,16 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
             );
         }
 
+        let is_human_readable = serializer.is_human_readable();
         Ok(Self::Wrapped {
             wrap: Wrap::SerializeStruct(serializer.serialize_struct(name, len)?),
             hooks,
             have_retains: have_retains(&field_actions),
             rename_all: rename_all(&field_actions),
             field_actions,
+            field_index: 0,
+            emitted_len: 0,
+            is_human_readable,
         })
     }
 
@@ -98,11 +124,36 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
         name: &'static str,
         variant_index: u32,
         variant: &'static str,
+        tag: Option<&'static str>,
         len: usize,
         hooks: &'h H,
         struct_actions: StructActions,
         field_actions: StructFieldActions,
     ) -> Result<Self, S::Error> {
+        if let Some(tag) = tag {
+            // Internally tagged variants are serialized as structs with the tag field first.
+            let mut wrapper = Self::serialize_struct(
+                serializer,
+                name,
+                len + 1,
+                hooks,
+                struct_actions,
+                field_actions,
+            )?;
+            if let Self::Wrapped {
+                wrap, emitted_len, ..
+            } = &mut wrapper
+            {
+                wrap.serialize_tag(tag, variant)?;
+                *emitted_len += 1;
+            }
+            return Ok(wrapper);
+        }
+
+        if struct_actions.serialize_as_seq {
+            return Self::serialize_struct_as_seq(serializer, len, hooks, field_actions);
+        }
+
         if should_serialize_as_map(&struct_actions, &field_actions) {
             return Self::serialize_struct_as_map(
                 serializer,
@@ -113,6 +164,7 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
             );
         }
 
+        let is_human_readable = serializer.is_human_readable();
         Ok(Self::Wrapped {
             wrap: Wrap::SerializeStructVariant(serializer.serialize_struct_variant(
                 name,
@@ -124,6 +176,9 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
             have_retains: have_retains(&field_actions),
             rename_all: rename_all(&field_actions),
             field_actions,
+            field_index: 0,
+            emitted_len: 0,
+            is_human_readable,
         })
     }
 
@@ -131,29 +186,67 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
         serializer: S,
         len: usize,
         hooks: &'h H,
-        _struct_actions: StructActions,
+        struct_actions: StructActions,
         field_actions: StructFieldActions,
     ) -> Result<Self, S::Error> {
         // If there's any potential of fields being skipped or added, don't feed map length hint
         // to the serializer.
-        let len = if can_change_in_length(&field_actions) {
+        let len = if can_change_in_length(&field_actions) || hooks.is_value_skipping_allowed() {
             None
         } else {
             Some(len)
         };
 
+        let is_human_readable = serializer.is_human_readable();
         let map_entry_actions = hooks.on_map(len);
+        let mut serialize_map =
+            SerializeMapWrapper::serialize_map(serializer, len, hooks, map_entry_actions)?;
+        if let Some(cmp) = struct_actions.sort_map_entries {
+            // Only string keys are sorted, all other keys compare as equal, so that a stable
+            // sort keeps their relative order.
+            serialize_map.sort_entries(Box::new(move |a, b| match (a, b) {
+                (Value::Str(a), Value::Str(b)) => cmp(a, b),
+                _ => Ordering::Equal,
+            }));
+        }
+        if let Some(field_order) = struct_actions.field_order {
+            serialize_map.reorder_entries(field_order);
+        }
+
         Ok(Self::Wrapped {
-            wrap: Wrap::SerializeAsMap(SerializeMapWrapper::serialize_map(
-                serializer,
-                len,
-                hooks,
-                map_entry_actions,
-            )?),
+            wrap: Wrap::SerializeAsMap(serialize_map),
+            hooks,
+            have_retains: have_retains(&field_actions),
+            rename_all: rename_all(&field_actions),
+            field_actions,
+            field_index: 0,
+            emitted_len: 0,
+            is_human_readable,
+        })
+    }
+
+    fn serialize_struct_as_seq(
+        serializer: S,
+        len: usize,
+        hooks: &'h H,
+        field_actions: StructFieldActions,
+    ) -> Result<Self, S::Error> {
+        let len = if can_change_in_length(&field_actions) || hooks.is_value_skipping_allowed() {
+            None
+        } else {
+            Some(len)
+        };
+
+        let is_human_readable = serializer.is_human_readable();
+        Ok(Self::Wrapped {
+            wrap: Wrap::SerializeAsSeq(serializer.serialize_seq(len)?),
             hooks,
             have_retains: have_retains(&field_actions),
             rename_all: rename_all(&field_actions),
             field_actions,
+            field_index: 0,
+            emitted_len: 0,
+            is_human_readable,
         })
     }
 
@@ -173,9 +266,13 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
                 field_actions: actions,
                 have_retains,
                 rename_all,
+                field_index,
+                emitted_len,
+                is_human_readable,
             } => {
                 let mut field_key: Cow<'static, str> = key.into();
                 let mut renamed_field = false;
+                let mut matching_case = None;
                 let mut retain_field = false;
                 let mut skip_field = false;
                 let mut replacement_value: Option<Value> = None;
@@ -196,6 +293,19 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
                         }
                         !matches
                     }
+                    StructFieldAction::SkipIf(n, predicate) => {
+                        let matches = field_key == *n;
+                        if matches {
+                            // Only primitive values are captured, compound ones are never skipped.
+                            // Values that fail to serialize are not skipped either, so that the
+                            // error is reported when the field is serialized.
+                            let value = MapKeyCapture::capture(value, String::new(), false);
+                            if matches!(value, Ok(v) if v.is_primitive() && predicate(&v)) {
+                                skip_field = true;
+                            }
+                        }
+                        !matches
+                    }
                     StructFieldAction::Rename(n, r) => {
                         let matches = field_key == *n;
                         if matches {
@@ -204,6 +314,14 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
                         }
                         !matches
                     }
+                    StructFieldAction::RenameAt(i, r) => {
+                        let matches = *field_index == *i;
+                        if matches {
+                            renamed_field = true;
+                            field_key = r.clone();
+                        }
+                        !matches
+                    }
                     StructFieldAction::ReplaceValue(n, v) => {
                         let matches = field_key == *n;
                         if matches {
@@ -212,6 +330,12 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
                         !matches
                     }
                     StructFieldAction::RenameAllCase(_) => false,
+                    StructFieldAction::RenameMatchingCase(predicate, case) => {
+                        if predicate(key) {
+                            matching_case = Some(*case);
+                        }
+                        true
+                    }
                     StructFieldAction::Flatten(n) => {
                         let matches = field_key == *n;
                         if matches {
@@ -219,8 +343,11 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
                         }
                         !matches
                     }
+                    StructFieldAction::Insert(_, _) | StructFieldAction::Clear => true,
                 });
 
+                *field_index += 1;
+
                 if *have_retains && !retain_field {
                     skip_field = true;
                 }
@@ -228,11 +355,11 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
                 if skip_field {
                     wrap.skip_field(key)
                 } else {
-                    hooks.path_push(PathSegment::StructField(key));
+                    hooks.path_push::<S>(PathSegment::StructField(key))?;
 
                     if !renamed_field {
-                        if let Some(case) = rename_all {
-                            field_key = Case::cow_to_case(&field_key, *case);
+                        if let Some(case) = matching_case.or(*rename_all) {
+                            field_key = Case::cow_to_case(&field_key, case);
                         }
                     }
 
@@ -242,17 +369,13 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
                             .or_else(|err| hooks.on_error::<S>(err))?;
                     }
 
+                    let mut emitted = true;
                     let res = if let Some(replacement_value) = replacement_value {
                         wrap.serialize_field(hooks.make_static_str(field_key), &replacement_value)
                     } else {
                         let s = SerializableWithHooks::new(value, *hooks, SerializableKind::Value);
 
-                        if flatten {
-                            let serialize_map = match wrap {
-                                Wrap::SerializeAsMap(m) => m,
-                                _ => unreachable!(),
-                            };
-
+                        if let (true, Wrap::SerializeAsMap(serialize_map)) = (flatten, &mut *wrap) {
                             let flatten_serializer = FlattenSerializer::new(serialize_map);
                             match s.serialize(flatten_serializer) {
                                 Ok(r) => Ok(r),
@@ -262,39 +385,99 @@ impl<'h, S: Serializer, H: SerializerWrapperHooks> SerializeStructWrapper<'h, S,
                                         data_type,
                                     )),
                             }
+                        } else if hooks.is_value_skipping_allowed() {
+                            super::capture_skippable_value::<T, S, H>(
+                                value,
+                                *hooks,
+                                *is_human_readable,
+                            )
+                            .and_then(|content| match content {
+                                Some(content) => {
+                                    wrap.serialize_field(hooks.make_static_str(field_key), &content)
+                                }
+                                None => {
+                                    emitted = false;
+                                    wrap.skip_field(key)
+                                }
+                            })
                         } else {
                             wrap.serialize_field(hooks.make_static_str(field_key), &s)
                         }
                     };
                     hooks.path_pop();
+                    if res.is_ok() && emitted {
+                        *emitted_len += 1;
+                    }
                     res
                 }
             }
         }
     }
 
+    fn insert_field(
+        wrap: &mut Wrap<'h, S, H>,
+        hooks: &'h H,
+        key: Cow<'static, str>,
+        value: &StaticValue,
+    ) -> Result<(), S::Error> {
+        value
+            .check_if_can_serialize()
+            .or_else(|err| hooks.on_error::<S>(err))?;
+
+        let key = hooks.make_static_str(key);
+        hooks.path_push::<S>(PathSegment::StructField(key))?;
+        let res = wrap.serialize_field(
+            key,
+            &SerializableWithHooks::new(value, hooks, SerializableKind::Value),
+        );
+        hooks.path_pop();
+        res
+    }
+
     fn end(self) -> Result<S::Ok, S::Error> {
         match self {
             SerializeStructWrapper::Skipped { end_result } => end_result,
             SerializeStructWrapper::Wrapped {
-                wrap,
+                mut wrap,
                 hooks,
                 field_actions: actions,
+                mut emitted_len,
                 ..
             } => {
-                if let Some(a) = actions.into_iter().next() {
+                let mut not_found = Vec::new();
+                for a in actions {
+                    match a {
+                        StructFieldAction::Insert(key, value) => {
+                            Self::insert_field(&mut wrap, hooks, key, &value)?;
+                            emitted_len += 1;
+                        }
+                        StructFieldAction::RenameMatchingCase(_, _) | StructFieldAction::Clear => {}
+                        a => not_found.push(a),
+                    }
+                }
+
+                for a in not_found {
                     match a {
                         StructFieldAction::Retain(f)
                         | StructFieldAction::Skip(f)
+                        | StructFieldAction::SkipIf(f, _)
                         | StructFieldAction::Rename(f, _)
                         | StructFieldAction::ReplaceValue(f, _)
                         | StructFieldAction::Flatten(f) => {
                             hooks.on_error::<S>(HooksError::FieldNotFound(f))?
                         }
-                        StructFieldAction::RenameAllCase(_) => {}
+                        StructFieldAction::RenameAt(i, _) => {
+                            hooks.on_error::<S>(HooksError::FieldIndexNotFound(i))?
+                        }
+                        StructFieldAction::RenameAllCase(_)
+                        | StructFieldAction::RenameMatchingCase(_, _)
+                        | StructFieldAction::Insert(_, _)
+                        | StructFieldAction::Clear => {}
                     }
                 }
 
+                hooks.on_struct_end(emitted_len);
+
                 wrap.end()
             }
         }
@@ -350,9 +533,14 @@ fn should_serialize_as_map(
     field_actions: &StructFieldActions,
 ) -> bool {
     struct_actions.serialize_as_map
-        || field_actions
-            .iter()
-            .any(|a| matches!(a, StructFieldAction::Flatten(_)))
+        || field_actions.iter().any(|a| {
+            matches!(
+                a,
+                StructFieldAction::Flatten(_)
+                    | StructFieldAction::Insert(_, _)
+                    | StructFieldAction::Clear
+            )
+        })
 }
 
 fn can_change_in_length(field_actions: &StructFieldActions) -> bool {
@@ -361,15 +549,19 @@ fn can_change_in_length(field_actions: &StructFieldActions) -> bool {
             a,
             StructFieldAction::Retain(_)
                 | StructFieldAction::Skip(_)
+                | StructFieldAction::SkipIf(_, _)
                 | StructFieldAction::Flatten(_)
+                | StructFieldAction::Insert(_, _)
+                | StructFieldAction::Clear
         )
     })
 }
 
 fn have_retains(field_actions: &StructFieldActions) -> bool {
+    // Clearing is the same as retaining nothing, explicitly retained fields are still kept.
     field_actions
         .iter()
-        .any(|a| matches!(a, StructFieldAction::Retain(_)))
+        .any(|a| matches!(a, StructFieldAction::Retain(_) | StructFieldAction::Clear))
 }
 
 fn rename_all(field_actions: &StructFieldActions) -> Option<Case> {
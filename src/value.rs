@@ -1,9 +1,25 @@
-use std::{borrow::Cow, fmt::Display};
+use std::{borrow::Cow, cmp::Ordering, fmt::Display};
 
 /// Value that corresponds to [serde data model](https://serde.rs/data-model.html).
 ///
 /// Primitive values, like numbers, will have the actual value copied,
 /// whilst for compound values, like structs, only metadata is available.
+///
+/// Values are partially ordered with [`PartialOrd`], consistently with their equality, and
+/// totally ordered with [`Value::total_cmp`], which can be used for sorting:
+///
+/// - Values of different kinds are ordered by kind: `bool`, numbers, strings and chars,
+///   bytes, and then the rest of the variants in the order of their declaration.
+/// - Numbers are compared by their numeric value, regardless of their type. Integers are
+///   compared exactly, integers and floats are compared as `f64`, floats are compared
+///   with [`f64::total_cmp`], i.e. `-0.0` goes before `0.0`, and `NaN` goes after infinity
+///   (negative `NaN` before negative infinity). Numerically equal values of different types
+///   are ordered by type, in the order of variants declaration.
+/// - Chars are compared as one-char strings, and go before equal strings.
+/// - Compound values are compared by their metadata (names, variant indices, lengths).
+///
+/// With [`PartialOrd`], floats are compared like `f64`, i.e. `-0.0` is equal to `0.0`,
+/// and `NaN` is not comparable with any number.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value<'v> {
     /// `bool` value
@@ -207,6 +223,64 @@ pub enum Value<'v> {
     },
 }
 
+/// A numeric value, for comparing numbers of different types.
+#[derive(Clone, Copy)]
+enum Number {
+    Signed(i128),
+    Unsigned(u128),
+    Float(f64),
+}
+
+impl Number {
+    fn of(value: &Value) -> Option<Self> {
+        match *value {
+            Value::I8(v) => Some(Number::Signed(v.into())),
+            Value::I16(v) => Some(Number::Signed(v.into())),
+            Value::I32(v) => Some(Number::Signed(v.into())),
+            Value::I64(v) => Some(Number::Signed(v.into())),
+            Value::I128(v) => Some(Number::Signed(v)),
+            Value::U8(v) => Some(Number::Unsigned(v.into())),
+            Value::U16(v) => Some(Number::Unsigned(v.into())),
+            Value::U32(v) => Some(Number::Unsigned(v.into())),
+            Value::U64(v) => Some(Number::Unsigned(v.into())),
+            Value::U128(v) => Some(Number::Unsigned(v)),
+            Value::F32(v) => Some(Number::Float(v.into())),
+            Value::F64(v) => Some(Number::Float(v)),
+            _ => None,
+        }
+    }
+
+    fn as_f64(self) -> f64 {
+        match self {
+            Number::Signed(v) => v as f64,
+            Number::Unsigned(v) => v as f64,
+            Number::Float(v) => v,
+        }
+    }
+
+    fn cmp(self, other: Number) -> Ordering {
+        match (self, other) {
+            (Number::Signed(a), Number::Signed(b)) => a.cmp(&b),
+            (Number::Unsigned(a), Number::Unsigned(b)) => a.cmp(&b),
+            (Number::Signed(a), Number::Unsigned(b)) => match u128::try_from(a) {
+                Ok(a) => a.cmp(&b),
+                Err(_) => Ordering::Less,
+            },
+            (Number::Unsigned(_), Number::Signed(_)) => other.cmp(self).reverse(),
+            (a, b) => a.as_f64().total_cmp(&b.as_f64()),
+        }
+    }
+
+    fn partial_cmp(self, other: Number) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Float(_), _) | (_, Number::Float(_)) => {
+                self.as_f64().partial_cmp(&other.as_f64())
+            }
+            _ => Some(self.cmp(other)),
+        }
+    }
+}
+
 /// A [`Value`] with static lifetime for borrowed data (strings, bytes).
 ///
 /// See [Static strings](crate::ser#static-strings) for more info.
@@ -214,6 +288,391 @@ pub type StaticValue = Value<'static>;
 
 impl Eq for Value<'_> {}
 
+impl PartialOrd for Value<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (Number::of(self), Number::of(other)) {
+            (Some(a), Some(b)) => a
+                .partial_cmp(b)
+                .map(|ord| ord.then_with(|| self.variant_rank().cmp(&other.variant_rank()))),
+            _ => Some(self.total_cmp(other)),
+        }
+    }
+}
+
+impl Value<'_> {
+    /// Returns the name of the value type in the [serde data model](https://serde.rs/data-model.html).
+    ///
+    /// Names are stable, lowercase, and follow the names of `Serializer` methods,
+    /// e.g. `"i128"`, `"str"`, `"some"`, `"unit_variant"`, `"struct"`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Bool(_) => "bool",
+            Value::I8(_) => "i8",
+            Value::I16(_) => "i16",
+            Value::I32(_) => "i32",
+            Value::I64(_) => "i64",
+            Value::I128(_) => "i128",
+            Value::U8(_) => "u8",
+            Value::U16(_) => "u16",
+            Value::U32(_) => "u32",
+            Value::U64(_) => "u64",
+            Value::U128(_) => "u128",
+            Value::F32(_) => "f32",
+            Value::F64(_) => "f64",
+            Value::Char(_) => "char",
+            Value::Str(_) => "str",
+            Value::Bytes(_) => "bytes",
+            Value::Unit => "unit",
+            Value::Some => "some",
+            Value::None => "none",
+            Value::UnitStruct(_) => "unit_struct",
+            Value::UnitVariant { .. } => "unit_variant",
+            Value::NewtypeStruct(_) => "newtype_struct",
+            Value::NewtypeVariant { .. } => "newtype_variant",
+            Value::Seq(_) => "seq",
+            Value::Tuple(_) => "tuple",
+            Value::TupleStruct { .. } => "tuple_struct",
+            Value::TupleVariant { .. } => "tuple_variant",
+            Value::Map(_) => "map",
+            Value::Struct { .. } => "struct",
+            Value::StructVariant { .. } => "struct_variant",
+        }
+    }
+
+    /// Returns `true` if the value is primitive, i.e. carries the actual data.
+    ///
+    /// Primitive values are all the scalars (numbers, `bool`, `char`), strings, bytes,
+    /// as well as `Unit`, `None`, `UnitStruct` and `UnitVariant`, that are fully described by
+    /// their metadata. They can be serialized, e.g. passed to
+    /// [`StructScope::replace_value`](crate::ser::StructScope::replace_value)
+    /// without causing a [`HooksError::ValueNotSerializable`](crate::ser::HooksError::ValueNotSerializable) error.
+    pub fn is_primitive(&self) -> bool {
+        !self.is_compound()
+    }
+
+    /// Returns `true` if the value is compound, i.e. only carries metadata.
+    ///
+    /// Compound values are sequences, tuples, maps, structs, and their variations, as well as
+    /// newtypes and `Some`, whose contents are serialized separately.
+    pub fn is_compound(&self) -> bool {
+        matches!(
+            self,
+            Value::Some
+                | Value::NewtypeStruct(_)
+                | Value::NewtypeVariant { .. }
+                | Value::Seq(_)
+                | Value::Tuple(_)
+                | Value::TupleStruct { .. }
+                | Value::TupleVariant { .. }
+                | Value::Map(_)
+                | Value::Struct { .. }
+                | Value::StructVariant { .. }
+        )
+    }
+
+    /// Converts the value into a [`StaticValue`], copying borrowed strings and bytes.
+    pub fn into_owned(self) -> StaticValue {
+        match self {
+            Value::Bool(v) => Value::Bool(v),
+            Value::I8(v) => Value::I8(v),
+            Value::I16(v) => Value::I16(v),
+            Value::I32(v) => Value::I32(v),
+            Value::I64(v) => Value::I64(v),
+            Value::I128(v) => Value::I128(v),
+            Value::U8(v) => Value::U8(v),
+            Value::U16(v) => Value::U16(v),
+            Value::U32(v) => Value::U32(v),
+            Value::U64(v) => Value::U64(v),
+            Value::U128(v) => Value::U128(v),
+            Value::F32(v) => Value::F32(v),
+            Value::F64(v) => Value::F64(v),
+            Value::Char(v) => Value::Char(v),
+            Value::Str(v) => Value::Str(Cow::Owned(v.into_owned())),
+            Value::Bytes(v) => Value::Bytes(Cow::Owned(v.into_owned())),
+            Value::Unit => Value::Unit,
+            Value::Some => Value::Some,
+            Value::None => Value::None,
+            Value::UnitStruct(name) => Value::UnitStruct(name),
+            Value::UnitVariant {
+                name,
+                variant_index,
+                variant,
+            } => Value::UnitVariant {
+                name,
+                variant_index,
+                variant,
+            },
+            Value::NewtypeStruct(name) => Value::NewtypeStruct(name),
+            Value::NewtypeVariant {
+                name,
+                variant_index,
+                variant,
+            } => Value::NewtypeVariant {
+                name,
+                variant_index,
+                variant,
+            },
+            Value::Seq(len) => Value::Seq(len),
+            Value::Tuple(len) => Value::Tuple(len),
+            Value::TupleStruct { name, len } => Value::TupleStruct { name, len },
+            Value::TupleVariant {
+                name,
+                variant_index,
+                variant,
+                len,
+            } => Value::TupleVariant {
+                name,
+                variant_index,
+                variant,
+                len,
+            },
+            Value::Map(len) => Value::Map(len),
+            Value::Struct { name, len } => Value::Struct { name, len },
+            Value::StructVariant {
+                name,
+                variant_index,
+                variant,
+                len,
+            } => Value::StructVariant {
+                name,
+                variant_index,
+                variant,
+                len,
+            },
+        }
+    }
+
+    /// Total ordering of values, see [`Value`] for the rules.
+    ///
+    /// Unlike the equality, distinguishes `-0.0` from `0.0`, and considers `NaN` equal to itself.
+    ///
+    /// ```
+    /// use serde_hooks::Value;
+    ///
+    /// let mut values = vec![Value::Str("a".into()), Value::F64(f64::NAN), Value::U8(1)];
+    /// values.sort_by(Value::total_cmp);
+    /// assert_eq!(values[0], Value::U8(1));
+    /// ```
+    pub fn total_cmp(&self, other: &Value) -> Ordering {
+        // Numbers, as well as chars and strings, are compared with each other.
+        let group = |v: &Value| match v.variant_rank() {
+            1..=12 => 1,
+            14 => 13,
+            rank => rank,
+        };
+
+        group(self)
+            .cmp(&group(other))
+            .then_with(|| self.cmp_within_group(other))
+            .then_with(|| self.variant_rank().cmp(&other.variant_rank()))
+    }
+
+    fn cmp_within_group(&self, other: &Value) -> Ordering {
+        if let (Some(a), Some(b)) = (Number::of(self), Number::of(other)) {
+            return a.cmp(b);
+        }
+
+        let (mut buf_a, mut buf_b) = ([0; 4], [0; 4]);
+        if let (Some(a), Some(b)) = (self.as_text(&mut buf_a), other.as_text(&mut buf_b)) {
+            return a.cmp(b);
+        }
+
+        match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+            (Value::UnitStruct(a), Value::UnitStruct(b))
+            | (Value::NewtypeStruct(a), Value::NewtypeStruct(b)) => a.cmp(b),
+            (
+                Value::UnitVariant {
+                    name: name_a,
+                    variant_index: index_a,
+                    variant: variant_a,
+                },
+                Value::UnitVariant {
+                    name: name_b,
+                    variant_index: index_b,
+                    variant: variant_b,
+                },
+            )
+            | (
+                Value::NewtypeVariant {
+                    name: name_a,
+                    variant_index: index_a,
+                    variant: variant_a,
+                },
+                Value::NewtypeVariant {
+                    name: name_b,
+                    variant_index: index_b,
+                    variant: variant_b,
+                },
+            ) => (name_a, index_a, variant_a).cmp(&(name_b, index_b, variant_b)),
+            (Value::Seq(a), Value::Seq(b)) | (Value::Map(a), Value::Map(b)) => a.cmp(b),
+            (Value::Tuple(a), Value::Tuple(b)) => a.cmp(b),
+            (
+                Value::TupleStruct {
+                    name: name_a,
+                    len: len_a,
+                },
+                Value::TupleStruct {
+                    name: name_b,
+                    len: len_b,
+                },
+            )
+            | (
+                Value::Struct {
+                    name: name_a,
+                    len: len_a,
+                },
+                Value::Struct {
+                    name: name_b,
+                    len: len_b,
+                },
+            ) => (name_a, len_a).cmp(&(name_b, len_b)),
+            (
+                Value::TupleVariant {
+                    name: name_a,
+                    variant_index: index_a,
+                    variant: variant_a,
+                    len: len_a,
+                },
+                Value::TupleVariant {
+                    name: name_b,
+                    variant_index: index_b,
+                    variant: variant_b,
+                    len: len_b,
+                },
+            )
+            | (
+                Value::StructVariant {
+                    name: name_a,
+                    variant_index: index_a,
+                    variant: variant_a,
+                    len: len_a,
+                },
+                Value::StructVariant {
+                    name: name_b,
+                    variant_index: index_b,
+                    variant: variant_b,
+                    len: len_b,
+                },
+            ) => (name_a, index_a, variant_a, len_a).cmp(&(name_b, index_b, variant_b, len_b)),
+            _ => Ordering::Equal,
+        }
+    }
+
+    /// Returns chars and strings as `&str`.
+    fn as_text<'s>(&'s self, buf: &'s mut [u8; 4]) -> Option<&'s str> {
+        match self {
+            Value::Char(c) => Some(c.encode_utf8(buf)),
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Index of the variant in the order of declaration.
+    fn variant_rank(&self) -> u8 {
+        match self {
+            Value::Bool(_) => 0,
+            Value::I8(_) => 1,
+            Value::I16(_) => 2,
+            Value::I32(_) => 3,
+            Value::I64(_) => 4,
+            Value::I128(_) => 5,
+            Value::U8(_) => 6,
+            Value::U16(_) => 7,
+            Value::U32(_) => 8,
+            Value::U64(_) => 9,
+            Value::U128(_) => 10,
+            Value::F32(_) => 11,
+            Value::F64(_) => 12,
+            Value::Char(_) => 13,
+            Value::Str(_) => 14,
+            Value::Bytes(_) => 15,
+            Value::Unit => 16,
+            Value::Some => 17,
+            Value::None => 18,
+            Value::UnitStruct(_) => 19,
+            Value::UnitVariant { .. } => 20,
+            Value::NewtypeStruct(_) => 21,
+            Value::NewtypeVariant { .. } => 22,
+            Value::Seq(_) => 23,
+            Value::Tuple(_) => 24,
+            Value::TupleStruct { .. } => 25,
+            Value::TupleVariant { .. } => 26,
+            Value::Map(_) => 27,
+            Value::Struct { .. } => 28,
+            Value::StructVariant { .. } => 29,
+        }
+    }
+
+    /// Returns an integer value as `i64`.
+    ///
+    /// Works for all integer variants, regardless of their width. Returns `None` if the value
+    /// doesn't fit into `i64` (e.g. `u64` values above `i64::MAX`, or `i128` values out of
+    /// the `i64` range), or if the value is not an integer. Floating point values are not
+    /// converted, even if they have no fractional part.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Value::I8(v) => Some(v.into()),
+            Value::I16(v) => Some(v.into()),
+            Value::I32(v) => Some(v.into()),
+            Value::I64(v) => Some(v),
+            Value::I128(v) => v.try_into().ok(),
+            Value::U8(v) => Some(v.into()),
+            Value::U16(v) => Some(v.into()),
+            Value::U32(v) => Some(v.into()),
+            Value::U64(v) => v.try_into().ok(),
+            Value::U128(v) => v.try_into().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns an integer value as `u64`.
+    ///
+    /// Works for all integer variants, regardless of their width. Returns `None` if the value
+    /// doesn't fit into `u64` (e.g. negative values, or `u128` values above `u64::MAX`),
+    /// or if the value is not an integer. Floating point values are not converted, even if
+    /// they have no fractional part.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Value::I8(v) => v.try_into().ok(),
+            Value::I16(v) => v.try_into().ok(),
+            Value::I32(v) => v.try_into().ok(),
+            Value::I64(v) => v.try_into().ok(),
+            Value::I128(v) => v.try_into().ok(),
+            Value::U8(v) => Some(v.into()),
+            Value::U16(v) => Some(v.into()),
+            Value::U32(v) => Some(v.into()),
+            Value::U64(v) => Some(v),
+            Value::U128(v) => v.try_into().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns a numeric value as `f64`.
+    ///
+    /// Works for all integer and floating point variants. Integers that can't be represented
+    /// exactly (e.g. `u64` values above 2<sup>53</sup>) are rounded to the nearest `f64` value.
+    /// Returns `None` if the value is not numeric.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::I8(v) => Some(v.into()),
+            Value::I16(v) => Some(v.into()),
+            Value::I32(v) => Some(v.into()),
+            Value::I64(v) => Some(v as f64),
+            Value::I128(v) => Some(v as f64),
+            Value::U8(v) => Some(v.into()),
+            Value::U16(v) => Some(v.into()),
+            Value::U32(v) => Some(v.into()),
+            Value::U64(v) => Some(v as f64),
+            Value::U128(v) => Some(v as f64),
+            Value::F32(v) => Some(v.into()),
+            Value::F64(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
 impl Display for Value<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -292,10 +751,12 @@ impl_value_from_type!(I8, i8);
 impl_value_from_type!(I16, i16);
 impl_value_from_type!(I32, i32);
 impl_value_from_type!(I64, i64);
+impl_value_from_type!(I128, i128);
 impl_value_from_type!(U8, u8);
 impl_value_from_type!(U16, u16);
 impl_value_from_type!(U32, u32);
 impl_value_from_type!(U64, u64);
+impl_value_from_type!(U128, u128);
 impl_value_from_type!(F32, f32);
 impl_value_from_type!(F64, f64);
 impl_value_from_type!(Char, char);
@@ -306,6 +767,22 @@ impl From<()> for Value<'_> {
     }
 }
 
+/// Converts `None` to [`Value::None`], and `Some(x)` to the value of `x`.
+///
+/// Note that `Some(x)` is not converted to [`Value::Some`], which is only metadata and
+/// does not carry the contained value. Serializing the converted value, e.g. with
+/// [`StructScope::replace_value`](crate::ser::StructScope::replace_value), produces the
+/// same output as serializing the option for most formats, except the ones that distinguish
+/// `Some` from the contained value, like RON.
+impl<'v, T: Into<Value<'v>>> From<Option<T>> for Value<'v> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => Value::None,
+        }
+    }
+}
+
 macro_rules! cow_value_from_type {
     ($variant:ident,$borrowed:ty,$owned:ty) => {
         impl<'v> From<&'v $borrowed> for Value<'v> {
@@ -330,3 +807,332 @@ macro_rules! cow_value_from_type {
 
 cow_value_from_type!(Str, str, String);
 cow_value_from_type!(Bytes, [u8], Vec<u8>);
+
+/// Converts a primitive value into a JSON value.
+///
+/// Numbers, strings, chars, `bool`, unit and `None` convert to the corresponding JSON values,
+/// bytes convert to an array of numbers, unit structs to `null` and unit variants to the variant
+/// name, the same way as `serde_json` serializes them. Non-finite floats convert to `null`.
+///
+/// Fails for compound values, like [`Value::Struct`], since they only carry metadata,
+/// and for 128-bit integers that don't fit into 64 bits.
+///
+/// Requires the `serde_json` feature.
+#[cfg(feature = "serde_json")]
+impl TryFrom<&Value<'_>> for serde_json::Value {
+    type Error = serde_json::Error;
+
+    fn try_from(value: &Value<'_>) -> Result<Self, Self::Error> {
+        serde_json::to_value(value)
+    }
+}
+
+#[test]
+fn test_type_name() {
+    let values = [
+        (Value::Bool(true), "bool"),
+        (Value::I8(0), "i8"),
+        (Value::I16(0), "i16"),
+        (Value::I32(0), "i32"),
+        (Value::I64(0), "i64"),
+        (Value::I128(0), "i128"),
+        (Value::U8(0), "u8"),
+        (Value::U16(0), "u16"),
+        (Value::U32(0), "u32"),
+        (Value::U64(0), "u64"),
+        (Value::U128(0), "u128"),
+        (Value::F32(0.0), "f32"),
+        (Value::F64(0.0), "f64"),
+        (Value::Char('c'), "char"),
+        (Value::from("str"), "str"),
+        (Value::from(&b"bytes"[..]), "bytes"),
+        (Value::Unit, "unit"),
+        (Value::Some, "some"),
+        (Value::None, "none"),
+        (Value::UnitStruct("S"), "unit_struct"),
+        (
+            Value::UnitVariant {
+                name: "E",
+                variant_index: 0,
+                variant: "V",
+            },
+            "unit_variant",
+        ),
+        (Value::NewtypeStruct("S"), "newtype_struct"),
+        (
+            Value::NewtypeVariant {
+                name: "E",
+                variant_index: 0,
+                variant: "V",
+            },
+            "newtype_variant",
+        ),
+        (Value::Seq(None), "seq"),
+        (Value::Tuple(2), "tuple"),
+        (Value::TupleStruct { name: "S", len: 2 }, "tuple_struct"),
+        (
+            Value::TupleVariant {
+                name: "E",
+                variant_index: 0,
+                variant: "V",
+                len: 2,
+            },
+            "tuple_variant",
+        ),
+        (Value::Map(Some(1)), "map"),
+        (Value::Struct { name: "S", len: 2 }, "struct"),
+        (
+            Value::StructVariant {
+                name: "E",
+                variant_index: 0,
+                variant: "V",
+                len: 2,
+            },
+            "struct_variant",
+        ),
+    ];
+
+    for (value, type_name) in &values {
+        assert_eq!(value.type_name(), *type_name, "{value:?}");
+    }
+}
+
+#[test]
+fn test_numeric_accessors() {
+    assert_eq!(Value::I8(-1).as_i64(), Some(-1));
+    assert_eq!(Value::I8(-1).as_u64(), None);
+    assert_eq!(Value::I8(-1).as_f64(), Some(-1.0));
+
+    assert_eq!(Value::U64(i64::MAX as u64).as_i64(), Some(i64::MAX));
+    assert_eq!(Value::U64(i64::MAX as u64 + 1).as_i64(), None);
+    assert_eq!(Value::U64(u64::MAX).as_u64(), Some(u64::MAX));
+
+    assert_eq!(Value::I128(i64::MIN.into()).as_i64(), Some(i64::MIN));
+    assert_eq!(Value::I128(i128::from(i64::MIN) - 1).as_i64(), None);
+    assert_eq!(Value::I128(i128::from(i64::MAX) + 1).as_i64(), None);
+    assert_eq!(Value::U128(u64::MAX.into()).as_u64(), Some(u64::MAX));
+    assert_eq!(Value::U128(u128::from(u64::MAX) + 1).as_u64(), None);
+    assert_eq!(Value::U128(u128::MAX).as_f64(), Some(u128::MAX as f64));
+
+    assert_eq!(Value::F32(1.5).as_f64(), Some(1.5));
+    assert_eq!(Value::F64(2.0).as_i64(), None);
+    assert_eq!(Value::F64(2.0).as_u64(), None);
+
+    assert_eq!(Value::from("1").as_i64(), None);
+    assert_eq!(Value::Bool(true).as_u64(), None);
+    assert_eq!(Value::Unit.as_f64(), None);
+}
+
+#[test]
+fn test_is_primitive() {
+    let values = [
+        (Value::Bool(true), true),
+        (Value::I8(0), true),
+        (Value::I16(0), true),
+        (Value::I32(0), true),
+        (Value::I64(0), true),
+        (Value::I128(0), true),
+        (Value::U8(0), true),
+        (Value::U16(0), true),
+        (Value::U32(0), true),
+        (Value::U64(0), true),
+        (Value::U128(0), true),
+        (Value::F32(0.0), true),
+        (Value::F64(0.0), true),
+        (Value::Char('c'), true),
+        (Value::from("str"), true),
+        (Value::from(&b"bytes"[..]), true),
+        (Value::Unit, true),
+        (Value::Some, false),
+        (Value::None, true),
+        (Value::UnitStruct("S"), true),
+        (
+            Value::UnitVariant {
+                name: "E",
+                variant_index: 0,
+                variant: "V",
+            },
+            true,
+        ),
+        (Value::NewtypeStruct("S"), false),
+        (
+            Value::NewtypeVariant {
+                name: "E",
+                variant_index: 0,
+                variant: "V",
+            },
+            false,
+        ),
+        (Value::Seq(None), false),
+        (Value::Tuple(2), false),
+        (Value::TupleStruct { name: "S", len: 2 }, false),
+        (
+            Value::TupleVariant {
+                name: "E",
+                variant_index: 0,
+                variant: "V",
+                len: 2,
+            },
+            false,
+        ),
+        (Value::Map(Some(1)), false),
+        (Value::Struct { name: "S", len: 2 }, false),
+        (
+            Value::StructVariant {
+                name: "E",
+                variant_index: 0,
+                variant: "V",
+                len: 2,
+            },
+            false,
+        ),
+    ];
+
+    for (value, is_primitive) in &values {
+        assert_eq!(value.is_primitive(), *is_primitive, "{value:?}");
+        assert_eq!(value.is_compound(), !*is_primitive, "{value:?}");
+        assert_eq!(
+            value.check_if_can_serialize().is_ok(),
+            *is_primitive,
+            "{value:?}"
+        );
+    }
+}
+
+#[test]
+fn test_into_owned() {
+    let s = String::from("borrowed");
+    let value = Value::Str(Cow::Borrowed(&s)).into_owned();
+    drop(s);
+    assert_eq!(value, Value::Str("borrowed".into()));
+
+    let bytes = vec![1u8, 2];
+    assert_eq!(
+        Value::Bytes(Cow::Borrowed(&bytes)).into_owned(),
+        Value::Bytes(vec![1u8, 2].into())
+    );
+    assert_eq!(
+        Value::Struct { name: "S", len: 2 }.into_owned(),
+        Value::Struct { name: "S", len: 2 }
+    );
+}
+
+#[test]
+fn test_ord() {
+    let mut values: Vec<Value> = vec![
+        Value::Str("b".into()),
+        Value::F64(f64::NAN),
+        Value::U8(2),
+        Value::Char('a'),
+        Value::F32(1.5),
+        Value::I64(-3),
+        Value::Str("a".into()),
+        Value::F64(f64::INFINITY),
+        Value::U128(u128::MAX),
+        Value::Bool(true),
+        Value::I32(2),
+        Value::F64(-0.0),
+        Value::F64(0.0),
+    ];
+    values.sort_by(Value::total_cmp);
+    assert!(matches!(values[9], Value::F64(v) if v.is_nan()));
+    values.remove(9);
+    assert_eq!(
+        values,
+        [
+            Value::Bool(true),
+            Value::I64(-3),
+            Value::F64(-0.0),
+            Value::F64(0.0),
+            Value::F32(1.5),
+            Value::I32(2),
+            Value::U8(2),
+            Value::U128(u128::MAX),
+            Value::F64(f64::INFINITY),
+            Value::Char('a'),
+            Value::Str("a".into()),
+            Value::Str("b".into()),
+        ]
+    );
+
+    assert!(Value::I8(-1) < Value::U64(0));
+    assert!(Value::U64(u64::MAX) > Value::I64(i64::MAX));
+    assert!(Value::Str("z".into()) > Value::F64(f64::NAN));
+    assert_ne!(Value::I32(2), Value::U8(2));
+    assert!(Value::I32(2) < Value::U8(2));
+}
+
+#[test]
+fn test_float_eq() {
+    // Equality and partial ordering follow f64.
+    assert_ne!(Value::F64(f64::NAN), Value::F64(f64::NAN));
+    assert_eq!(
+        Value::F64(f64::NAN).partial_cmp(&Value::F64(f64::NAN)),
+        None
+    );
+    assert_eq!(Value::F64(f64::NAN).partial_cmp(&Value::I32(1)), None);
+    assert_eq!(Value::F64(-0.0), Value::F64(0.0));
+    assert_eq!(
+        Value::F64(-0.0).partial_cmp(&Value::F64(0.0)),
+        Some(Ordering::Equal)
+    );
+    assert!(Value::F32(0.0) < Value::F64(-0.0));
+
+    // The total ordering doesn't.
+    assert_eq!(
+        Value::F64(f64::NAN).total_cmp(&Value::F64(f64::NAN)),
+        Ordering::Equal
+    );
+    assert_eq!(Value::F64(-0.0).total_cmp(&Value::F64(0.0)), Ordering::Less);
+}
+
+#[test]
+fn test_from_option() {
+    assert_eq!(StaticValue::from(Some(5i32)), Value::I32(5));
+    assert_eq!(StaticValue::from(None::<i32>), Value::None);
+    assert_eq!(StaticValue::from(Some("text")), Value::Str("text".into()));
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_into_json() {
+    use serde_json::json;
+
+    fn to_json(value: Value) -> Result<serde_json::Value, String> {
+        serde_json::Value::try_from(&value).map_err(|err| err.to_string())
+    }
+
+    assert_eq!(to_json(Value::Bool(true)), Ok(json!(true)));
+    assert_eq!(to_json(Value::I8(-8)), Ok(json!(-8)));
+    assert_eq!(to_json(Value::U64(u64::MAX)), Ok(json!(u64::MAX)));
+    assert_eq!(to_json(Value::I128(-42)), Ok(json!(-42)));
+    assert_eq!(to_json(Value::F64(1.5)), Ok(json!(1.5)));
+    assert_eq!(to_json(Value::F32(f32::NAN)), Ok(json!(null)));
+    assert_eq!(to_json(Value::Char('c')), Ok(json!("c")));
+    assert_eq!(to_json(Value::Str("text".into())), Ok(json!("text")));
+    assert_eq!(
+        to_json(Value::Bytes((&[1, 2][..]).into())),
+        Ok(json!([1, 2]))
+    );
+    assert_eq!(to_json(Value::Unit), Ok(json!(null)));
+    assert_eq!(to_json(Value::None), Ok(json!(null)));
+    assert_eq!(
+        to_json(Value::UnitVariant {
+            name: "Enum",
+            variant_index: 0,
+            variant: "Variant"
+        }),
+        Ok(json!("Variant"))
+    );
+
+    assert!(to_json(Value::U128(u128::MAX)).is_err());
+    assert_eq!(
+        to_json(Value::Struct {
+            name: "Struct",
+            len: 2
+        }),
+        Err("Struct{2 fields} cannot be represented fully in Value".to_string())
+    );
+    assert!(to_json(Value::Some).is_err());
+    assert!(to_json(Value::Seq(Some(3))).is_err());
+}
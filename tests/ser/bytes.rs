@@ -0,0 +1,111 @@
+use std::cell::RefCell;
+
+use serde::Serialize;
+use serde_hooks::{ser, Path};
+
+#[derive(Serialize)]
+struct Payload {
+    #[serde(with = "serde_bytes")]
+    data: Vec<u8>,
+    seq: Vec<u8>,
+}
+
+#[test]
+fn test_on_bytes_hex() {
+    #[derive(Default)]
+    struct Hooks {
+        paths: RefCell<Vec<String>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_bytes<S: serde::Serializer>(&self, path: &Path, bytes: &mut ser::BytesScope<S>) {
+            self.paths.borrow_mut().push(path.borrow_str().clone());
+            let hex: String = bytes.bytes().iter().map(|b| format!("{b:02x}")).collect();
+            bytes.replace_with_str(&hex);
+        }
+    }
+
+    let payload = Payload {
+        data: vec![0xde, 0xad, 0xbe, 0xef],
+        seq: vec![1, 2],
+    };
+
+    let hooks = Hooks::default();
+    let json = serde_json::to_string(&ser::hook(&payload, &hooks)).unwrap();
+    assert_eq!(json, r#"{"data":"deadbeef","seq":[1,2]}"#);
+    assert_eq!(hooks.paths.into_inner(), ["data"]);
+}
+
+#[test]
+fn test_on_bytes_after_on_value() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, path: &Path, value: &mut ser::ValueScope<S>) {
+            if path.borrow_str().as_str() == "data" {
+                value.replace("replaced");
+            }
+        }
+
+        fn on_bytes<S: serde::Serializer>(&self, _path: &Path, _bytes: &mut ser::BytesScope<S>) {
+            unreachable!("bytes were replaced in on_value");
+        }
+    }
+
+    let payload = Payload {
+        data: vec![1],
+        seq: vec![],
+    };
+
+    let json = serde_json::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(json, r#"{"data":"replaced","seq":[]}"#);
+}
+
+#[test]
+fn test_bytes_encoding() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {}
+
+    let payload = Payload {
+        data: vec![0xde, 0xad, 0xbe, 0xef],
+        seq: vec![1, 2],
+    };
+
+    let options = ser::HookOptions::new().bytes_encoding(ser::BytesEncoding::Base64);
+    let json = serde_json::to_string(&ser::hook_with_options(&payload, &Hooks, options)).unwrap();
+    assert_eq!(json, r#"{"data":"3q2+7w==","seq":[1,2]}"#);
+
+    let options = ser::HookOptions::new().bytes_encoding(ser::BytesEncoding::Hex);
+    let json = serde_json::to_string(&ser::hook_with_options(&payload, &Hooks, options)).unwrap();
+    assert_eq!(json, r#"{"data":"deadbeef","seq":[1,2]}"#);
+
+    let options = ser::HookOptions::new().bytes_encoding(ser::BytesEncoding::Raw);
+    let json = serde_json::to_string(&ser::hook_with_options(&payload, &Hooks, options)).unwrap();
+    assert_eq!(json, r#"{"data":[222,173,190,239],"seq":[1,2]}"#);
+}
+
+#[test]
+fn test_bytes_encoding_after_on_bytes() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_bytes<S: serde::Serializer>(&self, _path: &Path, bytes: &mut ser::BytesScope<S>) {
+            if bytes.bytes().is_empty() {
+                bytes.replace_with_str("<empty>");
+            }
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Pair {
+        #[serde(with = "serde_bytes")]
+        a: Vec<u8>,
+        #[serde(with = "serde_bytes")]
+        b: Vec<u8>,
+    }
+
+    let payload = Pair {
+        a: vec![],
+        b: vec![0xff],
+    };
+    let options = ser::HookOptions::new().bytes_encoding(ser::BytesEncoding::Hex);
+    let json = serde_json::to_string(&ser::hook_with_options(&payload, &Hooks, options)).unwrap();
+    assert_eq!(json, r#"{"a":"<empty>","b":"ff"}"#);
+}
@@ -0,0 +1,83 @@
+use std::cell::RefCell;
+
+use serde::Serialize;
+use serde_hooks::{ser, Case, Path};
+
+#[derive(Serialize)]
+struct User {
+    user_name: &'static str,
+    password: &'static str,
+    api_token: &'static str,
+}
+
+impl User {
+    fn new() -> Self {
+        Self {
+            user_name: "alice",
+            password: "secret",
+            api_token: "xyz",
+        }
+    }
+}
+
+struct Redact;
+impl ser::Hooks for Redact {
+    fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+        st.replace_value("password", "***").skip_field("api_token");
+    }
+}
+
+struct Rename(Case);
+impl ser::Hooks for Rename {
+    fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+        st.rename_all_fields_case(self.0);
+    }
+}
+
+#[test]
+fn test_chain_redaction_and_renaming() {
+    let json =
+        serde_json::to_string(&ser::hook(&User::new(), &(Redact, Rename(Case::Camel)))).unwrap();
+    assert_eq!(json, r#"{"userName":"alice","password":"***"}"#);
+
+    let json =
+        serde_json::to_string(&ser::hook(&User::new(), &(Rename(Case::Camel), Redact))).unwrap();
+    assert_eq!(json, r#"{"userName":"alice","password":"***"}"#);
+}
+
+#[test]
+fn test_chain_last_rename_all_wins() {
+    let hooks = (Redact, Rename(Case::Camel), Rename(Case::Pascal));
+    let json = serde_json::to_string(&ser::hook(&User::new(), &hooks)).unwrap();
+    assert_eq!(json, r#"{"UserName":"alice","Password":"***"}"#);
+}
+
+#[test]
+fn test_chain_call_order() {
+    struct Record<'a>(&'a RefCell<Vec<String>>, &'static str);
+    impl ser::Hooks for Record<'_> {
+        fn on_start(&self, _start: &mut ser::StartScope) {
+            self.0.borrow_mut().push(format!("{} start", self.1));
+        }
+
+        fn on_struct(&self, path: &Path, _st: &mut ser::StructScope) {
+            self.0
+                .borrow_mut()
+                .push(format!("{} struct '{path}'", self.1));
+        }
+    }
+
+    let calls = RefCell::new(Vec::new());
+    let first = Record(&calls, "first");
+    let second = Record(&calls, "second");
+    ser::invoke_hooks(&User::new(), &(&first, &second)).unwrap();
+    assert_eq!(
+        calls.into_inner(),
+        [
+            "first start",
+            "second start",
+            "first struct ''",
+            "second struct ''"
+        ]
+    );
+}
@@ -0,0 +1,80 @@
+use serde::Serialize;
+use serde_hooks::{ser, Case, Path, Value};
+
+#[derive(Serialize)]
+struct User {
+    user_name: &'static str,
+    password: &'static str,
+    age: u8,
+}
+
+impl User {
+    fn new() -> Self {
+        Self {
+            user_name: "alice",
+            password: "secret",
+            age: 30,
+        }
+    }
+}
+
+struct Redact;
+impl ser::DynHooks for Redact {
+    fn on_value(&self, path: &Path, value: &mut ser::DynValueScope) {
+        if path == "password" {
+            assert_eq!(*value.value(), Value::Str("secret".into()));
+            value.replace("***");
+        }
+    }
+}
+
+struct CamelCase;
+impl ser::DynHooks for CamelCase {
+    fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+        st.rename_all_fields_case(Case::Camel);
+    }
+}
+
+fn select_hooks(redact: bool) -> ser::BoxedHooks {
+    if redact {
+        ser::BoxedHooks::new(Redact)
+    } else {
+        ser::BoxedHooks::new(CamelCase)
+    }
+}
+
+#[test]
+fn test_boxed_hooks_selected_at_runtime() {
+    let json = serde_json::to_string(&ser::hook(&User::new(), &select_hooks(true))).unwrap();
+    assert_eq!(json, r#"{"user_name":"alice","password":"***","age":30}"#);
+
+    let json = serde_json::to_string(&ser::hook(&User::new(), &select_hooks(false))).unwrap();
+    assert_eq!(json, r#"{"userName":"alice","password":"secret","age":30}"#);
+}
+
+#[test]
+fn test_boxed_hooks_from_registry() {
+    let registry: Vec<(&str, Box<dyn ser::DynHooks>)> =
+        vec![("redact", Box::new(Redact)), ("camel", Box::new(CamelCase))];
+
+    let (_, hooks) = registry.into_iter().nth(1).unwrap();
+    let hooks = ser::BoxedHooks::from(hooks);
+    let json = serde_json::to_string(&ser::hook(&User::new(), &hooks)).unwrap();
+    assert_eq!(json, r#"{"userName":"alice","password":"secret","age":30}"#);
+}
+
+#[test]
+fn test_dyn_value_scope() {
+    struct Hooks;
+    impl ser::DynHooks for Hooks {
+        fn on_value(&self, path: &Path, value: &mut ser::DynValueScope) {
+            if path == "age" {
+                value.fail_serialization("age is private");
+            }
+        }
+    }
+
+    let err =
+        serde_json::to_string(&ser::hook(&User::new(), &ser::BoxedHooks::new(Hooks))).unwrap_err();
+    assert_eq!(err.to_string(), "age is private");
+}
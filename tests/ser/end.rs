@@ -1,4 +1,8 @@
-use std::{cell::Cell, pin::Pin};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+};
 
 use serde::Serialize;
 use serde_hooks::ser;
@@ -53,6 +57,62 @@ fn test_take_static_strs() {
     assert_eq!(Pin::get_ref(static_strs[0].as_ref()), "FIELD");
 }
 
+#[test]
+fn test_runtime_string_allocations() {
+    #[derive(Serialize)]
+    struct Payload {
+        first_field: (),
+        second_field: (),
+        third_field: (),
+    }
+
+    struct Hooks {
+        use_case: bool,
+        allocations: Cell<Option<usize>>,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &serde_hooks::Path, st: &mut ser::StructScope) {
+            if self.use_case {
+                st.rename_all_fields_case("camelCase");
+            } else {
+                st.rename_field("first_field", "first")
+                    .rename_field("second_field", "second")
+                    .rename_field("third_field", "third");
+            }
+        }
+
+        fn on_end<Error: serde::ser::Error>(&self, end: &mut ser::EndScope<Error>) {
+            self.allocations.set(Some(end.runtime_string_allocations()));
+        }
+    }
+
+    let payload = Payload {
+        first_field: (),
+        second_field: (),
+        third_field: (),
+    };
+
+    let hooks = Hooks {
+        use_case: true,
+        allocations: Cell::new(None),
+    };
+    let json = serde_json::to_string(&ser::hook(&payload, &hooks)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"firstField":null,"secondField":null,"thirdField":null}"#
+    );
+    assert_eq!(hooks.allocations.get(), Some(3));
+
+    let hooks = Hooks {
+        use_case: false,
+        allocations: Cell::new(None),
+    };
+    let json = serde_json::to_string(&ser::hook(&payload, &hooks)).unwrap();
+    assert_eq!(json, r#"{"first":null,"second":null,"third":null}"#);
+    assert_eq!(hooks.allocations.get(), Some(0));
+}
+
 #[test]
 fn test_ok_result() {
     struct Hooks {
@@ -131,3 +191,325 @@ fn test_error_result_failed_from_hook() {
     assert_eq!(err.to_string(), hooks_err);
     assert!(hooks_err.contains("FAUX ERROR"));
 }
+
+#[test]
+fn test_annotations() {
+    #[derive(Serialize)]
+    struct Payload {
+        map: HashMap<&'static str, u32>,
+        seq: Vec<u32>,
+        value: u32,
+    }
+
+    struct Hooks {
+        annotations: RefCell<BTreeMap<String, BTreeMap<String, String>>>,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &serde_hooks::Path, st: &mut ser::StructScope) {
+            st.set_annotation("comment", "root struct");
+        }
+
+        fn on_map(&self, _path: &serde_hooks::Path, map: &mut ser::MapScope) {
+            map.set_annotation("comment", "a map")
+                .set_annotation("style", "flow");
+        }
+
+        fn on_seq(&self, _path: &serde_hooks::Path, seq: &mut ser::SeqScope) {
+            seq.set_annotation("comment", "first")
+                .set_annotation("comment", "second");
+        }
+
+        fn on_value<S: serde::Serializer>(
+            &self,
+            path: &serde_hooks::Path,
+            value: &mut ser::ValueScope<S>,
+        ) {
+            if *path.borrow_str() == "value" {
+                value.set_annotation("unit", "seconds");
+            }
+        }
+
+        fn on_end<Error: serde::ser::Error>(&self, end: &mut ser::EndScope<Error>) {
+            *self.annotations.borrow_mut() = end.annotations().clone();
+        }
+    }
+
+    let hooks = Hooks {
+        annotations: RefCell::new(BTreeMap::new()),
+    };
+
+    let payload = Payload {
+        map: HashMap::from([("a", 1)]),
+        seq: vec![1, 2],
+        value: 3,
+    };
+
+    let json = serde_json::to_string(&ser::hook(&payload, &hooks)).unwrap();
+    assert_eq!(json, r#"{"map":{"a":1},"seq":[1,2],"value":3}"#);
+
+    let annotations = hooks.annotations.into_inner();
+    let annotations: Vec<_> = annotations
+        .iter()
+        .flat_map(|(path, annotations)| {
+            annotations
+                .iter()
+                .map(move |(k, v)| (path.as_str(), k.as_str(), v.as_str()))
+        })
+        .collect();
+    assert_eq!(
+        annotations,
+        [
+            ("", "comment", "root struct"),
+            ("map", "comment", "a map"),
+            ("map", "style", "flow"),
+            ("seq", "comment", "second"),
+            ("value", "unit", "seconds"),
+        ]
+    );
+}
+
+#[test]
+fn test_schema_fingerprint() {
+    struct Hooks {
+        fingerprint: Cell<Option<u64>>,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_start(&self, start: &mut ser::StartScope) {
+            start.enable_schema_fingerprint();
+        }
+
+        fn on_end<Error: serde::ser::Error>(&self, end: &mut ser::EndScope<Error>) {
+            self.fingerprint.set(end.schema_fingerprint());
+        }
+    }
+
+    fn fingerprint(value: &impl Serialize) -> u64 {
+        let hooks = Hooks {
+            fingerprint: Cell::new(None),
+        };
+        serde_json::to_string(&ser::hook(value, &hooks)).unwrap();
+        hooks.fingerprint.get().unwrap()
+    }
+
+    #[derive(Serialize)]
+    struct Payload {
+        id: u32,
+        tags: Vec<&'static str>,
+        scores: HashMap<&'static str, f64>,
+    }
+
+    #[derive(Serialize)]
+    struct RenamedField {
+        id: u32,
+        labels: Vec<&'static str>,
+        scores: HashMap<&'static str, f64>,
+    }
+
+    #[derive(Serialize)]
+    struct ChangedType {
+        id: i64,
+        tags: Vec<&'static str>,
+        scores: HashMap<&'static str, f64>,
+    }
+
+    let payload = fingerprint(&Payload {
+        id: 1,
+        tags: vec!["a", "b"],
+        scores: HashMap::from([("x", 1.0), ("y", 2.0)]),
+    });
+
+    // same shape, different values
+    assert_eq!(
+        payload,
+        fingerprint(&Payload {
+            id: 2,
+            tags: vec!["c", "d", "e"],
+            scores: HashMap::from([("z", 3.0), ("w", 4.0), ("v", 5.0)]),
+        })
+    );
+
+    // shape changes
+    assert_ne!(
+        payload,
+        fingerprint(&RenamedField {
+            id: 1,
+            labels: vec!["a", "b"],
+            scores: HashMap::from([("x", 1.0), ("y", 2.0)]),
+        })
+    );
+    assert_ne!(
+        payload,
+        fingerprint(&ChangedType {
+            id: 1,
+            tags: vec!["a", "b"],
+            scores: HashMap::from([("x", 1.0), ("y", 2.0)]),
+        })
+    );
+}
+
+#[test]
+fn test_schema_fingerprint_disabled() {
+    struct Hooks;
+
+    impl ser::Hooks for Hooks {
+        fn on_end<Error: serde::ser::Error>(&self, end: &mut ser::EndScope<Error>) {
+            assert_eq!(end.schema_fingerprint(), None);
+        }
+    }
+
+    serde_json::to_string(&ser::hook(&vec![1, 2], &Hooks)).unwrap();
+}
+
+#[test]
+fn test_statistics() {
+    #[derive(Serialize)]
+    struct Payload {
+        number: u32,
+        seq: Vec<u32>,
+        map: BTreeMap<&'static str, u32>,
+        nested: Nested,
+    }
+
+    #[derive(Serialize)]
+    struct Nested {
+        field: (),
+    }
+
+    #[derive(Default)]
+    struct Hooks {
+        statistics: RefCell<Vec<usize>>,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, path: &serde_hooks::Path, st: &mut ser::StructScope) {
+            if path.is_root() {
+                st.rename_field("number", "num".to_string());
+            }
+        }
+
+        fn on_end<Error: serde::ser::Error>(&self, end: &mut ser::EndScope<Error>) {
+            self.statistics.replace(vec![
+                end.values_serialized(),
+                end.structs_serialized(),
+                end.maps_serialized(),
+                end.runtime_string_allocations(),
+                end.static_strings_allocated(),
+            ]);
+        }
+    }
+
+    let payload = Payload {
+        number: 1,
+        seq: vec![2, 3],
+        map: [("a", 4), ("b", 5)].into(),
+        nested: Nested { field: () },
+    };
+
+    let hooks = Hooks::default();
+    serde_json::to_string(&ser::hook(&payload, &hooks)).unwrap();
+    // values: payload, number, seq, seq[0], seq[1], map, map["a"], map["b"], nested, nested.field
+    assert_eq!(hooks.statistics.into_inner(), [10, 2, 1, 1, 3]);
+}
+
+#[test]
+fn test_static_str_capacity() {
+    #[derive(Serialize)]
+    struct Payload {
+        first_field: u32,
+        second_field: u32,
+        third_field: u32,
+    }
+
+    #[derive(Default)]
+    struct Hooks {
+        allocations: Cell<usize>,
+        static_strs: Cell<Vec<Pin<Box<str>>>>,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &serde_hooks::Path, st: &mut ser::StructScope) {
+            st.rename_all_fields_case("SCREAMING_SNAKE_CASE");
+        }
+
+        fn on_end<Error: serde::ser::Error>(&self, end: &mut ser::EndScope<Error>) {
+            self.allocations.set(end.runtime_string_allocations());
+            unsafe { self.static_strs.set(end.take_static_strs()) }
+        }
+    }
+
+    let payload: Vec<_> = (0..100)
+        .map(|i| Payload {
+            first_field: i,
+            second_field: i,
+            third_field: i,
+        })
+        .collect();
+
+    let hooks = Hooks::default();
+    let json = serde_json::to_string(&ser::hook_with_options(
+        &payload,
+        &hooks,
+        ser::HookOptions::new().static_str_capacity(64),
+    ))
+    .unwrap();
+    assert!(json.starts_with(r#"[{"FIRST_FIELD":0,"SECOND_FIELD":0,"THIRD_FIELD":0}"#));
+
+    // The same names in every element are only allocated once.
+    assert_eq!(hooks.allocations.get(), 3);
+    let static_strs = hooks.static_strs.take();
+    assert_eq!(static_strs.len(), 3);
+    assert!(static_strs.capacity() >= 64);
+}
+
+#[test]
+fn test_ignored_errors() {
+    #[derive(Serialize)]
+    struct Payload {
+        field: u32,
+        nested: Nested,
+    }
+
+    #[derive(Serialize)]
+    struct Nested {
+        field: u32,
+    }
+
+    #[derive(Default)]
+    struct Hooks {
+        ignored: RefCell<Vec<String>>,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &serde_hooks::Path, st: &mut ser::StructScope) {
+            st.rename_field("missing", "other");
+        }
+
+        fn on_scope_error(&self, _path: &serde_hooks::Path, err: &mut ser::ErrorScope) {
+            err.ignore();
+        }
+
+        fn on_end<Error: serde::ser::Error>(&self, end: &mut ser::EndScope<Error>) {
+            self.ignored.borrow_mut().extend(
+                end.ignored_errors()
+                    .iter()
+                    .map(|(path, err)| format!("{path}: {err}")),
+            );
+        }
+    }
+
+    let payload = Payload {
+        field: 1,
+        nested: Nested { field: 2 },
+    };
+    let hooks = Hooks::default();
+    serde_json::to_string(&ser::hook(&payload, &hooks)).unwrap();
+    assert_eq!(
+        hooks.ignored.into_inner(),
+        [
+            "nested: field \"missing\" not found",
+            ": field \"missing\" not found"
+        ]
+    );
+}
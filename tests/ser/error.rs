@@ -0,0 +1,226 @@
+use std::cell::RefCell;
+
+use serde::Serialize;
+use serde_hooks::{ser, Path};
+
+#[test]
+fn test_error_codes() {
+    // Exhaustive on purpose, so that new variants can't be added without a code.
+    fn expected_code(err: &ser::HooksError) -> &'static str {
+        match err {
+            ser::HooksError::CannotAddEntryByIndex(_) => "cannot_add_entry_by_index",
+            ser::HooksError::KeyNotFound(_) => "key_not_found",
+            ser::HooksError::FieldNotFound(_) => "field_not_found",
+            ser::HooksError::FieldIndexNotFound(_) => "field_index_not_found",
+            ser::HooksError::ValueNotSerializable(_) => "value_not_serializable",
+            ser::HooksError::IndexNotFound(_) => "index_not_found",
+            ser::HooksError::CannotFlattenUnsupportedDataType(_) => {
+                "cannot_flatten_unsupported_data_type"
+            }
+            ser::HooksError::CannotSkipValue => "cannot_skip_value",
+            ser::HooksError::DuplicateKey(_) => "duplicate_key",
+            ser::HooksError::MaxDepthExceeded(_) => "max_depth_exceeded",
+        }
+    }
+
+    let errors = [
+        ser::HooksError::CannotAddEntryByIndex(0),
+        ser::HooksError::KeyNotFound(0usize.into()),
+        ser::HooksError::FieldNotFound("field".into()),
+        ser::HooksError::FieldIndexNotFound(0),
+        ser::HooksError::ValueNotSerializable("value".into()),
+        ser::HooksError::IndexNotFound(0),
+        ser::HooksError::CannotFlattenUnsupportedDataType("bool"),
+        ser::HooksError::CannotSkipValue,
+        ser::HooksError::DuplicateKey(0usize.into()),
+        ser::HooksError::MaxDepthExceeded(0),
+    ];
+
+    for err in &errors {
+        assert_eq!(err.code(), expected_code(err), "{err:?}");
+    }
+
+    let mut codes: Vec<_> = errors.iter().map(|err| err.code()).collect();
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), errors.len(), "codes must be unique");
+}
+
+#[test]
+fn test_error_code_in_scope_error() {
+    #[derive(Serialize)]
+    struct Payload {
+        field: Vec<u32>,
+    }
+
+    struct Hooks {
+        codes: RefCell<Vec<&'static str>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.skip_field("missing");
+        }
+
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            seq.skip_element(5);
+        }
+
+        fn on_scope_error(&self, _path: &Path, err: &mut ser::ErrorScope) {
+            self.codes.borrow_mut().push(err.error().code());
+            err.ignore();
+        }
+    }
+
+    let hooks = Hooks {
+        codes: RefCell::new(Vec::new()),
+    };
+    serde_json::to_string(&ser::hook(&Payload { field: vec![0] }, &hooks)).unwrap();
+    assert_eq!(
+        hooks.codes.into_inner(),
+        ["index_not_found", "field_not_found"]
+    );
+}
+
+#[test]
+fn test_replace_error() {
+    #[derive(Serialize)]
+    struct Payload {
+        field: u32,
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.skip_field("missing");
+        }
+
+        fn on_scope_error(&self, path: &Path, err: &mut ser::ErrorScope) {
+            if let ser::HooksError::FieldNotFound(field) = err.error() {
+                let message = format!("'{field}' is required at '{path}'");
+                err.ignore().replace_error(message);
+            }
+        }
+    }
+
+    let err = serde_json::to_string(&ser::hook(&Payload { field: 0 }, &Hooks)).unwrap_err();
+    assert_eq!(err.to_string(), "'missing' is required at ''");
+}
+
+#[test]
+fn test_suppress_and_continue() {
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    struct User {
+        name: &'static str,
+        password: &'static str,
+        tags: BTreeMap<&'static str, u8>,
+    }
+
+    struct Hooks {
+        errors: RefCell<Vec<String>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            // misspelled field name
+            st.retain_field("nmae");
+        }
+
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.skip_entry("missing").skip_entry("also_missing");
+        }
+
+        fn on_scope_error(&self, path: &Path, err: &mut ser::ErrorScope) {
+            self.errors
+                .borrow_mut()
+                .push(format!("{path}: {}", err.error()));
+            err.suppress_and_continue();
+        }
+    }
+
+    let users = [
+        User {
+            name: "a",
+            password: "secret",
+            tags: BTreeMap::new(),
+        },
+        User {
+            name: "b",
+            password: "secret",
+            tags: BTreeMap::new(),
+        },
+    ];
+
+    let hooks = Hooks {
+        errors: RefCell::new(Vec::new()),
+    };
+    let json = serde_json::to_string(&ser::hook(&users, &hooks)).unwrap();
+    assert_eq!(json, "[{},{}]");
+    assert_eq!(
+        hooks.errors.into_inner(),
+        [
+            "[0]: field \"nmae\" not found",
+            "[1]: field \"nmae\" not found"
+        ]
+    );
+
+    let mut tags = BTreeMap::new();
+    tags.insert("x", 1);
+    let hooks = Hooks {
+        errors: RefCell::new(Vec::new()),
+    };
+    let json = serde_json::to_string(&ser::hook(&tags, &hooks)).unwrap();
+    assert_eq!(json, r#"{"x":1}"#);
+    assert_eq!(hooks.errors.into_inner(), [": key [\"missing\"] not found"]);
+}
+
+#[test]
+fn test_all_leftover_actions_reported() {
+    #[derive(Serialize)]
+    struct Payload {
+        field: u8,
+    }
+
+    struct Hooks {
+        errors: RefCell<Vec<String>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.retain_field("field")
+                .retain_field("bogus1")
+                .retain_field("bogus2");
+        }
+
+        fn on_scope_error(&self, path: &Path, err: &mut ser::ErrorScope) {
+            self.errors
+                .borrow_mut()
+                .push(format!("{path}: {}", err.error()));
+            err.ignore();
+        }
+    }
+
+    let hooks = Hooks {
+        errors: RefCell::new(Vec::new()),
+    };
+    let json = serde_json::to_string(&ser::hook(&Payload { field: 1 }, &hooks)).unwrap();
+    assert_eq!(json, r#"{"field":1}"#);
+    assert_eq!(
+        hooks.errors.take(),
+        [
+            ": field \"bogus1\" not found",
+            ": field \"bogus2\" not found"
+        ]
+    );
+
+    let err = serde_json::to_string(&ser::hook_with_options(
+        &Payload { field: 1 },
+        &hooks,
+        ser::HookOptions::new().strict(),
+    ))
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error at path '': field \"bogus1\" not found"
+    );
+    assert_eq!(hooks.errors.take(), [": field \"bogus1\" not found"]);
+}
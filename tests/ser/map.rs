@@ -1,4 +1,7 @@
-use std::collections::BTreeMap;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+};
 
 use serde_hooks::{ser, Path};
 
@@ -122,6 +125,35 @@ fn test_rename_all_fields_case() {
     );
 }
 
+#[test]
+fn test_rename_all_keys_case() {
+    #[derive(serde::Serialize, PartialEq, Eq, PartialOrd, Ord)]
+    #[serde(untagged)]
+    enum Key {
+        Str(&'static str),
+        Int(u32),
+    }
+
+    let payload: BTreeMap<Key, u32> = [
+        (Key::Str("first_entry"), 1),
+        (Key::Str("second_entry"), 2),
+        (Key::Int(3), 3),
+    ]
+    .into();
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.rename_all_keys_case("camelCase")
+                .rename_key_case(2usize, "UPPERCASE")
+                .rename_key_case("second_entry", "PascalCase");
+        }
+    }
+
+    let with_hooks = ron::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(with_hooks, "{\"firstEntry\":1,\"SecondEntry\":2,3:3}");
+}
+
 #[test]
 fn test_insert_entry() {
     let payload: BTreeMap<u32, u32> = [(1, 1), (2, 2), (3, 3), (4, 4)].into();
@@ -145,3 +177,528 @@ fn test_insert_entry() {
         "{\"a\":'a',1:1,\"b\":'b',2:2,\"c\":'c',\"d\":'d',3:3,\"e\":'e',\"f\":'f',4:4,\"g\":'g'}"
     );
 }
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn test_normalize_string_keys() {
+    let payload: BTreeMap<&'static str, u32> = [("caf\u{e9}", 1), ("cafe\u{301}s", 2)].into();
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.normalize_string_keys(ser::UnicodeForm::Nfc);
+        }
+    }
+
+    let with_hooks = ron::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(with_hooks, "{\"caf\u{e9}s\":2,\"caf\u{e9}\":1}");
+}
+
+#[test]
+fn test_split_key_value_calls() {
+    /// Map that feeds keys and values with separate `serialize_key`/`serialize_value` calls.
+    struct SplitMap(Vec<(&'static str, u32)>);
+
+    impl serde::Serialize for SplitMap {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::SerializeMap;
+            let mut map = serializer.serialize_map(Some(self.0.len()))?;
+            for (k, v) in &self.0 {
+                map.serialize_key(k)?;
+                map.serialize_value(v)?;
+            }
+            map.end()
+        }
+    }
+
+    struct Hooks {
+        value_paths: RefCell<Vec<String>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.skip_entry("first")
+                .rename_key("second", "renamed")
+                .replace_value("third", "replaced")
+                .insert_entry(
+                    "inserted",
+                    0u32,
+                    ser::MapInsertLocation::After(1usize.into()),
+                );
+        }
+
+        fn on_value<S: serde::Serializer>(&self, path: &Path, _value: &mut ser::ValueScope<S>) {
+            self.value_paths
+                .borrow_mut()
+                .push(path.borrow_str().clone());
+        }
+    }
+
+    let hooks = Hooks {
+        value_paths: RefCell::new(Vec::new()),
+    };
+
+    let payload = SplitMap(vec![("first", 1), ("second", 2), ("third", 3)]);
+    let with_hooks = ron::to_string(&ser::hook(&payload, &hooks)).unwrap();
+    assert_eq!(
+        with_hooks,
+        "{\"renamed\":2,\"inserted\":0,\"third\":\"replaced\"}"
+    );
+    assert_eq!(
+        hooks.value_paths.into_inner(),
+        ["", "[\"renamed\"]", "[\"inserted\"]"]
+    );
+}
+
+#[test]
+fn test_select_key_case_insensitive() {
+    let payload: BTreeMap<&'static str, u32> = [("first_entry", 1), ("second_entry", 2)].into();
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.replace_value(ser::MapKeySelector::key_ci("First_Entry"), 10u32)
+                .skip_entry(ser::MapKeySelector::key_ci("MISSING"));
+        }
+
+        fn on_scope_error(&self, _path: &Path, err: &mut ser::ErrorScope) {
+            assert_eq!(
+                err.error().to_string(),
+                "key [\"MISSING\" (case-insensitive)] not found"
+            );
+            err.ignore();
+        }
+    }
+
+    let with_hooks = ron::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(with_hooks, "{\"first_entry\":10,\"second_entry\":2}");
+}
+
+#[test]
+fn test_sort_entries() {
+    let payload: HashMap<String, u32> = (0..20).map(|i| (format!("key_{i:02}"), i)).collect();
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.sort_entries();
+        }
+    }
+
+    let expected = format!(
+        "{{{}}}",
+        (0..20)
+            .map(|i| format!("\"key_{i:02}\":{i}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let with_hooks = ron::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(with_hooks, expected);
+}
+
+#[test]
+fn test_sort_entries_mixed_keys() {
+    #[derive(serde::Serialize, PartialEq, Eq, Hash)]
+    #[serde(untagged)]
+    enum Key {
+        Str(&'static str),
+        Signed(i64),
+        Unsigned(u8),
+        Bool(bool),
+    }
+
+    let payload: HashMap<Key, u32> = [
+        (Key::Str("b"), 1),
+        (Key::Signed(10), 2),
+        (Key::Unsigned(2), 3),
+        (Key::Signed(-1), 4),
+        (Key::Str("a"), 5),
+        (Key::Bool(true), 6),
+    ]
+    .into();
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.insert_entry("c", 7u32, ser::MapInsertLocation::End)
+                .sort_entries();
+        }
+    }
+
+    let with_hooks = ron::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(with_hooks, "{true:6,-1:4,2:3,10:2,\"a\":5,\"b\":1,\"c\":7}");
+}
+
+#[test]
+fn test_sort_entries_by() {
+    let payload: HashMap<&'static str, u32> = [("a", 1), ("b", 2), ("c", 3)].into();
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.sort_entries_by(|a, b| b.to_string().cmp(&a.to_string()));
+        }
+    }
+
+    let with_hooks = ron::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(with_hooks, "{\"c\":3,\"b\":2,\"a\":1}");
+}
+
+#[test]
+fn test_insert_entry_checked() {
+    let payload: BTreeMap<&'static str, u32> = [("first", 1), ("second", 2)].into();
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.insert_entry_checked("third", 3u32, ser::MapInsertLocation::End)
+                .insert_entry_checked(
+                    "zeroth",
+                    0u32,
+                    ser::MapInsertLocation::Before("first".into()),
+                );
+        }
+    }
+
+    let with_hooks = ron::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(
+        with_hooks,
+        "{\"zeroth\":0,\"first\":1,\"second\":2,\"third\":3}"
+    );
+}
+
+#[test]
+fn test_insert_entry_checked_duplicate() {
+    let payload: BTreeMap<&'static str, u32> = [("first", 1), ("second", 2)].into();
+
+    struct Hooks {
+        insert_first: bool,
+        ignore: bool,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            let location = if self.insert_first {
+                ser::MapInsertLocation::Before(0usize.into())
+            } else {
+                ser::MapInsertLocation::End
+            };
+            map.insert_entry_checked("second", 3u32, location);
+        }
+
+        fn on_scope_error(&self, _path: &Path, err: &mut ser::ErrorScope) {
+            assert_eq!(err.error().code(), "duplicate_key");
+            if self.ignore {
+                err.ignore();
+            }
+        }
+    }
+
+    // inserted after the existing key
+    let err = ron::to_string(&ser::hook(
+        &payload,
+        &Hooks {
+            insert_first: false,
+            ignore: false,
+        },
+    ))
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error at path '': duplicate key [\"second\"]"
+    );
+
+    // inserted before the existing key
+    let err = ron::to_string(&ser::hook(
+        &payload,
+        &Hooks {
+            insert_first: true,
+            ignore: false,
+        },
+    ))
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error at path '': duplicate key [\"second\"]"
+    );
+
+    // ignored error still inserts the entry
+    let with_hooks = ron::to_string(&ser::hook(
+        &payload,
+        &Hooks {
+            insert_first: false,
+            ignore: true,
+        },
+    ))
+    .unwrap();
+    assert_eq!(with_hooks, "{\"first\":1,\"second\":2,\"second\":3}");
+
+    // unchecked insert does not check
+    struct UncheckedHooks;
+    impl ser::Hooks for UncheckedHooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.insert_entry("second", 3u32, ser::MapInsertLocation::End);
+        }
+    }
+    let with_hooks = ron::to_string(&ser::hook(&payload, &UncheckedHooks)).unwrap();
+    assert_eq!(with_hooks, "{\"first\":1,\"second\":2,\"second\":3}");
+}
+
+#[test]
+fn test_on_map_entry_end() {
+    let payload: BTreeMap<&'static str, u32> =
+        [("first", 1), ("second", 2), ("third", 3), ("fourth", 4)].into();
+
+    #[derive(Default)]
+    struct Hooks {
+        ended: RefCell<Vec<(String, String, usize)>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.retain_entry("first")
+                .retain_entry("second")
+                .retain_entry("third")
+                .rename_key("second", "2nd")
+                .insert_entry("fifth", 5u32, ser::MapInsertLocation::End);
+        }
+
+        fn on_map_entry_end(&self, path: &Path, key: &serde_hooks::Value, index: usize) {
+            self.ended
+                .borrow_mut()
+                .push((path.borrow_str().clone(), key.to_string(), index));
+        }
+    }
+
+    let hooks = Hooks::default();
+    let with_hooks = ron::to_string(&ser::hook(&payload, &hooks)).unwrap();
+    assert_eq!(
+        with_hooks,
+        "{\"first\":1,\"2nd\":2,\"third\":3,\"fifth\":5}"
+    );
+    assert_eq!(
+        hooks.ended.into_inner(),
+        [
+            ("[\"first\"]".to_string(), "\"first\"".to_string(), 0),
+            ("[\"2nd\"]".to_string(), "\"2nd\"".to_string(), 2),
+            ("[\"third\"]".to_string(), "\"third\"".to_string(), 3),
+        ]
+    );
+
+    // Entries skipped in on_value are not reported either.
+    #[derive(Default)]
+    struct SkippingHooks {
+        ended: RefCell<Vec<String>>,
+    }
+    impl ser::Hooks for SkippingHooks {
+        fn on_value<S: serde::Serializer>(&self, path: &Path, value: &mut ser::ValueScope<S>) {
+            if path == "[\"b\"]" {
+                value.skip();
+            }
+        }
+
+        fn on_map_entry_end(&self, _path: &Path, key: &serde_hooks::Value, _index: usize) {
+            self.ended.borrow_mut().push(key.to_string());
+        }
+    }
+
+    let payload: BTreeMap<&'static str, u32> = [("a", 1), ("b", 2), ("c", 3)].into();
+    let hooks = SkippingHooks::default();
+    let options = ser::HookOptions::new().allow_skipping_values();
+    let json = serde_json::to_string(&ser::hook_with_options(&payload, &hooks, options)).unwrap();
+    assert_eq!(json, r#"{"a":1,"c":3}"#);
+    assert_eq!(hooks.ended.into_inner(), ["\"a\"", "\"c\""]);
+}
+
+#[test]
+fn test_skip_entries_matching() {
+    let payload: BTreeMap<&'static str, u32> = [
+        ("_internal_id", 1),
+        ("_internal_rev", 2),
+        ("name", 3),
+        ("value", 4),
+    ]
+    .into();
+
+    struct Hooks {
+        retain: bool,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.skip_entries_matching(
+                |key| matches!(key, serde_hooks::Value::Str(s) if s.starts_with("_internal")),
+            );
+            if self.retain {
+                map.retain_entry("_internal_id").retain_entry("name");
+            }
+        }
+    }
+
+    let with_hooks = ron::to_string(&ser::hook(&payload, &Hooks { retain: false })).unwrap();
+    assert_eq!(with_hooks, "{\"name\":3,\"value\":4}");
+
+    let with_hooks = ron::to_string(&ser::hook(&payload, &Hooks { retain: true })).unwrap();
+    assert_eq!(with_hooks, "{\"_internal_id\":1,\"name\":3}");
+}
+
+#[test]
+fn test_replace_values_matching() {
+    let payload: BTreeMap<&'static str, &'static str> = [
+        ("access_token", "secret1"),
+        ("name", "name"),
+        ("refresh_token", "secret2"),
+    ]
+    .into();
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.replace_values_matching(
+                |key| matches!(key, serde_hooks::Value::Str(s) if s.ends_with("_token")),
+                "***",
+            )
+            .replace_value("refresh_token", "[redacted]");
+        }
+    }
+
+    let with_hooks = ron::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(
+        with_hooks,
+        "{\"access_token\":\"***\",\"name\":\"name\",\"refresh_token\":\"[redacted]\"}"
+    );
+}
+
+#[test]
+fn test_replace_values_matching_not_serializable() {
+    let payload: BTreeMap<&'static str, u32> = [("key", 1)].into();
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.replace_values_matching(|_| true, serde_hooks::Value::Map(Some(1)));
+        }
+    }
+
+    let err = ron::to_string(&ser::hook(&payload, &Hooks)).unwrap_err();
+    assert!(
+        err.to_string().contains("value is not serializable"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_set_len_hint() {
+    let payload: BTreeMap<u32, u32> = [(1, 10), (2, 20), (3, 30)].into();
+
+    struct Hooks {
+        len_hint: Option<usize>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.skip_entry(2u32);
+            if let Some(len) = self.len_hint {
+                map.set_len_hint(len);
+            }
+        }
+    }
+
+    // bincode requires the map length to be known upfront
+    bincode::serialize(&ser::hook(&payload, &Hooks { len_hint: None })).unwrap_err();
+
+    let bytes = bincode::serialize(&ser::hook(&payload, &Hooks { len_hint: Some(2) })).unwrap();
+    let map: BTreeMap<u32, u32> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(map, [(1, 10), (3, 30)].into());
+}
+
+#[test]
+fn test_capture_byte_keys() {
+    use serde_bytes::ByteBuf;
+
+    let key = |k: &[u8]| ByteBuf::from(k.to_vec());
+    let payload: BTreeMap<ByteBuf, u32> = [(key(b"a"), 1), (key(b"b"), 2), (key(b"c"), 3)].into();
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.skip_entry(ser::MapKeySelector::ByValue(serde_hooks::Value::Bytes(
+                b"b".to_vec().into(),
+            )));
+        }
+    }
+
+    let options = ser::HookOptions::new().capture_byte_keys();
+    let with_hooks = ron::to_string(&ser::hook_with_options(&payload, &Hooks, options)).unwrap();
+    let expected: BTreeMap<ByteBuf, u32> = [(key(b"a"), 1), (key(b"c"), 3)].into();
+    assert_eq!(with_hooks, ron::to_string(&expected).unwrap());
+
+    // Byte keys are not captured by default, so the entry is not found
+    let err = ron::to_string(&ser::hook(&payload, &Hooks)).unwrap_err();
+    assert!(err.to_string().contains("not found"), "{err}");
+}
+
+#[test]
+fn test_select_128_bit_keys() {
+    let payload: BTreeMap<i128, u128> = [(-170, 1), (170, 2), (i128::MAX, 3)].into();
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.skip_entry(170i128).replace_value(i128::MAX, u128::MAX);
+        }
+    }
+
+    let with_hooks = serde_json::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(
+        with_hooks,
+        format!(r#"{{"-170":1,"{}":{}}}"#, i128::MAX, u128::MAX)
+    );
+}
+
+#[test]
+fn test_clear() {
+    let payload: BTreeMap<&str, u32> = [("a", 1), ("b", 2), ("c", 3)].into();
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.clear()
+                .insert_entry("x", 10u32, ser::MapInsertLocation::After("b".into()))
+                .insert_entry("y", 20u32, ser::MapInsertLocation::End);
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(json, r#"{"x":10,"y":20}"#);
+
+    let empty: BTreeMap<&str, u32> = BTreeMap::new();
+    struct EmptyHooks;
+    impl ser::Hooks for EmptyHooks {
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            map.clear();
+        }
+    }
+    let json = serde_json::to_string(&ser::hook(&payload, &EmptyHooks)).unwrap();
+    assert_eq!(json, "{}");
+    let json = serde_json::to_string(&ser::hook(&empty, &EmptyHooks)).unwrap();
+    assert_eq!(json, "{}");
+}
+
+#[test]
+fn test_failing_key() {
+    struct Failing;
+    impl serde::Serialize for Failing {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("cannot serialize"))
+        }
+    }
+
+    struct Payload;
+    impl serde::Serialize for Payload {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_map([(Failing, 1)])
+        }
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {}
+
+    let err = serde_json::to_string(&ser::hook(&Payload, &Hooks)).unwrap_err();
+    assert_eq!(err.to_string(), "cannot serialize");
+}
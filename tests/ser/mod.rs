@@ -1,8 +1,19 @@
+mod bytes;
+mod chain;
+mod dynamic;
 mod end;
+mod error;
 mod map;
+mod newtype;
+mod no_hooks;
+mod option;
+mod options;
+mod recording;
+mod scoped;
 mod seq;
 mod start;
 mod r#struct;
 mod tuple;
+mod unit_struct;
 mod value;
 mod variant;
@@ -0,0 +1,77 @@
+use std::cell::RefCell;
+
+use serde::Serialize;
+use serde_hooks::{ser, Path};
+
+#[derive(Serialize)]
+struct Millimeters(u8);
+
+#[derive(Serialize)]
+struct Payload {
+    width: Millimeters,
+    height: Millimeters,
+}
+
+#[test]
+fn test_unwrap() {
+    struct Hooks {
+        names: RefCell<Vec<(String, &'static str)>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_newtype_struct(&self, path: &Path, nt: &mut ser::NewtypeStructScope) {
+            self.names
+                .borrow_mut()
+                .push((path.to_string(), nt.struct_name()));
+            if path == "width" {
+                nt.unwrap();
+            }
+        }
+    }
+
+    let payload = Payload {
+        width: Millimeters(10),
+        height: Millimeters(20),
+    };
+
+    let ron = ron::to_string(&payload).unwrap();
+    assert_eq!(ron, "(width:(10),height:(20))");
+
+    let hooks = Hooks {
+        names: RefCell::new(Vec::new()),
+    };
+    let ron = ron::to_string(&ser::hook(&payload, &hooks)).unwrap();
+    assert_eq!(ron, "(width:10,height:(20))");
+    assert_eq!(
+        hooks.names.take(),
+        [
+            ("width".to_string(), "Millimeters"),
+            ("height".to_string(), "Millimeters")
+        ]
+    );
+}
+
+#[test]
+fn test_rename() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_newtype_struct(&self, _path: &Path, nt: &mut ser::NewtypeStructScope) {
+            nt.rename("Mm");
+        }
+    }
+
+    let payload = Payload {
+        width: Millimeters(10),
+        height: Millimeters(20),
+    };
+
+    let ron = ron::ser::to_string_pretty(
+        &ser::hook(&payload, &Hooks),
+        ron::ser::PrettyConfig::default()
+            .struct_names(true)
+            .compact_arrays(true)
+            .new_line(String::new())
+            .indentor(String::new()),
+    )
+    .unwrap();
+    assert_eq!(ron, "Payload(width: Mm(10),height: Mm(20),)");
+}
@@ -0,0 +1,73 @@
+use std::cell::RefCell;
+
+use serde::{Serialize, Serializer};
+use serde_hooks::ser;
+
+/// Records the type name of the serializer it's serialized with.
+struct Probe<'a>(&'a RefCell<Vec<&'static str>>);
+
+impl Serialize for Probe<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.borrow_mut().push(std::any::type_name::<S>());
+        serializer.serialize_u8(42)
+    }
+}
+
+fn is_bypassed(hooks: &impl ser::Hooks, options: ser::HookOptions) -> bool {
+    let names = RefCell::new(Vec::new());
+    let json =
+        serde_json::to_string(&ser::hook_with_options(&Probe(&names), hooks, options)).unwrap();
+    assert_eq!(json, "42");
+    names.into_inner()[0].starts_with("&mut serde_json::ser::Serializer")
+}
+
+#[test]
+fn test_no_hooks_bypass() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {}
+
+    assert!(is_bypassed(&ser::NoHooks, ser::HookOptions::new()));
+    assert!(!is_bypassed(&Hooks, ser::HookOptions::new()));
+
+    // Combinators are inactive only if all the hooks are.
+    assert!(is_bypassed(
+        &(ser::NoHooks, &ser::NoHooks),
+        ser::HookOptions::new()
+    ));
+    assert!(is_bypassed(
+        &ser::scoped("a", ser::NoHooks),
+        ser::HookOptions::new()
+    ));
+    assert!(!is_bypassed(
+        &(ser::NoHooks, Hooks),
+        ser::HookOptions::new()
+    ));
+}
+
+#[test]
+fn test_no_hooks_with_options() {
+    assert!(is_bypassed(
+        &ser::NoHooks,
+        ser::HookOptions::new().allow_skipping_values().strict()
+    ));
+    assert!(!is_bypassed(
+        &ser::NoHooks,
+        ser::HookOptions::new().bytes_encoding(ser::BytesEncoding::Hex)
+    ));
+    assert!(!is_bypassed(
+        &ser::NoHooks,
+        ser::HookOptions::new().float_policy(ser::FloatPolicy::NullifyNonFinite)
+    ));
+    assert!(!is_bypassed(
+        &ser::NoHooks,
+        ser::HookOptions::new().max_depth(3)
+    ));
+
+    let json = serde_json::to_string(&ser::hook_with_options(
+        &[f64::NAN],
+        &ser::NoHooks,
+        ser::HookOptions::new().float_policy(ser::FloatPolicy::NullifyNonFinite),
+    ))
+    .unwrap();
+    assert_eq!(json, "[null]");
+}
@@ -0,0 +1,98 @@
+use std::cell::RefCell;
+
+use serde::Serialize;
+use serde_hooks::{ser, Path};
+
+#[derive(Serialize)]
+struct Payload {
+    name: Option<&'static str>,
+    age: Option<u32>,
+    tags: Vec<Option<u32>>,
+}
+
+#[test]
+fn test_skip_none() {
+    struct Hooks {
+        options: RefCell<Vec<(String, bool)>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_option<S: serde::Serializer>(&self, path: &Path, opt: &mut ser::OptionScope<S>) {
+            self.options
+                .borrow_mut()
+                .push((path.borrow_str().clone(), opt.is_some()));
+            if opt.is_none() {
+                opt.skip();
+            }
+        }
+    }
+
+    let payload = Payload {
+        name: Some("John"),
+        age: None,
+        tags: vec![Some(1), None, Some(3)],
+    };
+
+    let hooks = Hooks {
+        options: RefCell::new(Vec::new()),
+    };
+    let options = ser::HookOptions::new().allow_skipping_values();
+    let json = serde_json::to_string(&ser::hook_with_options(&payload, &hooks, options)).unwrap();
+    assert_eq!(json, r#"{"name":"John","tags":[1,3]}"#);
+    assert_eq!(
+        hooks.options.take(),
+        [
+            ("name".to_string(), true),
+            ("age".to_string(), false),
+            ("tags[0]".to_string(), true),
+            ("tags[1]".to_string(), false),
+            ("tags[2]".to_string(), true),
+        ]
+    );
+}
+
+#[test]
+fn test_skip_not_allowed() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_option<S: serde::Serializer>(&self, _path: &Path, opt: &mut ser::OptionScope<S>) {
+            opt.skip();
+        }
+
+        fn on_scope_error(&self, _path: &Path, error: &mut ser::ErrorScope) {
+            assert_eq!(error.error().code(), "cannot_skip_value");
+            error.ignore();
+        }
+    }
+
+    let payload = Payload {
+        name: None,
+        age: Some(5),
+        tags: vec![],
+    };
+    let json = serde_json::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(json, r#"{"name":null,"age":5,"tags":[]}"#);
+}
+
+#[test]
+fn test_not_called_after_replace() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, _path: &Path, value: &mut ser::ValueScope<S>) {
+            if let serde_hooks::Value::None = value.value() {
+                value.replace(&0);
+            }
+        }
+
+        fn on_option<S: serde::Serializer>(&self, _path: &Path, opt: &mut ser::OptionScope<S>) {
+            assert!(opt.is_some());
+        }
+    }
+
+    let payload = Payload {
+        name: Some("John"),
+        age: None,
+        tags: vec![None],
+    };
+    let json = serde_json::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(json, r#"{"name":"John","age":0,"tags":[0]}"#);
+}
@@ -0,0 +1,279 @@
+use std::cell::RefCell;
+
+use serde::Serialize;
+
+use serde_hooks::{ser, Path, Value};
+
+#[derive(Serialize)]
+struct User {
+    name: String,
+    password: String,
+    tags: Vec<&'static str>,
+}
+
+#[test]
+fn test_only_matching() {
+    struct Hooks {
+        paths: RefCell<Vec<String>>,
+        started: RefCell<bool>,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_start(&self, _start: &mut ser::StartScope) {
+            *self.started.borrow_mut() = true;
+        }
+
+        fn on_struct(&self, path: &Path, st: &mut ser::StructScope) {
+            self.paths.borrow_mut().push(path.to_string());
+            st.rename_field("name", "login");
+        }
+
+        fn on_value<S: serde::Serializer>(&self, path: &Path, value: &mut ser::ValueScope<S>) {
+            self.paths.borrow_mut().push(path.to_string());
+            if let Value::Str(_) = value.value() {
+                value.replace("***");
+            }
+        }
+    }
+
+    let users = vec![
+        User {
+            name: "alice".into(),
+            password: "123".into(),
+            tags: vec!["admin", "secret"],
+        },
+        User {
+            name: "bob".into(),
+            password: "456".into(),
+            tags: vec![],
+        },
+    ];
+
+    let hooks = Hooks {
+        paths: RefCell::new(Vec::new()),
+        started: RefCell::new(false),
+    };
+
+    let options = ser::HookOptions::new().only_matching(
+        ser::CompiledMatchers::builder()
+            .pattern("[*].password")
+            .patterns(["[1]", "**.tags[1]"])
+            .build(),
+    );
+
+    let json = serde_json::to_string(&ser::hook_with_options(&users, &hooks, options)).unwrap();
+
+    assert_eq!(
+        json,
+        r#"[{"name":"alice","password":"***","tags":["admin","***"]},{"login":"bob","password":"***","tags":[]}]"#
+    );
+    assert_eq!(
+        hooks.paths.into_inner(),
+        vec!["[0].password", "[0].tags[1]", "[1]", "[1]", "[1].password"]
+    );
+    assert!(hooks.started.into_inner());
+}
+
+#[test]
+fn test_compiled_matchers() {
+    struct Hooks {
+        matchers: ser::CompiledMatchers,
+        matches: RefCell<Vec<(String, Vec<usize>)>>,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, path: &Path, _value: &mut ser::ValueScope<S>) {
+            if self.matchers.is_match(path) {
+                self.matches
+                    .borrow_mut()
+                    .push((path.to_string(), self.matchers.matching_patterns(path)));
+            }
+        }
+    }
+
+    let user = User {
+        name: "alice".into(),
+        password: "123".into(),
+        tags: vec!["admin"],
+    };
+
+    let hooks = Hooks {
+        matchers: ser::CompiledMatchers::builder()
+            .pattern("**")
+            .pattern("name")
+            .pattern("*[0]")
+            .pattern("tags[1]")
+            .build(),
+        matches: RefCell::new(Vec::new()),
+    };
+
+    ser::invoke_hooks(&user, &hooks).unwrap();
+
+    assert_eq!(
+        hooks.matches.into_inner(),
+        vec![
+            ("".to_string(), vec![0]),
+            ("name".to_string(), vec![0, 1]),
+            ("password".to_string(), vec![0]),
+            ("tags".to_string(), vec![0]),
+            ("tags[0]".to_string(), vec![0, 2]),
+        ]
+    );
+}
+
+#[test]
+fn test_max_depth() {
+    #[derive(Serialize)]
+    enum List {
+        Nil,
+        Cons(i32, Box<List>),
+    }
+
+    struct Hooks {
+        ignore: bool,
+        errors: RefCell<Vec<String>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_scope_error(&self, path: &Path, err: &mut ser::ErrorScope) {
+            assert_eq!(*err.error(), ser::HooksError::MaxDepthExceeded(1));
+            self.errors.borrow_mut().push(path.borrow_str().clone());
+            if self.ignore {
+                err.ignore();
+            }
+        }
+    }
+
+    let list = List::Cons(1, Box::new(List::Cons(2, Box::new(List::Nil))));
+
+    let hooks = Hooks {
+        ignore: false,
+        errors: RefCell::new(Vec::new()),
+    };
+    let res = serde_json::to_string(&ser::hook_with_options(
+        &list,
+        &hooks,
+        ser::HookOptions::new().max_depth(1),
+    ));
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .contains("maximum depth 1 exceeded"));
+    assert_eq!(hooks.errors.into_inner(), vec!["[1][0]"]);
+
+    let hooks = Hooks {
+        ignore: true,
+        errors: RefCell::new(Vec::new()),
+    };
+    let json = serde_json::to_string(&ser::hook_with_options(
+        &list,
+        &hooks,
+        ser::HookOptions::new().max_depth(1),
+    ))
+    .unwrap();
+    assert_eq!(json, r#"{"Cons":[1,{"Cons":[2,"Nil"]}]}"#);
+    assert_eq!(hooks.errors.into_inner(), vec!["[1][0]", "[1][1]"]);
+
+    let json = serde_json::to_string(&ser::hook_with_options(
+        &list,
+        &Hooks {
+            ignore: false,
+            errors: RefCell::new(Vec::new()),
+        },
+        ser::HookOptions::new().max_depth(2),
+    ))
+    .unwrap();
+    assert_eq!(json, r#"{"Cons":[1,{"Cons":[2,"Nil"]}]}"#);
+}
+
+#[test]
+fn test_float_policy() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {}
+
+    let payload = (f64::NAN, f64::INFINITY, f32::NEG_INFINITY, 1.5f64);
+
+    // serde_json writes non-finite floats as null
+    let json = serde_json::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(json, "[null,null,null,1.5]");
+
+    let options = ser::HookOptions::new().float_policy(ser::FloatPolicy::Custom(|v| {
+        if v.is_nan() {
+            "NaN".into()
+        } else if v > 0.0 {
+            "+Inf".into()
+        } else {
+            "-Inf".into()
+        }
+    }));
+    let json = serde_json::to_string(&ser::hook_with_options(&payload, &Hooks, options)).unwrap();
+    assert_eq!(json, r#"["NaN","+Inf","-Inf",1.5]"#);
+
+    let options = ser::HookOptions::new().float_policy(ser::FloatPolicy::NullifyNonFinite);
+    let ron = ron::to_string(&ser::hook_with_options(&payload, &Hooks, options)).unwrap();
+    assert_eq!(ron, "(None,None,None,1.5)");
+
+    let ron = ron::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(ron, "(NaN,inf,-inf,1.5)");
+}
+
+#[test]
+fn test_float_policy_unserializable() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {}
+
+    let options =
+        ser::HookOptions::new().float_policy(ser::FloatPolicy::Custom(|_| Value::Seq(Some(0))));
+    let err =
+        serde_json::to_string(&ser::hook_with_options(&f64::NAN, &Hooks, options)).unwrap_err();
+    assert!(
+        err.to_string().contains("value is not serializable"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_hook_builder() {
+    #[derive(Serialize)]
+    struct Payload {
+        ratio: f64,
+        nested: Vec<Vec<u8>>,
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {}
+
+    let payload = Payload {
+        ratio: f64::INFINITY,
+        nested: vec![vec![1]],
+    };
+
+    let json = serde_json::to_string(
+        &ser::HookBuilder::new(&Hooks)
+            .float_policy(ser::FloatPolicy::NullifyNonFinite)
+            .wrap(&payload),
+    )
+    .unwrap();
+    assert_eq!(json, r#"{"ratio":null,"nested":[[1]]}"#);
+
+    let err = serde_json::to_string(
+        &ser::HookBuilder::new(&Hooks)
+            .float_policy(ser::FloatPolicy::NullifyNonFinite)
+            .max_depth(2)
+            .wrap(&payload),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error at path 'nested[0][0]': maximum depth 2 exceeded"
+    );
+
+    // Setting options replaces the ones set before.
+    let json = serde_json::to_string(
+        &ser::HookBuilder::new(&Hooks)
+            .max_depth(2)
+            .options(ser::HookOptions::new())
+            .wrap(&payload),
+    )
+    .unwrap();
+    assert_eq!(json, r#"{"ratio":null,"nested":[[1]]}"#);
+}
@@ -0,0 +1,55 @@
+use serde::Serialize;
+use serde_hooks::{ser, Value};
+
+#[test]
+fn test_recording_hooks() {
+    #[derive(Serialize)]
+    struct Address {
+        city: String,
+    }
+
+    #[derive(Serialize)]
+    struct User {
+        name: &'static str,
+        address: Address,
+        tags: Vec<u8>,
+    }
+
+    let user = User {
+        name: "Alice",
+        address: Address {
+            city: "Paris".to_string(),
+        },
+        tags: vec![1, 2],
+    };
+
+    let hooks = ser::RecordingHooks::new();
+    let json = serde_json::to_string(&ser::hook(&user, &hooks)).unwrap();
+    assert_eq!(json, serde_json::to_string(&user).unwrap());
+
+    let records = hooks.into_records();
+    assert_eq!(
+        records,
+        [
+            (
+                "".to_string(),
+                Value::Struct {
+                    name: "User",
+                    len: 3
+                }
+            ),
+            ("name".to_string(), Value::Str("Alice".into())),
+            (
+                "address".to_string(),
+                Value::Struct {
+                    name: "Address",
+                    len: 1
+                }
+            ),
+            ("address.city".to_string(), Value::Str("Paris".into())),
+            ("tags".to_string(), Value::Seq(Some(2))),
+            ("tags[0]".to_string(), Value::U8(1)),
+            ("tags[1]".to_string(), Value::U8(2)),
+        ]
+    );
+}
@@ -0,0 +1,134 @@
+use serde::Serialize;
+use serde_hooks::{ser, Path, Value};
+
+#[derive(Serialize)]
+struct User {
+    name: &'static str,
+    age: u8,
+}
+
+#[derive(Serialize)]
+struct Data {
+    users: Vec<User>,
+}
+
+#[derive(Serialize)]
+struct Response {
+    data: Data,
+    database: &'static str,
+}
+
+impl Response {
+    fn new() -> Self {
+        Self {
+            data: Data {
+                users: vec![
+                    User {
+                        name: "Alice",
+                        age: 30,
+                    },
+                    User {
+                        name: "Bob",
+                        age: 40,
+                    },
+                ],
+            },
+            database: "main",
+        }
+    }
+}
+
+#[test]
+fn test_scoped() {
+    let hooks = ser::scoped("data", ser::RecordingHooks::new());
+    ser::invoke_hooks(&Response::new(), &hooks).unwrap();
+
+    let paths: Vec<_> = hooks
+        .inner()
+        .records()
+        .iter()
+        .map(|(path, _)| path.clone())
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            "",
+            "users",
+            "users[0]",
+            "users[0].name",
+            "users[0].age",
+            "users[1]",
+            "users[1].name",
+            "users[1].age"
+        ]
+    );
+}
+
+#[test]
+fn test_nested_scoped() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, path: &Path, st: &mut ser::StructScope) {
+            assert!(path.is_root());
+            st.skip_field("age");
+        }
+    }
+
+    let hooks = ser::scoped("data", ser::scoped("users[1]", Hooks));
+    assert_eq!(hooks.prefix(), "data");
+    assert_eq!(hooks.inner().prefix(), "users[1]");
+
+    let json = serde_json::to_string(&ser::hook(&Response::new(), &hooks)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"data":{"users":[{"name":"Alice","age":30},{"name":"Bob"}]},"database":"main"}"#
+    );
+
+    let recording = ser::scoped("data", ser::scoped("users[0]", ser::RecordingHooks::new()));
+    ser::invoke_hooks(&Response::new(), &recording).unwrap();
+    let records = recording.into_inner().into_inner().into_records();
+    assert_eq!(records[1], ("name".to_string(), Value::Str("Alice".into())));
+    assert_eq!(records[2], ("age".to_string(), Value::U8(30)));
+    assert_eq!(records.len(), 3);
+}
+
+#[test]
+fn test_scoped_matches_whole_segments() {
+    let hooks = ser::scoped("data", ser::RecordingHooks::new());
+    ser::invoke_hooks(&"unrelated", &hooks).unwrap();
+    assert!(hooks.inner().records().is_empty());
+
+    let hooks = ser::scoped("datab", ser::RecordingHooks::new());
+    ser::invoke_hooks(&Response::new(), &hooks).unwrap();
+    assert!(hooks.inner().records().is_empty());
+
+    let hooks = ser::scoped("database", ser::RecordingHooks::new());
+    ser::invoke_hooks(&Response::new(), &hooks).unwrap();
+    assert_eq!(
+        &*hooks.inner().records(),
+        [("".to_string(), Value::Str("main".into()))]
+    );
+}
+
+#[test]
+fn test_scoped_errors() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.skip_field("missing");
+        }
+
+        fn on_scope_error(&self, path: &Path, err: &mut ser::ErrorScope) {
+            if path == "users[0]" {
+                err.ignore();
+            }
+        }
+    }
+
+    let hooks = ser::scoped("data", Hooks);
+    let err = serde_json::to_string(&ser::hook(&Response::new(), &hooks)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error at path 'data.users[1]': field \"missing\" not found"
+    );
+}
@@ -1,6 +1,6 @@
 use std::{cell::RefCell, collections::HashSet};
 
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 use serde_hooks::{ser, Path, StaticValue};
 
 #[test]
@@ -98,6 +98,189 @@ fn test_seq_retain_element() {
     assert_eq!(json, "[1,2]");
 }
 
+/// Sequence that does not report its length to the serializer.
+struct UnknownLen(Vec<Vec<i32>>);
+
+impl Serialize for UnknownLen {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(None)?;
+        for element in &self.0 {
+            seq.serialize_element(element)?;
+        }
+        seq.end()
+    }
+}
+
+#[test]
+fn test_seq_skip_last() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, path: &Path, seq: &mut ser::SeqScope) {
+            if path.borrow_str().is_empty() {
+                assert_eq!(seq.seq_len(), None);
+                seq.skip_last().skip_element(0);
+            }
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(
+        &UnknownLen(vec![vec![0], vec![1, 2], vec![3]]),
+        &Hooks,
+    ))
+    .unwrap();
+    assert_eq!(json, "[[1,2]]");
+
+    let json = serde_json::to_string(&ser::hook(&UnknownLen(vec![vec![0]]), &Hooks)).unwrap();
+    assert_eq!(json, "[]");
+}
+
+#[test]
+fn test_seq_retain_last() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, path: &Path, seq: &mut ser::SeqScope) {
+            if path.borrow_str().is_empty() {
+                seq.retain_element(0).retain_last();
+            } else {
+                // hooks on the held back elements still apply
+                seq.skip_element(0);
+            }
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(
+        &UnknownLen(vec![vec![0, 1], vec![2, 3], vec![4, 5]]),
+        &Hooks,
+    ))
+    .unwrap();
+    assert_eq!(json, "[[1],[5]]");
+
+    let json = serde_json::to_string(&ser::hook(&UnknownLen(vec![vec![0, 1]]), &Hooks)).unwrap();
+    assert_eq!(json, "[[1]]");
+}
+
+#[test]
+fn test_seq_insert_element() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            assert_eq!(seq.seq_len(), Some(3));
+            seq.insert_element(0, -1i32, ser::SeqInsertLocation::Before)
+                .insert_element(0, -2i32, ser::SeqInsertLocation::Before)
+                .insert_element(1, "after", ser::SeqInsertLocation::After)
+                .insert_element(0, 100i32, ser::SeqInsertLocation::End)
+                .insert_element(12345, "error", ser::SeqInsertLocation::Before);
+        }
+
+        fn on_scope_error(&self, path: &Path, err: &mut ser::ErrorScope) {
+            assert_eq!(path, "");
+            assert_eq!(*err.error(), ser::HooksError::IndexNotFound(12345));
+            err.ignore();
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(&vec![0i32, 1, 2], &Hooks)).unwrap();
+    assert_eq!(json, "[-1,-2,0,1,\"after\",2,100]");
+}
+
+#[test]
+fn test_seq_insert_element_with_skip_last() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, path: &Path, seq: &mut ser::SeqScope) {
+            if path.borrow_str().is_empty() {
+                seq.skip_last()
+                    .insert_element(2, "after last", ser::SeqInsertLocation::After)
+                    .insert_element(2, "end", ser::SeqInsertLocation::End);
+            }
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(
+        &UnknownLen(vec![vec![0], vec![1], vec![2]]),
+        &Hooks,
+    ))
+    .unwrap();
+    assert_eq!(json, "[[0],[1],\"after last\",\"end\"]");
+}
+
+#[test]
+fn test_seq_truncate() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            seq.truncate(4).truncate(3);
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(&vec![0i32, 1, 2, 3, 4], &Hooks)).unwrap();
+    assert_eq!(json, "[0,1,2]");
+
+    let json = serde_json::to_string(&ser::hook(&vec![0i32, 1], &Hooks)).unwrap();
+    assert_eq!(json, "[0,1]");
+}
+
+#[test]
+fn test_seq_truncate_with_skip() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            seq.truncate(3).skip_element(1);
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(&vec![0i32, 1, 2, 3, 4], &Hooks)).unwrap();
+    assert_eq!(json, "[0,2]");
+}
+
+#[test]
+fn test_seq_retain_range() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            seq.retain_range(1..3);
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(&vec![0i32, 1, 2, 3, 4], &Hooks)).unwrap();
+    assert_eq!(json, "[1,2]");
+}
+
+#[test]
+fn test_seq_skip_range() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            seq.skip_range(..2);
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(&vec![0i32, 1, 2, 3, 4], &Hooks)).unwrap();
+    assert_eq!(json, "[2,3,4]");
+}
+
+#[test]
+fn test_seq_open_ended_range_unknown_len() {
+    struct Seq;
+    impl Serialize for Seq {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq((0..5).filter(|_| true))
+        }
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            assert_eq!(seq.seq_len(), None);
+            seq.retain_range(3..).retain_range(..=0);
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(&Seq, &Hooks)).unwrap();
+    assert_eq!(json, "[0,3,4]");
+}
+
 #[test]
 fn test_seq_replace_value() {
     struct Hooks;
@@ -131,3 +314,138 @@ fn test_seq_replace_value_unserializable() {
     let err = serde_json::to_string(&ser::hook(&vec![0i32, 1, 2, 3], &Hooks)).unwrap_err();
     assert_eq!(err.to_string(), "Error at path '[1]': value is not serializable: newtype STRUCT cannot be represented fully in Value");
 }
+
+#[test]
+fn test_on_seq_element_end() {
+    #[derive(Default)]
+    struct Hooks {
+        ended: RefCell<Vec<(String, usize)>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            seq.skip_element(1)
+                .insert_element(2, 10, ser::SeqInsertLocation::After);
+        }
+
+        fn on_seq_element_end(&self, path: &Path, index: usize) {
+            self.ended
+                .borrow_mut()
+                .push((path.borrow_str().clone(), index));
+        }
+    }
+
+    let hooks = Hooks::default();
+    let json = serde_json::to_string(&ser::hook(&vec![1, 2, 3, 4], &hooks)).unwrap();
+    assert_eq!(json, "[1,3,10,4]");
+    assert_eq!(
+        hooks.ended.into_inner(),
+        [
+            ("[0]".to_string(), 0),
+            ("[1]".to_string(), 1),
+            ("[2]".to_string(), 2),
+            ("[3]".to_string(), 3),
+        ]
+    );
+}
+
+#[test]
+fn test_seq_set_len_hint() {
+    struct Hooks {
+        len_hint: Option<usize>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            seq.skip_element(0)
+                .insert_element(1, 10u8, ser::SeqInsertLocation::Before)
+                .insert_element(1, 20u8, ser::SeqInsertLocation::Before);
+            if let Some(len) = self.len_hint {
+                seq.set_len_hint(len);
+            }
+        }
+    }
+
+    let payload = vec![0u8, 1, 2];
+
+    // bincode requires the sequence length to be known upfront
+    bincode::serialize(&ser::hook(&payload, &Hooks { len_hint: None })).unwrap_err();
+
+    // bincode writes the length it was given as u64, followed by the elements
+    let bytes = bincode::serialize(&ser::hook(&payload, &Hooks { len_hint: Some(4) })).unwrap();
+    assert_eq!(bytes, [4, 0, 0, 0, 0, 0, 0, 0, 10, 20, 1, 2]);
+}
+
+#[test]
+fn test_skip_elements_matching() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            seq.skip_elements_matching(|v| matches!(v, serde_hooks::Value::I32(i) if *i < 0));
+        }
+    }
+
+    let payload = vec![3, -1, 0, -7, 5];
+    let json = serde_json::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(json, "[3,0,5]");
+
+    // Compound elements are never matched
+    let payload = vec![vec![-1], vec![2]];
+    let json = serde_json::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(json, "[[],[2]]");
+}
+
+#[test]
+fn test_skip_elements_matching_failing_serialize() {
+    struct Failing;
+    impl Serialize for Failing {
+        fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("cannot serialize"))
+        }
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            seq.skip_elements_matching(|_| true);
+        }
+    }
+
+    // The element is not skipped, and its error is reported by the serializer.
+    let err = serde_json::to_string(&ser::hook(&vec![Failing], &Hooks)).unwrap_err();
+    assert_eq!(err.to_string(), "cannot serialize");
+}
+
+#[test]
+fn test_all_missing_indices_reported() {
+    struct Hooks {
+        errors: RefCell<Vec<String>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            seq.skip_element(5)
+                .replace_value(7, 0)
+                .retain_element(0)
+                .retain_element(9);
+        }
+
+        fn on_scope_error(&self, path: &Path, err: &mut ser::ErrorScope) {
+            self.errors
+                .borrow_mut()
+                .push(format!("{path}: {}", err.error()));
+            err.ignore();
+        }
+    }
+
+    let hooks = Hooks {
+        errors: RefCell::new(Vec::new()),
+    };
+    let json = serde_json::to_string(&ser::hook(&[1, 2, 3], &hooks)).unwrap();
+    assert_eq!(json, "[1]");
+    assert_eq!(
+        hooks.errors.into_inner(),
+        [
+            ": index \"5\" not found",
+            ": index \"7\" not found",
+            ": index \"9\" not found"
+        ]
+    );
+}
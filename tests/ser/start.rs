@@ -1,5 +1,6 @@
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 
+use serde::Serialize;
 use serde_hooks::ser;
 
 #[test]
@@ -47,3 +48,96 @@ fn test_is_human_readable() {
     ))
     .unwrap();
 }
+
+#[test]
+fn test_override_human_readable() {
+    struct Hooks {
+        flags: RefCell<Vec<bool>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_start(&self, start: &mut ser::StartScope) {
+            start.override_human_readable(false);
+            // The real serializer flag is still reported here
+            assert!(start.is_format_human_readable());
+        }
+
+        fn on_seq(&self, _path: &serde_hooks::Path, seq: &mut ser::SeqScope) {
+            self.flags.borrow_mut().push(seq.is_human_readable());
+        }
+    }
+
+    let hooks = Hooks {
+        flags: RefCell::new(Vec::new()),
+    };
+    let json = serde_json::to_string(&ser::hook(&vec![vec![1]], &hooks)).unwrap();
+    assert_eq!(json, "[[1]]");
+    assert_eq!(hooks.flags.into_inner(), [false, false]);
+}
+
+#[test]
+fn test_format_hint() {
+    struct Hooks {
+        expect_format: ser::FormatHint<'static>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_start(&self, start: &mut ser::StartScope) {
+            assert_eq!(start.format_hint(), self.expect_format);
+        }
+    }
+
+    serde_json::to_string(&ser::hook_with_format(
+        &(),
+        &Hooks {
+            expect_format: ser::FormatHint::Tagged("custom-json"),
+        },
+        "custom-json",
+    ))
+    .unwrap();
+
+    serde_json::to_string(&ser::hook(
+        &(),
+        &Hooks {
+            expect_format: ser::FormatHint::HumanReadable,
+        },
+    ))
+    .unwrap();
+
+    bincode::serialize(&ser::hook(
+        &(),
+        &Hooks {
+            expect_format: ser::FormatHint::Compact,
+        },
+    ))
+    .unwrap();
+}
+
+#[test]
+fn test_abort() {
+    struct Payload;
+    impl Serialize for Payload {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            unreachable!("serialization must be aborted")
+        }
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_start(&self, start: &mut ser::StartScope) {
+            start.abort("precondition failed");
+        }
+
+        fn on_end<Error: serde::ser::Error>(&self, end: &mut ser::EndScope<Error>) {
+            assert!(end.result().is_err());
+        }
+    }
+
+    let mut events = Vec::new();
+    let err = ser::hook(&Payload, &Hooks)
+        .serialize(ser::testing::RecordingSerializer::new(&mut events))
+        .unwrap_err();
+    assert_eq!(err.to_string(), "precondition failed");
+    assert!(events.is_empty());
+
+    let err = serde_json::to_string(&ser::hook(&Payload, &Hooks)).unwrap_err();
+    assert_eq!(err.to_string(), "precondition failed");
+}
@@ -1,6 +1,6 @@
 use std::{
     cell::{Cell, RefCell},
-    collections::HashSet,
+    collections::{HashMap, HashSet},
 };
 
 use serde::Serialize;
@@ -208,6 +208,32 @@ fn test_rename_field() {
     assert_eq!(json, "{\"not_foo\":42,\"bar_42\":\"a\",\"baz3\":\"sample\",\"e\":{\"StructVariant\":{\"not_foo_either\":21,\"bar_21\":\"b\",\"baz5\":\"example\"}}}");
 }
 
+#[test]
+fn test_rename_field_with() {
+    #[derive(Serialize)]
+    struct Payload {
+        some_field: (),
+        other_field: (),
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.rename_field_with("some_field", |key| format!("x_{key}"));
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(
+        &Payload {
+            some_field: (),
+            other_field: (),
+        },
+        &Hooks,
+    ))
+    .unwrap();
+    assert_eq!(json, "{\"x_some_field\":null,\"other_field\":null}");
+}
+
 #[test]
 fn test_rename_field_case() {
     #[derive(Serialize)]
@@ -226,6 +252,41 @@ fn test_rename_field_case() {
     assert_eq!(json, "{\"SOME-FIELD\":null}");
 }
 
+#[test]
+fn test_rename_field_at() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, path: &Path, st: &mut ser::StructScope) {
+            if path.is_root() {
+                st.rename_field_at(1, "second");
+            }
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(&Payload::new(), &Hooks)).unwrap();
+    assert_eq!(json, "{\"p1\":42,\"second\":\"a\",\"p3\":\"sample\",\"e\":{\"StructVariant\":{\"p1\":21,\"p2\":\"b\",\"p3\":\"example\"}}}");
+}
+
+#[test]
+fn test_rename_field_at_out_of_range() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, path: &Path, st: &mut ser::StructScope) {
+            if path.is_root() {
+                st.rename_field_at(4, "fifth");
+            }
+        }
+
+        fn on_scope_error(&self, path: &Path, err: &mut ser::ErrorScope) {
+            assert_eq!(path, "");
+            assert_eq!(*err.error(), ser::HooksError::FieldIndexNotFound(4));
+        }
+    }
+
+    let err = serde_json::to_string(&ser::hook(&Payload::new(), &Hooks)).unwrap_err();
+    assert_eq!(err.to_string(), "Error at path '': field index 4 not found");
+}
+
 #[test]
 fn test_rename_all_fields() {
     #[derive(Serialize)]
@@ -276,6 +337,71 @@ fn test_rename_all_fields() {
     );
 }
 
+#[test]
+fn test_rename_fields_matching() {
+    #[derive(Serialize)]
+    struct Payload {
+        first_field: (),
+        _private_field: (),
+        #[serde(rename = "serde_renamed")]
+        renamed_by_serde: (),
+        explicitly_renamed: (),
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.rename_field("explicitly_renamed", "explicit")
+                .rename_fields_matching(|key| !key.starts_with('_'), Case::Pascal);
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(
+        &Payload {
+            first_field: (),
+            _private_field: (),
+            renamed_by_serde: (),
+            explicitly_renamed: (),
+        },
+        &Hooks,
+    ))
+    .unwrap();
+    assert_eq!(
+        json,
+        "{\"FirstField\":null,\"_private_field\":null,\"SerdeRenamed\":null,\"explicit\":null}"
+    );
+}
+
+#[test]
+fn test_rename_all_fields_custom_case() {
+    #[derive(Serialize)]
+    struct Payload {
+        foo_bar: (),
+        baz: (),
+    }
+
+    fn upper_reversed(name: &str) -> String {
+        name.chars().rev().collect::<String>().to_uppercase()
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.rename_all_fields_case(Case::Custom(upper_reversed));
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(
+        &Payload {
+            foo_bar: (),
+            baz: (),
+        },
+        &Hooks,
+    ))
+    .unwrap();
+    assert_eq!(json, "{\"RAB_OOF\":null,\"ZAB\":null}");
+}
+
 #[test]
 fn test_replace_value() {
     struct Hooks;
@@ -300,6 +426,25 @@ fn test_replace_value() {
     assert_eq!(json, "{\"p1\":42,\"p2\":\"a\",\"p3\":-15,\"e\":{\"StructVariant\":{\"p1\":21,\"p2\":\"b\",\"p3\":\"x\"}}}");
 }
 
+#[test]
+fn test_replace_value_128_bit() {
+    assert_eq!(StaticValue::from(1i128), StaticValue::I128(1));
+    assert_eq!(StaticValue::from(1u128), StaticValue::U128(1));
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, path: &Path, st: &mut ser::StructScope) {
+            if path.is_root() {
+                st.replace_value("p1", i128::MIN)
+                    .replace_value("p3", u128::MAX);
+            }
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(&Payload::new(), &Hooks)).unwrap();
+    assert_eq!(json, format!("{{\"p1\":{},\"p2\":\"a\",\"p3\":{},\"e\":{{\"StructVariant\":{{\"p1\":21,\"p2\":\"b\",\"p3\":\"example\"}}}}}}", i128::MIN, u128::MAX));
+}
+
 #[test]
 fn test_struct_replace_value_unserializable() {
     struct Hooks;
@@ -401,6 +546,216 @@ fn test_serialize_as_map() {
     assert!(hooks.on_map_called.get());
 }
 
+#[test]
+fn test_serialize_as_seq() {
+    #[derive(Serialize)]
+    struct Point {
+        x: i32,
+        y: i32,
+        label: &'static str,
+    }
+
+    struct Hooks {
+        skip_y: bool,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.serialize_as_seq().rename_field("x", "renamed");
+            if self.skip_y {
+                st.skip_field("y");
+            }
+        }
+    }
+
+    let point = Point {
+        x: 1,
+        y: 2,
+        label: "origin",
+    };
+
+    let json = serde_json::to_string(&ser::hook(&point, &Hooks { skip_y: false })).unwrap();
+    assert_eq!(json, r#"[1,2,"origin"]"#);
+
+    let json = serde_json::to_string(&ser::hook(&point, &Hooks { skip_y: true })).unwrap();
+    assert_eq!(json, r#"[1,"origin"]"#);
+}
+
+#[test]
+fn test_serialize_as_map_value_paths() {
+    #[derive(Serialize)]
+    struct Outer {
+        a: u8,
+        b: Inner,
+    }
+
+    #[derive(Serialize)]
+    struct Inner {
+        c: u8,
+    }
+
+    struct Hooks {
+        paths: RefCell<Vec<String>>,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.serialize_as_map();
+        }
+
+        fn on_value<S: serde::Serializer>(&self, path: &Path, _value: &mut ser::ValueScope<S>) {
+            self.paths.borrow_mut().push(path.to_string());
+        }
+    }
+
+    let hooks = Hooks {
+        paths: RefCell::new(Vec::new()),
+    };
+    let payload = Outer {
+        a: 1,
+        b: Inner { c: 2 },
+    };
+
+    let json = serde_json::to_string(&ser::hook(&payload, &hooks)).unwrap();
+    assert_eq!(json, r#"{"a":1,"b":{"c":2}}"#);
+
+    // each value is visited once, at the struct field path
+    assert_eq!(hooks.paths.into_inner(), vec!["", "a", "b", "b.c"]);
+}
+
+#[test]
+fn test_serialize_as_sorted_map() {
+    #[derive(Serialize)]
+    struct Unordered {
+        zeta: u8,
+        alpha: Option<char>,
+        mid: Vec<u8>,
+        inner: Inner,
+    }
+
+    #[derive(Serialize)]
+    struct Inner {
+        b: (),
+        a: (),
+    }
+
+    struct Hooks {
+        sorted: bool,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, path: &Path, st: &mut ser::StructScope) {
+            if self.sorted {
+                st.serialize_as_sorted_map();
+                if path.is_root() {
+                    st.rename_field("zeta", "beta");
+                }
+            } else {
+                st.serialize_as_map();
+            }
+        }
+    }
+
+    let payload = Unordered {
+        zeta: 1,
+        alpha: Some('a'),
+        mid: vec![1, 2],
+        inner: Inner { b: (), a: () },
+    };
+
+    // using RON in this test because it distinguishes between structs and maps
+    let ron = ron::to_string(&ser::hook(&payload, &Hooks { sorted: false })).unwrap();
+    assert_eq!(
+        ron,
+        "{\"zeta\":1,\"alpha\":Some('a'),\"mid\":[1,2],\"inner\":{\"b\":(),\"a\":()}}"
+    );
+
+    let ron = ron::to_string(&ser::hook(&payload, &Hooks { sorted: true })).unwrap();
+    assert_eq!(
+        ron,
+        "{\"alpha\":Some('a'),\"beta\":1,\"inner\":{\"a\":(),\"b\":()},\"mid\":[1,2]}"
+    );
+}
+
+#[test]
+fn test_sort_fields() {
+    #[derive(Serialize)]
+    struct Fields {
+        b_field: u8,
+        c_field: u8,
+        a_field: u8,
+    }
+
+    struct Hooks {
+        reverse: bool,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.rename_all_fields_case(Case::Pascal)
+                .rename_field("c_field", "Z");
+            if self.reverse {
+                st.sort_fields_by(|a, b| b.cmp(a));
+            } else {
+                st.sort_fields();
+            }
+        }
+    }
+
+    let payload = Fields {
+        b_field: 1,
+        c_field: 2,
+        a_field: 3,
+    };
+
+    let json = serde_json::to_string(&ser::hook(&payload, &Hooks { reverse: false })).unwrap();
+    assert_eq!(json, r#"{"AField":3,"BField":1,"Z":2}"#);
+
+    let json = serde_json::to_string(&ser::hook(&payload, &Hooks { reverse: true })).unwrap();
+    assert_eq!(json, r#"{"Z":2,"BField":1,"AField":3}"#);
+}
+
+#[test]
+fn test_reorder_fields() {
+    #[derive(Serialize)]
+    struct Fields {
+        a: u8,
+        b: u8,
+        c: u8,
+        d: u8,
+        e: u8,
+    }
+
+    struct Hooks {
+        sorted: bool,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.reorder_fields(["d", "missing", "b"])
+                .rename_field("a", "z");
+            if self.sorted {
+                st.serialize_as_sorted_map();
+            }
+        }
+    }
+
+    let payload = Fields {
+        a: 1,
+        b: 2,
+        c: 3,
+        d: 4,
+        e: 5,
+    };
+
+    // using RON in this test because it distinguishes between structs and maps
+    let ron = ron::to_string(&ser::hook(&payload, &Hooks { sorted: false })).unwrap();
+    assert_eq!(ron, "{\"d\":4,\"b\":2,\"z\":1,\"c\":3,\"e\":5}");
+
+    let ron = ron::to_string(&ser::hook(&payload, &Hooks { sorted: true })).unwrap();
+    assert_eq!(ron, "{\"d\":4,\"b\":2,\"c\":3,\"e\":5,\"z\":1}");
+}
+
 #[test]
 fn test_flatten() {
     #[derive(Serialize, Default)]
@@ -465,3 +820,275 @@ fn test_flatten_unsupported_data_type() {
         "Error at path 'inner': cannot flatten unsupported data type \"i8\""
     );
 }
+
+#[test]
+fn test_insert_field() {
+    struct Hooks {
+        value_paths: RefCell<Vec<String>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, path: &Path, st: &mut ser::StructScope) {
+            if path.is_root() {
+                st.insert_field("generated", true);
+            }
+        }
+
+        fn on_value<S: serde::Serializer>(&self, path: &Path, value: &mut ser::ValueScope<S>) {
+            if let serde_hooks::Value::Bool(_) = value.value() {
+                self.value_paths.borrow_mut().push(path.to_string());
+            }
+        }
+    }
+
+    let hooks = Hooks {
+        value_paths: RefCell::new(Vec::new()),
+    };
+
+    let json = serde_json::to_string(&ser::hook(&Payload::new(), &hooks)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"p1":42,"p2":"a","p3":"sample","e":{"StructVariant":{"p1":21,"p2":"b","p3":"example"}},"generated":true}"#
+    );
+    assert_eq!(hooks.value_paths.take(), ["generated"]);
+
+    // The struct is serialized as a map
+    let ron = ron::to_string(&ser::hook(&Payload::new(), &hooks)).unwrap();
+    assert_eq!(ron, "{\"p1\":42,\"p2\":Some('a'),\"p3\":\"sample\",\"e\":StructVariant(p1:21,p2:Some('b'),p3:\"example\"),\"generated\":true}");
+}
+
+#[test]
+fn test_insert_field_unserializable() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, path: &Path, st: &mut ser::StructScope) {
+            if path.is_root() {
+                st.insert_field("generated", StaticValue::NewtypeStruct("STRUCT"));
+            }
+        }
+    }
+
+    let err = serde_json::to_string(&ser::hook(&Payload::new(), &Hooks)).unwrap_err();
+    assert_eq!(err.to_string(), "Error at path '': value is not serializable: newtype STRUCT cannot be represented fully in Value");
+}
+
+#[test]
+fn test_is_human_readable() {
+    #[derive(Serialize)]
+    struct Payload {
+        map: HashMap<u8, u8>,
+        seq: Vec<u8>,
+    }
+
+    struct Hooks {
+        flags: RefCell<Vec<bool>>,
+    }
+
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            self.flags.borrow_mut().push(st.is_human_readable());
+        }
+
+        fn on_map(&self, _path: &Path, map: &mut ser::MapScope) {
+            self.flags.borrow_mut().push(map.is_human_readable());
+        }
+
+        fn on_seq(&self, _path: &Path, seq: &mut ser::SeqScope) {
+            self.flags.borrow_mut().push(seq.is_human_readable());
+        }
+    }
+
+    let payload = Payload {
+        map: HashMap::new(),
+        seq: vec![],
+    };
+
+    let hooks = Hooks {
+        flags: RefCell::new(Vec::new()),
+    };
+    serde_json::to_string(&ser::hook(&payload, &hooks)).unwrap();
+    assert_eq!(hooks.flags.into_inner(), [true, true, true]);
+
+    let hooks = Hooks {
+        flags: RefCell::new(Vec::new()),
+    };
+    bincode::serialize(&ser::hook(&payload, &hooks)).unwrap();
+    // bincode runs serialization twice, first to compute the size
+    assert_eq!(hooks.flags.into_inner(), [false; 6]);
+}
+
+#[test]
+fn test_skip_field_if_value() {
+    #[derive(Serialize)]
+    struct Counters {
+        a: i32,
+        b: u64,
+        c: Option<i32>,
+        d: Vec<i32>,
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            let is_zero = |v: &serde_hooks::Value| {
+                matches!(v, serde_hooks::Value::I32(0) | serde_hooks::Value::U64(0))
+            };
+            st.skip_field_if_value("a", is_zero)
+                .skip_field_if_value("b", is_zero)
+                .skip_field_if_value("c", is_zero)
+                .skip_field_if_value("d", |_| true);
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(
+        &Counters {
+            a: 0,
+            b: 7,
+            c: Some(0),
+            d: vec![],
+        },
+        &Hooks,
+    ))
+    .unwrap();
+    assert_eq!(json, r#"{"b":7,"d":[]}"#);
+
+    let json = serde_json::to_string(&ser::hook(
+        &Counters {
+            a: 1,
+            b: 0,
+            c: None,
+            d: vec![0],
+        },
+        &Hooks,
+    ))
+    .unwrap();
+    assert_eq!(json, r#"{"a":1,"c":null,"d":[0]}"#);
+}
+
+#[test]
+fn test_skip_field_if_value_failing_serialize() {
+    struct Failing;
+    impl Serialize for Failing {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("cannot serialize"))
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Payload {
+        a: Failing,
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.skip_field_if_value("a", |_| true);
+        }
+    }
+
+    // The field is not skipped, and its error is reported by the serializer.
+    let err = serde_json::to_string(&ser::hook(&Payload { a: Failing }, &Hooks)).unwrap_err();
+    assert_eq!(err.to_string(), "cannot serialize");
+}
+
+#[test]
+fn test_on_struct_end() {
+    struct Hooks {
+        lens: RefCell<Vec<(String, usize, usize)>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, path: &Path, st: &mut ser::StructScope) {
+            if path.is_root() {
+                st.retain_field("p1").retain_field("e");
+            }
+            self.lens
+                .borrow_mut()
+                .push((path.to_string(), st.struct_len(), 0));
+        }
+
+        fn on_struct_end(&self, path: &Path, emitted_len: usize) {
+            let mut lens = self.lens.borrow_mut();
+            let entry = lens.iter_mut().rev().find(|e| e.0 == path.to_string());
+            entry.unwrap().2 = emitted_len;
+        }
+    }
+
+    let hooks = Hooks {
+        lens: RefCell::new(Vec::new()),
+    };
+    let json = serde_json::to_string(&ser::hook(&Payload::new(), &hooks)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"p1":42,"e":{"StructVariant":{"p1":21,"p2":"b","p3":"example"}}}"#
+    );
+    assert_eq!(
+        hooks.lens.take(),
+        [("".to_string(), 4, 2), ("e".to_string(), 3, 3)]
+    );
+}
+
+#[test]
+fn test_clear_fields() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, path: &Path, st: &mut ser::StructScope) {
+            if path.is_root() {
+                st.clear_fields().insert_field("summary", "p1=42");
+            }
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(&Payload::new(), &Hooks)).unwrap();
+    assert_eq!(json, r#"{"summary":"p1=42"}"#);
+
+    // Retained fields are kept
+    struct RetainHooks;
+    impl ser::Hooks for RetainHooks {
+        fn on_struct(&self, path: &Path, st: &mut ser::StructScope) {
+            if path.is_root() {
+                st.clear_fields()
+                    .retain_field("p1")
+                    .insert_field("extra", true);
+            }
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(&Payload::new(), &RetainHooks)).unwrap();
+    assert_eq!(json, r#"{"p1":42,"extra":true}"#);
+}
+
+#[test]
+fn test_all_missing_fields_reported() {
+    struct Hooks {
+        errors: RefCell<Vec<String>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_struct(&self, _path: &Path, st: &mut ser::StructScope) {
+            st.skip_field("bogus1")
+                .rename_field("bogus2", "x")
+                .rename_field_at(42, "y")
+                .replace_value("bogus3", 0);
+        }
+
+        fn on_scope_error(&self, path: &Path, err: &mut ser::ErrorScope) {
+            self.errors
+                .borrow_mut()
+                .push(format!("{path}: {}", err.error()));
+            err.ignore();
+        }
+    }
+
+    let hooks = Hooks {
+        errors: RefCell::new(Vec::new()),
+    };
+    let payload = Payload::new();
+    serde_json::to_string(&ser::hook(&payload.e, &hooks)).unwrap();
+    assert_eq!(
+        hooks.errors.into_inner(),
+        [
+            ": field \"bogus1\" not found",
+            ": field \"bogus2\" not found",
+            ": field index 42 not found",
+            ": field \"bogus3\" not found"
+        ]
+    );
+}
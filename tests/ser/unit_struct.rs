@@ -0,0 +1,34 @@
+use serde::Serialize;
+use serde_hooks::{ser, Path};
+
+#[derive(Serialize)]
+struct Marker;
+
+#[derive(Serialize)]
+struct Payload {
+    marker: Marker,
+}
+
+#[test]
+fn test_rename() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_unit_struct(&self, path: &Path, us: &mut ser::UnitStructScope) {
+            assert_eq!(path, "marker");
+            assert_eq!(us.struct_name(), "Marker");
+            us.rename(format!("{}V2", us.struct_name()));
+        }
+    }
+
+    let config = ron::ser::PrettyConfig::default()
+        .struct_names(true)
+        .new_line(String::new())
+        .indentor(String::new());
+
+    let ron = ron::ser::to_string_pretty(&Payload { marker: Marker }, config.clone()).unwrap();
+    assert_eq!(ron, "Payload(marker: Marker,)");
+
+    let ron = ron::ser::to_string_pretty(&ser::hook(&Payload { marker: Marker }, &Hooks), config)
+        .unwrap();
+    assert_eq!(ron, "Payload(marker: MarkerV2,)");
+}
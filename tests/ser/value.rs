@@ -358,6 +358,36 @@ fn test_replace_in_seq() {
     );
 }
 
+#[test]
+fn test_replace_with() {
+    #[derive(Serialize)]
+    struct Payload {
+        a: u8,
+        b: i64,
+        c: Vec<i32>,
+        d: &'static str,
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, _path: &Path, value: &mut ser::ValueScope<S>) {
+            if value.value().as_i64().is_some() {
+                value.replace_with(|v| v.as_i64().map(|v| v * 2));
+            }
+        }
+    }
+
+    let payload = Payload {
+        a: 1,
+        b: -2,
+        c: vec![3, 4],
+        d: "5",
+    };
+
+    let json = serde_json::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(json, r#"{"a":2,"b":-4,"c":[6,8],"d":"5"}"#);
+}
+
 #[test]
 fn test_fail_serialization() {
     struct Hooks;
@@ -370,3 +400,359 @@ fn test_fail_serialization() {
     let err = serde_yaml::to_string(&ser::hook(&(), &Hooks)).unwrap_err();
     assert!(err.to_string().contains("FAUX ERROR"))
 }
+
+#[test]
+fn test_expand_into_elements() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, path: &Path, value: &mut ser::ValueScope<S>) {
+            if path == "[1]" {
+                value.expand_into_elements(vec![10i32.into(), 11i32.into(), 12i32.into()]);
+            }
+        }
+    }
+
+    let options = ser::HookOptions::new().allow_expanding_elements();
+    let json =
+        serde_json::to_string(&ser::hook_with_options(&vec![0i32, 1, 2], &Hooks, options)).unwrap();
+    assert_eq!(json, "[0,10,11,12,2]");
+
+    // expanding fails unless allowed
+    let err = serde_json::to_string(&ser::hook(&vec![0i32, 1, 2], &Hooks)).unwrap_err();
+    assert_eq!(err.to_string(), "expanding elements is not allowed");
+}
+
+#[test]
+fn test_expand_into_elements_len_hint() {
+    use ser::testing::SerializerEvent;
+    use serde_hooks::Value;
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, path: &Path, value: &mut ser::ValueScope<S>) {
+            if path == "[0]" {
+                value.expand_into_elements(vec![10i32.into(), 11i32.into()]);
+            }
+        }
+    }
+
+    let options = ser::HookOptions::new().allow_expanding_elements();
+
+    // no length hint for sequences
+    let events = ser::testing::record_events(&ser::hook_with_options(
+        &vec![0i32],
+        &Hooks,
+        options.clone(),
+    ))
+    .unwrap();
+    assert_eq!(events[0], SerializerEvent::Serialize(Value::Seq(None)));
+    assert_eq!(events.len(), 6);
+
+    // tuples are serialized as sequences
+    let events =
+        ser::testing::record_events(&ser::hook_with_options(&(0i32, 1i32), &Hooks, options))
+            .unwrap();
+    assert_eq!(events[0], SerializerEvent::Serialize(Value::Seq(None)));
+    assert_eq!(events.len(), 8);
+}
+
+#[test]
+fn test_expand_inserted_element() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_seq(&self, path: &Path, seq: &mut ser::SeqScope) {
+            if path.is_root() {
+                seq.insert_element(0, 5i32, ser::SeqInsertLocation::Before)
+                    .insert_element(0, 6i32, ser::SeqInsertLocation::After)
+                    .insert_element(0, 7i32, ser::SeqInsertLocation::End);
+            }
+        }
+
+        fn on_value<S: serde::Serializer>(&self, _path: &Path, value: &mut ser::ValueScope<S>) {
+            if let Some(v @ 5..=7) = value.value().as_i64() {
+                value.expand_into_elements(vec![v.into(), (v * 10).into()]);
+            }
+        }
+    }
+
+    let options = ser::HookOptions::new().allow_expanding_elements();
+    let json =
+        serde_json::to_string(&ser::hook_with_options(&vec![0i32, 1], &Hooks, options)).unwrap();
+    assert_eq!(json, "[5,50,0,6,60,1,7,70]");
+}
+
+#[test]
+fn test_expand_into_elements_outside_of_seq() {
+    #[derive(Serialize)]
+    struct Payload {
+        field: i32,
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, path: &Path, value: &mut ser::ValueScope<S>) {
+            if path == "field" {
+                value.expand_into_elements(vec![10i32.into(), 11i32.into()]);
+            }
+        }
+    }
+
+    let err = serde_json::to_string(&ser::hook(&Payload { field: 1 }, &Hooks)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "cannot expand a value that is not a sequence element"
+    );
+}
+
+#[test]
+fn test_replace_with_compound_value() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, _path: &Path, value: &mut ser::ValueScope<S>) {
+            value.replace(&serde_hooks::StaticValue::Struct {
+                name: "Struct",
+                len: 2,
+            });
+        }
+    }
+
+    let err = serde_json::to_string(&ser::hook(&(), &Hooks)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "value is not serializable: Struct{2 fields} cannot be represented fully in Value"
+    );
+}
+
+#[test]
+#[should_panic(expected = "value already replaced")]
+fn test_replace_twice_with_compound_value() {
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, _path: &Path, value: &mut ser::ValueScope<S>) {
+            value.replace(&1);
+            // the double replacement is reported before the value is checked
+            value.replace(&serde_hooks::StaticValue::Struct {
+                name: "Struct",
+                len: 2,
+            });
+        }
+    }
+
+    let _ = serde_json::to_string(&ser::hook(&(), &Hooks));
+}
+
+#[test]
+fn test_128_bit_values() {
+    struct Hooks {
+        values: RefCell<Vec<String>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_map_key<S: serde::Serializer>(&self, path: &Path, key: &mut ser::MapKeyScope<S>) {
+            self.values
+                .borrow_mut()
+                .push(format!("key {path}: {:?}", key.value()));
+        }
+
+        fn on_value<S: serde::Serializer>(&self, path: &Path, value: &mut ser::ValueScope<S>) {
+            self.values
+                .borrow_mut()
+                .push(format!("value {path}: {:?}", value.value()));
+        }
+    }
+
+    let payload: BTreeMap<i128, Vec<u128>> = [(i128::MIN, vec![u128::MAX])].into_iter().collect();
+    let hooks = Hooks {
+        values: RefCell::new(Vec::new()),
+    };
+
+    let json = serde_json::to_string(&ser::hook(&payload, &hooks)).unwrap();
+    assert_eq!(json, format!("{{\"{}\":[{}]}}", i128::MIN, u128::MAX));
+
+    assert_eq!(
+        hooks.values.into_inner(),
+        vec![
+            "value : Map(Some(1))".to_string(),
+            format!("key [{0}]: I128({0})", i128::MIN),
+            format!("value [{}]: Seq(Some(1))", i128::MIN),
+            format!("value [{}][0]: U128({})", i128::MIN, u128::MAX),
+        ]
+    );
+}
+
+#[test]
+fn test_map_number() {
+    #[derive(Serialize)]
+    struct Payload {
+        int: i32,
+        small: u8,
+        float: f64,
+        string: &'static str,
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, path: &Path, value: &mut ser::ValueScope<S>) {
+            if !path.is_root() {
+                value.map_number(|v| v * 2.5);
+            }
+        }
+    }
+
+    let payload = Payload {
+        int: 3,
+        small: 200,
+        float: 1.5,
+        string: "text",
+    };
+    let json = serde_json::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"int":7,"small":255,"float":3.75,"string":"text"}"#
+    );
+}
+
+#[test]
+fn test_map_integer() {
+    #[derive(Serialize)]
+    struct Payload {
+        int: i32,
+        small: i8,
+        big: u64,
+        float: f64,
+        string: &'static str,
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, path: &Path, value: &mut ser::ValueScope<S>) {
+            if !path.is_root() {
+                value.map_integer(|v| v - 100);
+            }
+            if let serde_hooks::Value::I32(v) = value.value() {
+                assert_eq!(*v, -97, "value is updated in the scope");
+            }
+        }
+    }
+
+    let payload = Payload {
+        int: 3,
+        small: -100,
+        big: u64::MAX,
+        float: 1.5,
+        string: "text",
+    };
+    let json = serde_json::to_string(&ser::hook(&payload, &Hooks)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"int":-97,"small":-128,"big":18446744073709551515,"float":1.5,"string":"text"}"#
+    );
+}
+
+#[test]
+fn test_skip() {
+    #[derive(Serialize)]
+    struct Payload {
+        seq: Vec<i32>,
+        map: BTreeMap<&'static str, i32>,
+        field: i32,
+        kept: i32,
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, _path: &Path, value: &mut ser::ValueScope<S>) {
+            if let serde_hooks::Value::I32(v) = value.value() {
+                if *v < 0 {
+                    value.skip();
+                }
+            }
+        }
+    }
+
+    let payload = Payload {
+        seq: vec![1, -2, 3, -4],
+        map: [("a", -1), ("b", 2)].into_iter().collect(),
+        field: -1,
+        kept: 1,
+    };
+    let options = ser::HookOptions::new().allow_skipping_values();
+    let json = serde_json::to_string(&ser::hook_with_options(&payload, &Hooks, options)).unwrap();
+    assert_eq!(json, r#"{"seq":[1,3],"map":{"b":2},"kept":1}"#);
+}
+
+#[test]
+fn test_skip_errors() {
+    struct Hooks {
+        errors: RefCell<Vec<String>>,
+    }
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, _path: &Path, value: &mut ser::ValueScope<S>) {
+            if let serde_hooks::Value::I32(_) = value.value() {
+                value.skip();
+            }
+        }
+
+        fn on_scope_error(&self, path: &Path, err: &mut ser::ErrorScope) {
+            assert!(matches!(err.error(), ser::HooksError::CannotSkipValue));
+            self.errors.borrow_mut().push(path.to_string());
+            err.ignore();
+        }
+    }
+
+    // The root value can't be skipped
+    let hooks = Hooks {
+        errors: RefCell::new(Vec::new()),
+    };
+    let options = ser::HookOptions::new().allow_skipping_values();
+    let json = serde_json::to_string(&ser::hook_with_options(&5, &hooks, options)).unwrap();
+    assert_eq!(json, "5");
+    assert_eq!(hooks.errors.take(), [""]);
+
+    // Skipping is not allowed by default
+    let json = serde_json::to_string(&ser::hook(&vec![1, 2], &hooks)).unwrap();
+    assert_eq!(json, "[1,2]");
+    assert_eq!(hooks.errors.take(), ["[0]", "[1]"]);
+
+    // Not ignoring the error fails serialization
+    struct FailingHooks;
+    impl ser::Hooks for FailingHooks {
+        fn on_value<S: serde::Serializer>(&self, _path: &Path, value: &mut ser::ValueScope<S>) {
+            value.skip();
+        }
+    }
+    let err = serde_json::to_string(&ser::hook(&5, &FailingHooks)).unwrap_err();
+    assert_eq!(err.to_string(), "Error at path '': value cannot be skipped");
+}
+
+#[test]
+fn test_skip_not_allowed() {
+    #[derive(Serialize)]
+    struct Payload {
+        field: i32,
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_value<S: serde::Serializer>(&self, path: &Path, value: &mut ser::ValueScope<S>) {
+            if path == "field" {
+                value.skip();
+            }
+        }
+    }
+
+    // Without allow_skipping_values, even a struct field can't be skipped from on_value.
+    let err = serde_json::to_string(&ser::hook(&Payload { field: 1 }, &Hooks)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error at path 'field': value cannot be skipped"
+    );
+
+    let options = ser::HookOptions::new().allow_skipping_values();
+    let json = serde_json::to_string(&ser::hook_with_options(
+        &Payload { field: 1 },
+        &Hooks,
+        options,
+    ))
+    .unwrap();
+    assert_eq!(json, "{}");
+}
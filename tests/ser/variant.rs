@@ -323,3 +323,147 @@ fn test_variant_rename() {
         "{\"unit_variant\":\"new_variant_name\",\"newtype_variant\":{\"NEW_newtype_variant\":null},\"struct_variant\":{\"STRUCT-VARIANT\":{\"struct_variant_val\":null}},\"tuple_variant\":{\"TupleVariant\":[null,null]}}"
     );
 }
+
+#[test]
+fn test_variant_rename_with() {
+    struct Hooks;
+
+    impl ser::Hooks for Hooks {
+        fn on_enum_variant(&self, path: &Path, ev: &mut ser::EnumVariantScope) {
+            if path == "tuple_variant" {
+                ev.rename_variant_with(|name| format!("variant_{name}_v2"));
+            }
+        }
+    }
+
+    let json = serde_json::to_string(&ser::hook(&Payload::new(), &Hooks)).unwrap();
+    assert_eq!(
+        json,
+        "{\"unit_variant\":\"UnitVariant\",\"newtype_variant\":{\"NewtypeVariant\":null},\"struct_variant\":{\"StructVariant\":{\"struct_variant_val\":null}},\"tuple_variant\":{\"variant_TupleVariant_v2\":[null,null]}}"
+    );
+}
+
+#[test]
+fn test_enum_rename_with() {
+    struct Hooks;
+
+    impl ser::Hooks for Hooks {
+        fn on_enum_variant(&self, path: &Path, ev: &mut ser::EnumVariantScope) {
+            if path == "struct_variant" || path == "tuple_variant" {
+                ev.rename_enum_with(|name| format!("{name}V2"));
+            }
+        }
+    }
+
+    use serde_reflection::{Samples, Tracer, TracerConfig};
+
+    let mut tracer = Tracer::new(TracerConfig::default());
+    let mut samples = Samples::new();
+    tracer
+        .trace_value(&mut samples, &ser::hook(&Payload::new(), &Hooks))
+        .unwrap();
+    let registry = tracer.registry().unwrap();
+
+    let actual = serde_yaml::to_string(&registry).unwrap();
+    let expected = indoc! {"
+        Enum: !ENUM
+          0:
+            UnitVariant: UNIT
+          1:
+            NewtypeVariant: !NEWTYPE UNIT
+        EnumV2: !ENUM
+          2:
+            StructVariant: !STRUCT
+            - struct_variant_val: UNIT
+          3:
+            TupleVariant: !TUPLE
+            - UNIT
+            - UNIT
+        Payload: !STRUCT
+        - unit_variant: !TYPENAME Enum
+        - newtype_variant: !TYPENAME Enum
+        - struct_variant: !TYPENAME EnumV2
+        - tuple_variant: !TYPENAME EnumV2
+    "};
+    assert_eq!(
+        actual, expected,
+        "\n\nExpected YAML:\n\n{expected}\n\nActual YAML:\n\n{actual}\n\n"
+    );
+}
+
+#[test]
+fn test_serialize_as_internally_tagged() {
+    #[derive(Serialize)]
+    struct Inner {
+        c: u8,
+    }
+
+    #[derive(Serialize)]
+    enum External {
+        Unit,
+        Struct { a: u8, b: &'static str },
+        Newtype(Inner),
+    }
+
+    #[derive(Serialize)]
+    #[serde(tag = "type")]
+    enum Internal {
+        Unit,
+        Struct { a: u8, b: &'static str },
+        Newtype(Inner),
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_enum_variant(&self, _path: &Path, ev: &mut ser::EnumVariantScope) {
+            ev.serialize_as_internally_tagged("type");
+        }
+    }
+
+    let external = [
+        External::Unit,
+        External::Struct { a: 1, b: "x" },
+        External::Newtype(Inner { c: 2 }),
+    ];
+    let internal = [
+        Internal::Unit,
+        Internal::Struct { a: 1, b: "x" },
+        Internal::Newtype(Inner { c: 2 }),
+    ];
+
+    assert_eq!(
+        serde_json::to_string(&external).unwrap(),
+        r#"["Unit",{"Struct":{"a":1,"b":"x"}},{"Newtype":{"c":2}}]"#
+    );
+
+    let json = serde_json::to_string(&ser::hook(&external, &Hooks)).unwrap();
+    assert_eq!(json, serde_json::to_string(&internal).unwrap());
+    assert_eq!(
+        json,
+        r#"[{"type":"Unit"},{"type":"Struct","a":1,"b":"x"},{"type":"Newtype","c":2}]"#
+    );
+
+    let ron = ron::to_string(&ser::hook(&External::Struct { a: 1, b: "x" }, &Hooks)).unwrap();
+    assert_eq!(ron, r#"(type:"Struct",a:1,b:"x")"#);
+}
+
+#[test]
+fn test_serialize_as_internally_tagged_unsupported() {
+    #[derive(Serialize)]
+    enum E {
+        Newtype(u8),
+    }
+
+    struct Hooks;
+    impl ser::Hooks for Hooks {
+        fn on_enum_variant(&self, _path: &Path, ev: &mut ser::EnumVariantScope) {
+            ev.serialize_as_internally_tagged("type");
+        }
+    }
+
+    let err = serde_json::to_string(&ser::hook(&E::Newtype(1), &Hooks)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error at path '': cannot flatten unsupported data type \"u8\""
+    );
+}